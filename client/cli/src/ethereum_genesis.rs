@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Geth-style genesis files, as written by `geth dumpgenesis` or used by `geth init`.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use ethereum_types::{H160, H256, U256};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+// Frontier
+use fp_evm::GenesisAccount;
+
+/// The EVM accounts and chain id of a geth-style genesis file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EthereumGenesis {
+	/// `config.chainId`, if set.
+	pub chain_id: Option<u64>,
+	/// The `alloc` accounts.
+	pub alloc: BTreeMap<H160, GenesisAccount>,
+}
+
+#[derive(Deserialize)]
+struct RawGenesis {
+	#[serde(default)]
+	config: RawConfig,
+	#[serde(default)]
+	alloc: BTreeMap<String, RawAccount>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawConfig {
+	chain_id: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RawAccount {
+	balance: Option<Quantity>,
+	nonce: Option<Quantity>,
+	code: Option<String>,
+	#[serde(default)]
+	storage: BTreeMap<String, String>,
+}
+
+/// Geth accepts quantities as JSON numbers, decimal strings or hex strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Quantity {
+	Number(u64),
+	String(String),
+}
+
+impl Quantity {
+	fn parse(&self) -> Result<U256, String> {
+		match self {
+			Quantity::Number(number) => Ok(U256::from(*number)),
+			Quantity::String(string) => {
+				let parsed = match string.strip_prefix("0x") {
+					Some(hex) => U256::from_str_radix(hex, 16).ok(),
+					None => U256::from_dec_str(string).ok(),
+				};
+				parsed.ok_or_else(|| format!("Invalid quantity `{}`", string))
+			}
+		}
+	}
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+	let value = value.strip_prefix("0x").unwrap_or(value);
+	if value.len() % 2 == 1 {
+		hex::decode(format!("0{}", value))
+	} else {
+		hex::decode(value)
+	}
+	.map_err(|err| format!("Invalid hex `{}`: {}", value, err))
+}
+
+fn parse_address(value: &str) -> Result<H160, String> {
+	let bytes = decode_hex(value)?;
+	if bytes.len() != 20 {
+		return Err(format!("Invalid address `{}`", value));
+	}
+	Ok(H160::from_slice(&bytes))
+}
+
+/// Storage keys and values may omit their leading zeros.
+fn parse_word(value: &str) -> Result<H256, String> {
+	let bytes = decode_hex(value)?;
+	if bytes.len() > 32 {
+		return Err(format!("Invalid storage word `{}`", value));
+	}
+	let mut word = H256::default();
+	word.as_bytes_mut()[32 - bytes.len()..].copy_from_slice(&bytes);
+	Ok(word)
+}
+
+impl EthereumGenesis {
+	/// Reads a `genesis.json` file.
+	pub fn from_json_file(path: &Path) -> Result<Self, String> {
+		let bytes = fs::read(path)
+			.map_err(|err| format!("Failed to read `{}`: {}", path.display(), err))?;
+		Self::from_json_bytes(&bytes)
+	}
+
+	/// Parses the content of a `genesis.json` file.
+	pub fn from_json_bytes(bytes: &[u8]) -> Result<Self, String> {
+		let raw: RawGenesis = serde_json::from_slice(bytes)
+			.map_err(|err| format!("Invalid genesis file: {}", err))?;
+
+		let mut alloc = BTreeMap::new();
+		for (address, account) in raw.alloc {
+			let address = parse_address(&address)?;
+			let mut storage = BTreeMap::new();
+			for (key, value) in account.storage {
+				storage.insert(parse_word(&key)?, parse_word(&value)?);
+			}
+			let account = GenesisAccount {
+				nonce: account
+					.nonce
+					.map(|n| n.parse())
+					.transpose()?
+					.unwrap_or_default(),
+				balance: account
+					.balance
+					.map(|b| b.parse())
+					.transpose()?
+					.unwrap_or_default(),
+				storage,
+				code: account
+					.code
+					.map(|code| decode_hex(&code))
+					.transpose()?
+					.unwrap_or_default(),
+			};
+			alloc.insert(address, account);
+		}
+
+		Ok(Self {
+			chain_id: raw.config.chain_id,
+			alloc,
+		})
+	}
+
+	/// Builds the `genesis.json` representation, leaving out empty fields.
+	pub fn to_json(&self) -> Value {
+		let alloc = self
+			.alloc
+			.iter()
+			.map(|(address, account)| {
+				let mut entry = Map::new();
+				entry.insert("balance".into(), format!("0x{:x}", account.balance).into());
+				if !account.nonce.is_zero() {
+					entry.insert("nonce".into(), format!("0x{:x}", account.nonce).into());
+				}
+				if !account.code.is_empty() {
+					entry.insert(
+						"code".into(),
+						format!("0x{}", hex::encode(&account.code)).into(),
+					);
+				}
+				if !account.storage.is_empty() {
+					let storage = account
+						.storage
+						.iter()
+						.map(|(key, value)| (format!("{:?}", key), format!("{:?}", value).into()))
+						.collect::<Map<_, _>>();
+					entry.insert("storage".into(), storage.into());
+				}
+				(format!("{:?}", address), Value::Object(entry))
+			})
+			.collect::<Map<_, _>>();
+
+		let mut config = Map::new();
+		if let Some(chain_id) = self.chain_id {
+			config.insert("chainId".into(), chain_id.into());
+		}
+		json!({ "config": config, "alloc": alloc })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_geth_genesis() {
+		let genesis = EthereumGenesis::from_json_bytes(
+			br#"{
+				"config": { "chainId": 1337, "homesteadBlock": 0 },
+				"difficulty": "0x1",
+				"gasLimit": "0x1c9c380",
+				"alloc": {
+					"6be02d1d3665660d22ff9624b7be0551ee1ac91b": { "balance": "1000" },
+					"0x1000000000000000000000000000000000000001": {
+						"balance": "0x10",
+						"nonce": "0x2",
+						"code": "0x6000",
+						"storage": { "0x01": "0x2a" }
+					}
+				}
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(genesis.chain_id, Some(1337));
+		let eoa =
+			&genesis.alloc[&parse_address("6be02d1d3665660d22ff9624b7be0551ee1ac91b").unwrap()];
+		assert_eq!(eoa.balance, U256::from(1000));
+		assert!(eoa.code.is_empty());
+
+		let contract =
+			&genesis.alloc[&parse_address("0x1000000000000000000000000000000000000001").unwrap()];
+		assert_eq!(contract.balance, U256::from(16));
+		assert_eq!(contract.nonce, U256::from(2));
+		assert_eq!(contract.code, vec![0x60, 0x00]);
+		assert_eq!(
+			contract.storage.get(&H256::from_low_u64_be(1)),
+			Some(&H256::from_low_u64_be(42))
+		);
+	}
+
+	#[test]
+	fn json_round_trip() {
+		let mut genesis = EthereumGenesis {
+			chain_id: Some(42),
+			alloc: BTreeMap::new(),
+		};
+		genesis.alloc.insert(
+			H160::repeat_byte(0x11),
+			GenesisAccount {
+				nonce: U256::from(1),
+				balance: U256::from(1_000_000_000_000_000_000u128),
+				storage: [(H256::from_low_u64_be(1), H256::repeat_byte(0xff))].into(),
+				code: vec![0x00],
+			},
+		);
+
+		let json = serde_json::to_vec(&genesis.to_json()).unwrap();
+		assert_eq!(EthereumGenesis::from_json_bytes(&json).unwrap(), genesis);
+	}
+
+	#[test]
+	fn rejects_invalid_address() {
+		assert!(EthereumGenesis::from_json_bytes(
+			br#"{ "alloc": { "0x12": { "balance": "1" } } }"#
+		)
+		.is_err());
+	}
+}
@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+	str::FromStr,
+	sync::Arc,
+};
+
+use ethereum_types::{H160, H256};
+use scale_codec::Decode;
+// Substrate
+use sc_cli::{BlockNumberOrHash, PruningParams, SharedParams};
+use sc_client_api::{backend::Backend, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::{blake2_128, twox_128},
+	storage::StorageKey,
+};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+// Frontier
+use fp_evm::GenesisAccount;
+use fp_rpc::EthereumRuntimeRPCApi;
+use fp_storage::{EVM_ACCOUNT_CODES, EVM_ACCOUNT_STORAGES, PALLET_EVM};
+
+use crate::EthereumGenesis;
+
+/// Export the `pallet_evm` state at a given block as a geth-style `genesis.json`.
+///
+/// Contracts and their storage are always exported. Balances and nonces are exported for the
+/// contracts and for every account of the `--accounts-pallet`/`--accounts-storage` map whose id
+/// is a 20 bytes address.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportEvmStateCmd {
+	/// Block hash or number to export the state at, defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Pallet of the map listing the accounts of the chain.
+	#[arg(long, default_value = "System")]
+	pub accounts_pallet: String,
+
+	/// Storage item of the map listing the accounts of the chain, keyed by `Blake2_128Concat`
+	/// hashed account ids.
+	#[arg(long, default_value = "Account")]
+	pub accounts_storage: String,
+
+	/// Output file, stdout when not set.
+	#[arg(long, short('o'))]
+	pub output: Option<PathBuf>,
+
+	/// Shared parameters
+	#[command(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl ExportEvmStateCmd {
+	pub fn run<B, C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		B: BlockT,
+		C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + HeaderBackend<B>,
+		C::Api: EthereumRuntimeRPCApi<B>,
+		BE: Backend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: std::fmt::Debug,
+		NumberFor<B>: FromStr,
+		<NumberFor<B> as FromStr>::Err: std::fmt::Debug,
+	{
+		let hash = match &self.at {
+			Some(at) => client
+				.block_hash_from_id(&at.parse::<B>()?)?
+				.ok_or_else(|| format!("Unknown block `{:?}`", at))?,
+			None => client.info().best_hash,
+		};
+
+		let genesis = export_evm_state(
+			client.as_ref(),
+			hash,
+			(&self.accounts_pallet, &self.accounts_storage),
+		)?;
+		let json = serde_json::to_string_pretty(&genesis.to_json())
+			.map_err(|err| format!("Failed to serialize the genesis: {}", err))?;
+		match &self.output {
+			Some(path) => fs::write(path, json)?,
+			None => writeln!(io::stdout(), "{}", json)?,
+		}
+		Ok(())
+	}
+}
+
+/// Collects the EVM accounts of the state at `hash`.
+///
+/// `accounts` names the `(pallet, storage)` map listing the accounts of the chain, usually
+/// `("System", "Account")`.
+pub fn export_evm_state<B, C, BE>(
+	client: &C,
+	hash: B::Hash,
+	accounts: (&str, &str),
+) -> sc_cli::Result<EthereumGenesis>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	BE: Backend<B>,
+{
+	let EvmStorage {
+		addresses,
+		mut codes,
+		mut storages,
+	} = read_evm_storage(client, hash, accounts)?;
+
+	let api = client.runtime_api();
+	let chain_id = api
+		.chain_id(hash)
+		.map_err(|err| format!("Failed to get the chain id: {}", err))?;
+
+	let mut alloc = BTreeMap::new();
+	for address in addresses {
+		let basic = api
+			.account_basic(hash, address)
+			.map_err(|err| format!("Failed to get the account of {:?}: {}", address, err))?;
+		let account = GenesisAccount {
+			nonce: basic.nonce,
+			balance: basic.balance,
+			storage: storages.remove(&address).unwrap_or_default(),
+			code: codes.remove(&address).unwrap_or_default(),
+		};
+		if account.nonce.is_zero()
+			&& account.balance.is_zero()
+			&& account.code.is_empty()
+			&& account.storage.is_empty()
+		{
+			continue;
+		}
+		alloc.insert(address, account);
+	}
+
+	Ok(EthereumGenesis {
+		chain_id: Some(chain_id),
+		alloc,
+	})
+}
+
+/// The EVM accounts found in the storage, along with their code and storage.
+struct EvmStorage {
+	addresses: BTreeSet<H160>,
+	codes: BTreeMap<H160, Vec<u8>>,
+	storages: BTreeMap<H160, BTreeMap<H256, H256>>,
+}
+
+fn read_evm_storage<B, C, BE>(
+	client: &C,
+	hash: B::Hash,
+	accounts: (&str, &str),
+) -> sc_cli::Result<EvmStorage>
+where
+	B: BlockT,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+{
+	let mut addresses = BTreeSet::new();
+	let mut codes = BTreeMap::new();
+	let mut storages: BTreeMap<H160, BTreeMap<H256, H256>> = BTreeMap::new();
+
+	// `AccountCodes`: prefix ++ blake2_128(address) ++ address
+	let prefix = storage_prefix(PALLET_EVM, EVM_ACCOUNT_CODES);
+	for (key, value) in client.storage_pairs(hash, Some(&prefix), None)? {
+		let address = H160::from_slice(&key.0[prefix.0.len() + 16..]);
+		let code = Vec::<u8>::decode(&mut &value.0[..])
+			.map_err(|err| format!("Invalid code of {:?}: {}", address, err))?;
+		addresses.insert(address);
+		codes.insert(address, code);
+	}
+
+	// `AccountStorages`: prefix ++ blake2_128(address) ++ address ++ blake2_128(slot) ++ slot
+	let prefix = storage_prefix(PALLET_EVM, EVM_ACCOUNT_STORAGES);
+	for (key, value) in client.storage_pairs(hash, Some(&prefix), None)? {
+		let key = &key.0[prefix.0.len()..];
+		let address = H160::from_slice(&key[16..36]);
+		let slot = H256::from_slice(&key[52..84]);
+		addresses.insert(address);
+		storages
+			.entry(address)
+			.or_default()
+			.insert(slot, H256::from_slice(&value.0));
+	}
+
+	// Accounts map, e.g. `System::Account`: prefix ++ blake2_128(account id) ++ account id
+	let prefix = storage_prefix(accounts.0.as_bytes(), accounts.1.as_bytes());
+	for key in client.storage_keys(hash, Some(&prefix), None)? {
+		let account_id = &key.0[prefix.0.len() + 16..];
+		if account_id.len() == 20 && blake2_128(account_id) == key.0[prefix.0.len()..][..16] {
+			addresses.insert(H160::from_slice(account_id));
+		}
+	}
+
+	Ok(EvmStorage {
+		addresses,
+		codes,
+		storages,
+	})
+}
+
+fn storage_prefix(pallet: &[u8], storage: &[u8]) -> StorageKey {
+	StorageKey([twox_128(pallet), twox_128(storage)].concat())
+}
+
+impl sc_cli::CliConfiguration for ExportEvmStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::executor;
+	use scale_codec::Encode;
+	// Substrate
+	use sc_block_builder::BlockBuilderProvider;
+	use sp_consensus::BlockOrigin;
+	use substrate_test_runtime_client::{
+		BlockBuilderExt, ClientBlockImportExt, DefaultTestClientBuilderExt, TestClientBuilder,
+	};
+
+	use super::*;
+
+	fn map_key(prefix: &StorageKey, key: &[u8]) -> Vec<u8> {
+		[&prefix.0[..], &blake2_128(key), key].concat()
+	}
+
+	#[test]
+	fn reads_the_evm_accounts_from_the_storage() {
+		let mut client = TestClientBuilder::new().build();
+
+		let contract = H160::repeat_byte(0x11);
+		let eoa = H160::repeat_byte(0x22);
+		let slot = H256::from_low_u64_be(1);
+		let codes = storage_prefix(PALLET_EVM, EVM_ACCOUNT_CODES);
+		let storages = storage_prefix(PALLET_EVM, EVM_ACCOUNT_STORAGES);
+		let accounts = storage_prefix(b"Accounts", b"Map");
+
+		let mut builder = client.new_block(Default::default()).unwrap();
+		for (key, value) in [
+			(
+				map_key(&codes, contract.as_bytes()),
+				vec![0x60u8, 0x00].encode(),
+			),
+			(
+				[
+					map_key(&storages, contract.as_bytes()),
+					map_key(&StorageKey(vec![]), slot.as_bytes()),
+				]
+				.concat(),
+				H256::repeat_byte(0xff).as_bytes().to_vec(),
+			),
+			(map_key(&accounts, eoa.as_bytes()), vec![]),
+			// Not an EVM address.
+			(map_key(&accounts, &[0x33; 32]), vec![]),
+			// Not the configured accounts map.
+			(
+				map_key(&storage_prefix(b"System", b"Account"), &[0x44; 20]),
+				vec![],
+			),
+		] {
+			builder.push_storage_change(key, Some(value)).unwrap();
+		}
+		let block = builder.build().unwrap().block;
+		let hash = block.hash();
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+		let storage = read_evm_storage(&client, hash, ("Accounts", "Map")).unwrap();
+		assert_eq!(storage.addresses, [contract, eoa].into());
+		assert_eq!(storage.codes, [(contract, vec![0x60, 0x00])].into());
+		assert_eq!(
+			storage.storages,
+			[(contract, [(slot, H256::repeat_byte(0xff))].into())].into()
+		);
+	}
+}
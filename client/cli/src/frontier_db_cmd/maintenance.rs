@@ -0,0 +1,382 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::H256;
+// Substrate
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+// Frontier
+use fc_storage::OverrideHandle;
+use fp_consensus::{FindLogError, Hashes, Log, PostLog, PreLog};
+use fp_rpc::EthereumRuntimeRPCApi;
+
+/// Maintenance operation on the mappings of a range of canonical blocks.
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum Maintenance {
+	/// Re-run the mapping sync for the canonical blocks of the range.
+	Reindex(BlockRange),
+	/// Check the mappings of the canonical blocks of the range against their Ethereum digest.
+	Verify(BlockRange),
+	/// Re-run the mapping sync for the canonical blocks of the range failing the verification.
+	Repair(BlockRange),
+}
+
+/// Range of canonical blocks.
+#[derive(Debug, Clone, clap::Args)]
+pub struct BlockRange {
+	/// First block of the range.
+	#[arg(long, default_value_t = 0)]
+	pub from: u32,
+
+	/// Last block of the range, the best block by default.
+	#[arg(long)]
+	pub to: Option<u32>,
+}
+
+/// A canonical block whose mappings do not match its Ethereum digest.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Inconsistency<H> {
+	pub number: u32,
+	pub hash: H,
+	pub reason: String,
+}
+
+impl<H: std::fmt::Debug> std::fmt::Display for Inconsistency<H> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "#{} ({:?}): {}", self.number, self.hash, self.reason)
+	}
+}
+
+impl Maintenance {
+	/// Runs the operation against the key-value or the SQL backend.
+	///
+	/// The SQL backend must be driven from within a tokio runtime context.
+	pub fn run<B, C, BE>(
+		&self,
+		client: Arc<C>,
+		backend: fc_db::Backend<B>,
+		overrides: Arc<OverrideHandle<B>>,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT<Hash = H256>,
+		C: ProvideRuntimeApi<B>,
+		C::Api: EthereumRuntimeRPCApi<B>,
+		C: HeaderBackend<B> + StorageProvider<B, BE> + Send + Sync + 'static,
+		BE: Backend<B> + 'static,
+		BE::State: StateBackend<BlakeTwo256>,
+	{
+		let maintenance_db = MaintenanceDb::new(client, backend, overrides);
+		match self {
+			Maintenance::Reindex(range) => {
+				let mut reindexed = 0;
+				for number in maintenance_db.numbers(range) {
+					let Some(header) = maintenance_db.canonical_header(number)? else {
+						break;
+					};
+					maintenance_db.reindex(number, &header)?;
+					reindexed += 1;
+				}
+				println!("Reindexed {} blocks", reindexed);
+			}
+			Maintenance::Verify(range) => {
+				let inconsistencies = maintenance_db.verify(range)?;
+				for inconsistency in inconsistencies.iter() {
+					println!("{}", inconsistency);
+				}
+				if !inconsistencies.is_empty() {
+					return Err(format!("{} inconsistent blocks", inconsistencies.len()).into());
+				}
+			}
+			Maintenance::Repair(range) => {
+				let inconsistencies = maintenance_db.verify(range)?;
+				let mut remaining = Vec::new();
+				for inconsistency in inconsistencies.iter() {
+					let Some(header) = maintenance_db.canonical_header(inconsistency.number)? else {
+						continue;
+					};
+					match maintenance_db.reindex(inconsistency.number, &header) {
+						Ok(()) => remaining
+							.extend(maintenance_db.verify_block(inconsistency.number, &header)?),
+						Err(reason) => remaining.push(Inconsistency {
+							reason,
+							..inconsistency.clone()
+						}),
+					}
+				}
+				for inconsistency in remaining.iter() {
+					println!("{}", inconsistency);
+				}
+				println!(
+					"Repaired {} of {} inconsistent blocks",
+					inconsistencies.len() - remaining.len(),
+					inconsistencies.len()
+				);
+				if !remaining.is_empty() {
+					return Err(format!("{} blocks could not be repaired", remaining.len()).into());
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+pub struct MaintenanceDb<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: fc_db::Backend<B>,
+	overrides: Arc<OverrideHandle<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B, C, BE> MaintenanceDb<B, C, BE>
+where
+	B: BlockT<Hash = H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	pub fn new(
+		client: Arc<C>,
+		backend: fc_db::Backend<B>,
+		overrides: Arc<OverrideHandle<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			overrides,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Checks the canonical blocks of the range, returning the inconsistent ones.
+	pub fn verify(&self, range: &BlockRange) -> Result<Vec<Inconsistency<B::Hash>>, String> {
+		let mut inconsistencies = Vec::new();
+		for number in self.numbers(range) {
+			let Some(header) = self.canonical_header(number)? else {
+				break;
+			};
+			inconsistencies.extend(self.verify_block(number, &header)?);
+		}
+		Ok(inconsistencies)
+	}
+
+	/// Checks the mappings of the canonical block against its Ethereum digest.
+	pub fn verify_block(
+		&self,
+		number: u32,
+		header: &B::Header,
+	) -> Result<Option<Inconsistency<B::Hash>>, String> {
+		let hash = header.hash();
+		let reason = match self.expected_hashes(number, header) {
+			Ok(expected) => match &self.backend {
+				fc_db::Backend::KeyValue(backend) => {
+					Self::verify_key_value(backend, hash, expected.as_ref())?
+				}
+				#[cfg(feature = "sql")]
+				fc_db::Backend::Sql(backend) => futures::executor::block_on(Self::verify_sql(
+					backend,
+					number,
+					hash,
+					expected.as_ref(),
+				))?,
+			},
+			Err(err) => Some(err),
+		};
+		Ok(reason.map(|reason| Inconsistency {
+			number,
+			hash,
+			reason,
+		}))
+	}
+
+	/// Re-runs the mapping sync for the canonical block.
+	pub fn reindex(&self, number: u32, header: &B::Header) -> Result<(), String> {
+		match &self.backend {
+			fc_db::Backend::KeyValue(backend) => {
+				if number == 0 {
+					fc_mapping_sync::kv::sync_genesis_block(self.client.as_ref(), backend, header)
+				} else {
+					fc_mapping_sync::kv::sync_block(
+						self.client.as_ref(),
+						self.overrides.clone(),
+						backend,
+						header,
+					)
+				}
+			}
+			#[cfg(feature = "sql")]
+			fc_db::Backend::Sql(backend) => futures::executor::block_on(async {
+				let hash = header.hash();
+				backend
+					.remove_block(hash)
+					.await
+					.map_err(|e| format!("{:?}", e))?;
+				if number == 0 {
+					backend
+						.insert_genesis_block_metadata(self.client.clone())
+						.await
+						.map(|_| ())
+						.map_err(|e| format!("{:?}", e))
+				} else if fp_consensus::find_log(header.digest()).is_ok() {
+					fc_mapping_sync::sql::index_block(self.client.clone(), backend, hash).await
+				} else {
+					// Blocks without Ethereum digest are not indexed.
+					Ok(())
+				}
+			}),
+		}
+	}
+
+	/// Numbers of the blocks of the range, up to the best block by default.
+	pub fn numbers(&self, range: &BlockRange) -> std::ops::RangeInclusive<u32> {
+		let to = range.to.unwrap_or_else(|| {
+			UniqueSaturatedInto::<u32>::unique_saturated_into(self.client.info().best_number)
+		});
+		range.from..=to
+	}
+
+	/// Header of the canonical block, `None` past the best block.
+	pub fn canonical_header(&self, number: u32) -> Result<Option<B::Header>, String> {
+		let Some(hash) = self
+			.client
+			.hash(number.into())
+			.map_err(|e| format!("{:?}", e))?
+		else {
+			return Ok(None);
+		};
+		self.client.header(hash).map_err(|e| format!("{:?}", e))
+	}
+
+	/// Ethereum hashes committed by the block, `None` for blocks without Ethereum digest.
+	fn expected_hashes(&self, number: u32, header: &B::Header) -> Result<Option<Hashes>, String> {
+		let hash = header.hash();
+		let current_block = || {
+			let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), hash);
+			self.overrides
+				.schemas
+				.get(&schema)
+				.unwrap_or(&self.overrides.fallback)
+				.current_block(hash)
+		};
+
+		if number == 0 {
+			// The genesis block has no digest, its Ethereum block is read from the state.
+			return Ok(current_block().map(Hashes::from_block));
+		}
+		match fp_consensus::find_log(header.digest()) {
+			Ok(Log::Pre(PreLog::Block(block))) | Ok(Log::Post(PostLog::Block(block))) => {
+				Ok(Some(Hashes::from_block(block)))
+			}
+			Ok(Log::Post(PostLog::Hashes(hashes))) => Ok(Some(hashes)),
+			Ok(Log::Post(PostLog::BlockHash(expect_eth_block_hash))) => match current_block() {
+				Some(block) if block.header.hash() == expect_eth_block_hash => {
+					Ok(Some(Hashes::from_block(block)))
+				}
+				Some(block) => Err(format!(
+					"Ethereum block hash mismatch: frontier consensus digest \
+					({expect_eth_block_hash:?}), db state ({:?})",
+					block.header.hash()
+				)),
+				None => Err(format!(
+					"Missing Ethereum block {expect_eth_block_hash:?}, the state may be pruned"
+				)),
+			},
+			Err(FindLogError::NotFound) => Ok(None),
+			Err(FindLogError::MultipleLogs) => Err("Multiple logs found".to_string()),
+		}
+	}
+
+	fn verify_key_value(
+		backend: &fc_db::kv::Backend<B>,
+		hash: B::Hash,
+		expected: Option<&Hashes>,
+	) -> Result<Option<String>, String> {
+		let mapping = backend.mapping();
+		if !mapping.is_synced(&hash)? {
+			return Ok(Some("Not synced".to_string()));
+		}
+		let Some(expected) = expected else {
+			return Ok(None);
+		};
+
+		if !mapping
+			.block_hash(&expected.block_hash)?
+			.unwrap_or_default()
+			.contains(&hash)
+		{
+			return Ok(Some(format!(
+				"Missing mapping of Ethereum block {:?}",
+				expected.block_hash
+			)));
+		}
+		for (index, transaction_hash) in expected.transaction_hashes.iter().enumerate() {
+			let mapped = mapping
+				.transaction_metadata(transaction_hash)?
+				.iter()
+				.any(|metadata| {
+					metadata.block_hash == hash
+						&& metadata.ethereum_block_hash == expected.block_hash
+						&& metadata.ethereum_index == index as u32
+				});
+			if !mapped {
+				return Ok(Some(format!(
+					"Missing mapping of Ethereum transaction {:?}",
+					transaction_hash
+				)));
+			}
+		}
+		Ok(None)
+	}
+
+	#[cfg(feature = "sql")]
+	async fn verify_sql(
+		backend: &fc_db::sql::Backend<B>,
+		number: u32,
+		hash: B::Hash,
+		expected: Option<&Hashes>,
+	) -> Result<Option<String>, String> {
+		let indexed = backend
+			.indexed_hashes(hash)
+			.await
+			.map_err(|e| format!("{:?}", e))?;
+		Ok(match (expected, indexed) {
+			(None, None) => None,
+			(None, Some(_)) => Some("Indexed without Ethereum digest".to_string()),
+			(Some(_), None) => Some("Not indexed".to_string()),
+			(Some(expected), Some(indexed)) if *expected != indexed => Some(format!(
+				"Indexed Ethereum block {:?} with {} transactions, expected {:?} with {}",
+				indexed.block_hash,
+				indexed.transaction_hashes.len(),
+				expected.block_hash,
+				expected.transaction_hashes.len(),
+			)),
+			// The genesis block is indexed without sync status.
+			(Some(_), Some(_))
+				if number > 0 && !backend.block_indexed_and_canon_status(hash).await.canon =>
+			{
+				Some("Not marked as canon".to_string())
+			}
+			(Some(_), Some(_)) => None,
+		})
+	}
+}
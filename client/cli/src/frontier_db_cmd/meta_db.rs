@@ -27,7 +27,7 @@ use serde::Deserialize;
 // Substrate
 use sp_runtime::traits::Block as BlockT;
 
-use super::{utils::FrontierDbMessage, FrontierDbCmd, Operation};
+use super::{utils::FrontierDbMessage, Operation};
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
@@ -58,17 +58,17 @@ impl FromStr for MetaKey {
 }
 
 pub struct MetaDb<'a, B: BlockT> {
-	cmd: &'a FrontierDbCmd,
+	operation: &'a Operation,
 	backend: Arc<fc_db::kv::Backend<B>>,
 }
 
 impl<'a, B: BlockT> MetaDb<'a, B> {
-	pub fn new(cmd: &'a FrontierDbCmd, backend: Arc<fc_db::kv::Backend<B>>) -> Self {
-		Self { cmd, backend }
+	pub fn new(operation: &'a Operation, backend: Arc<fc_db::kv::Backend<B>>) -> Self {
+		Self { operation, backend }
 	}
 
 	pub fn query(&self, key: &MetaKey, value: &Option<MetaValue<B::Hash>>) -> sc_cli::Result<()> {
-		match self.cmd.operation {
+		match self.operation {
 			Operation::Create => match (key, value) {
 				// Insert data to the meta column, static tips key.
 				(MetaKey::Tips, Some(MetaValue::Tips(hashes))) => {
@@ -110,7 +110,7 @@ impl<'a, B: BlockT> MetaDb<'a, B> {
 				// Update the static tips key's value.
 				(MetaKey::Tips, Some(MetaValue::Tips(new_value))) => {
 					let value = self.backend.meta().current_syncing_tips()?;
-					self.confirmation_prompt(&self.cmd.operation, key, &value, new_value)?;
+					self.confirmation_prompt(self.operation, key, &value, new_value)?;
 					self.backend
 						.meta()
 						.write_current_syncing_tips(new_value.clone())?;
@@ -123,7 +123,7 @@ impl<'a, B: BlockT> MetaDb<'a, B> {
 						.map(|(key, value)| (*value, *key))
 						.collect::<Vec<(fp_storage::EthereumStorageSchema, H256)>>();
 					self.confirmation_prompt(
-						&self.cmd.operation,
+						self.operation,
 						key,
 						&value,
 						&Some(new_value.clone()),
@@ -136,13 +136,13 @@ impl<'a, B: BlockT> MetaDb<'a, B> {
 				// Deletes the static tips key's value.
 				MetaKey::Tips => {
 					let value = self.backend.meta().current_syncing_tips()?;
-					self.confirmation_prompt(&self.cmd.operation, key, &value, &vec![])?;
+					self.confirmation_prompt(self.operation, key, &value, &vec![])?;
 					self.backend.meta().write_current_syncing_tips(vec![])?;
 				}
 				// Deletes the static schema cache key's value.
 				MetaKey::Schema => {
 					let value = self.backend.meta().ethereum_schema()?;
-					self.confirmation_prompt(&self.cmd.operation, key, &value, &Some(vec![]))?;
+					self.confirmation_prompt(self.operation, key, &value, &Some(vec![]))?;
 					self.backend.meta().write_ethereum_schema(vec![])?;
 				}
 			},
@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod maintenance;
 mod mapping_db;
 mod meta_db;
 #[cfg(test)]
@@ -33,6 +34,7 @@ use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::Block as BlockT;
 
+pub use self::maintenance::{BlockRange, Inconsistency, Maintenance, MaintenanceDb};
 use self::{
 	mapping_db::{MappingDb, MappingKey, MappingValue},
 	meta_db::{MetaDb, MetaKey, MetaValue},
@@ -40,22 +42,27 @@ use self::{
 
 /// Cli tool to interact with the Frontier backend db
 #[derive(Debug, Clone, clap::Parser)]
+#[command(subcommand_negates_reqs = true)]
 pub struct FrontierDbCmd {
+	/// Maintenance operation on the canonical blocks, instead of a query.
+	#[command(subcommand)]
+	pub maintenance: Option<Maintenance>,
+
 	/// Specify the operation to perform.
 	///
 	/// Can be one of `create | read | update | delete`.
 	#[arg(value_enum, ignore_case = true, required = true)]
-	pub operation: Operation,
+	pub operation: Option<Operation>,
 
 	/// Specify the column to query.
 	///
 	/// Can be one of `meta | block | transaction`.
 	#[arg(value_enum, ignore_case = true, required = true)]
-	pub column: Column,
+	pub column: Option<Column>,
 
 	/// Specify the key to either read or write.
 	#[arg(short('k'), long, required = true)]
-	pub key: String,
+	pub key: Option<String>,
 
 	/// Specify the value to write.
 	///
@@ -108,42 +115,42 @@ impl FrontierDbCmd {
 		C::Api: fp_rpc::EthereumRuntimeRPCApi<B>,
 		C: HeaderBackend<B>,
 	{
-		match self.column {
+		let (Some(operation), Some(column), Some(key)) = (&self.operation, &self.column, &self.key)
+		else {
+			return Err("An operation, a column and a key are required".into());
+		};
+		match column {
 			Column::Meta => {
 				// New meta db handler
-				let meta_db = MetaDb::new(self, backend);
+				let meta_db = MetaDb::new(operation, backend);
 				// Maybe get a MetaKey
-				let key = MetaKey::from_str(&self.key)?;
+				let key = MetaKey::from_str(key)?;
 				// Maybe get a MetaValue
-				let value = match utils::maybe_deserialize_value::<B>(
-					&self.operation,
-					self.value.as_ref(),
-				)? {
-					Some(DbValue::Meta(value)) => Some(value),
-					None => None,
-					_ => return Err(format!("Unexpected `{:?}` value", self.value).into()),
-				};
+				let value =
+					match utils::maybe_deserialize_value::<B>(operation, self.value.as_ref())? {
+						Some(DbValue::Meta(value)) => Some(value),
+						None => None,
+						_ => return Err(format!("Unexpected `{:?}` value", self.value).into()),
+					};
 				// Run the query
 				meta_db.query(&key, &value)?
 			}
 			Column::Block | Column::Transaction => {
 				// New mapping db handler
-				let mapping_db = MappingDb::new(self, client, backend);
+				let mapping_db = MappingDb::new(operation, client, backend);
 				// Maybe get a MappingKey
 				let key = MappingKey::EthBlockOrTransactionHash(
-					H256::from_str(&self.key).expect("H256 provided key"),
+					H256::from_str(key).expect("H256 provided key"),
 				);
 				// Maybe get a MappingValue
-				let value = match utils::maybe_deserialize_value::<B>(
-					&self.operation,
-					self.value.as_ref(),
-				)? {
-					Some(DbValue::Mapping(value)) => Some(value),
-					None => None,
-					_ => return Err(format!("Unexpected `{:?}` value", self.value).into()),
-				};
+				let value =
+					match utils::maybe_deserialize_value::<B>(operation, self.value.as_ref())? {
+						Some(DbValue::Mapping(value)) => Some(value),
+						None => None,
+						_ => return Err(format!("Unexpected `{:?}` value", self.value).into()),
+					};
 				// Run the query
-				mapping_db.query(&self.column, &key, &value)?
+				mapping_db.query(column, &key, &value)?
 			}
 		}
 		Ok(())
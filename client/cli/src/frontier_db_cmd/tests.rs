@@ -30,7 +30,7 @@ use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
 use sp_io::hashing::twox_128;
 use sp_runtime::{
-	generic::{Block, Header},
+	generic::{Block, DigestItem, Header},
 	traits::{BlakeTwo256, Block as BlockT},
 };
 use substrate_test_runtime_client::{
@@ -41,7 +41,9 @@ use substrate_test_runtime_client::{
 use fp_storage::{EthereumStorageSchema, ETHEREUM_CURRENT_TRANSACTION_STATUS, PALLET_ETHEREUM};
 use frontier_template_runtime::RuntimeApi;
 
-use crate::frontier_db_cmd::{Column, FrontierDbCmd, Operation};
+use crate::frontier_db_cmd::{
+	BlockRange, Column, FrontierDbCmd, Maintenance, MaintenanceDb, Operation,
+};
 
 type OpaqueBlock =
 	Block<Header<u64, BlakeTwo256>, substrate_test_runtime_client::runtime::Extrinsic>;
@@ -75,9 +77,10 @@ enum TestValue {
 
 fn cmd(key: String, value: Option<PathBuf>, operation: Operation, column: Column) -> FrontierDbCmd {
 	FrontierDbCmd {
-		operation,
-		column,
-		key,
+		maintenance: None,
+		operation: Some(operation),
+		column: Some(column),
+		key: Some(key),
 		value,
 		shared_params: sc_cli::SharedParams {
 			chain: None,
@@ -791,3 +794,124 @@ fn mapping_read_works() {
 	.run(Arc::clone(&client), backend.clone())
 	.is_ok());
 }
+
+fn ethereum_digest(number: u8) -> DigestItem {
+	// Commit the hashes of an Ethereum block with a single transaction.
+	let hashes = fp_consensus::Hashes {
+		block_hash: H256::repeat_byte(number),
+		transaction_hashes: vec![H256::repeat_byte(0x10 + number)],
+	};
+	DigestItem::Consensus(
+		fp_consensus::FRONTIER_ENGINE_ID,
+		fp_consensus::PostLog::Hashes(hashes).encode(),
+	)
+}
+
+#[ignore]
+#[test]
+fn repair_fixes_missing_mappings() {
+	let tmp = tempdir().expect("create a temporary directory");
+
+	// Test client.
+	let (c, _) = TestClientBuilder::new().build_with_native_executor::<RuntimeApi, _>(None);
+	let mut client = Arc::new(c);
+
+	// Build two blocks including an Ethereum digest.
+	let mut block_hashes = vec![];
+	for number in 1..=2 {
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder
+			.push_deposit_log_digest_item(ethereum_digest(number))
+			.expect("deposit log");
+		let block = builder.build().unwrap().block;
+		block_hashes.push(block.header.hash());
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	// Create a temporary frontier secondary DB.
+	let backend = open_frontier_backend::<OpaqueBlock, _>(client.clone(), tmp.into_path())
+		.expect("a temporary db was created");
+	let overrides = fc_storage::overrides_handle(client.clone());
+	let maintenance_db = MaintenanceDb::new(
+		client.clone(),
+		fc_db::Backend::KeyValue((*backend).clone()),
+		overrides.clone(),
+	);
+	let range = BlockRange { from: 1, to: None };
+
+	// Expect both blocks to be reported, as none was synced.
+	let inconsistencies = maintenance_db.verify(&range).expect("verify the range");
+	assert_eq!(
+		inconsistencies
+			.iter()
+			.map(|inconsistency| inconsistency.hash)
+			.collect::<Vec<_>>(),
+		block_hashes
+	);
+
+	// Run the repair.
+	assert!(Maintenance::Repair(range.clone())
+		.run(
+			client.clone(),
+			fc_db::Backend::KeyValue((*backend).clone()),
+			overrides,
+		)
+		.is_ok());
+
+	// Expect the mappings of both blocks.
+	assert_eq!(maintenance_db.verify(&range), Ok(vec![]));
+	assert_eq!(
+		backend.mapping().block_hash(&H256::repeat_byte(2)),
+		Ok(Some(vec![block_hashes[1]]))
+	);
+}
+
+#[ignore]
+#[test]
+fn reindex_does_not_duplicate_mappings() {
+	let tmp = tempdir().expect("create a temporary directory");
+
+	// Test client.
+	let (c, _) = TestClientBuilder::new().build_with_native_executor::<RuntimeApi, _>(None);
+	let mut client = Arc::new(c);
+
+	// Build a block including an Ethereum digest.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder
+		.push_deposit_log_digest_item(ethereum_digest(1))
+		.expect("deposit log");
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+	// Create a temporary frontier secondary DB.
+	let backend = open_frontier_backend::<OpaqueBlock, _>(client.clone(), tmp.into_path())
+		.expect("a temporary db was created");
+	let overrides = fc_storage::overrides_handle(client.clone());
+
+	// Reindex the block twice.
+	for _ in 0..2 {
+		assert!(Maintenance::Reindex(BlockRange {
+			from: 1,
+			to: Some(1)
+		})
+		.run(
+			client.clone(),
+			fc_db::Backend::KeyValue((*backend).clone()),
+			overrides.clone(),
+		)
+		.is_ok());
+	}
+
+	// Expect a single transaction metadata.
+	assert_eq!(
+		backend
+			.mapping()
+			.transaction_metadata(&H256::repeat_byte(0x11)),
+		Ok(vec![fc_db::TransactionMetadata {
+			block_hash,
+			ethereum_block_hash: H256::repeat_byte(1),
+			ethereum_index: 0,
+		}])
+	);
+}
@@ -18,6 +18,12 @@
 
 #![deny(unused_crate_dependencies)]
 
+mod ethereum_genesis;
+mod export_evm_state_cmd;
 mod frontier_db_cmd;
 
-pub use self::frontier_db_cmd::FrontierDbCmd;
+pub use self::{
+	ethereum_genesis::EthereumGenesis,
+	export_evm_state_cmd::{export_evm_state, ExportEvmStateCmd},
+	frontier_db_cmd::{BlockRange, FrontierDbCmd, Inconsistency, Maintenance, MaintenanceDb},
+};
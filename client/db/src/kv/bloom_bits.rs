@@ -0,0 +1,498 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2023 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bloom bits index of the logs blooms.
+//!
+//! The logs blooms of each section of canonical blocks are stored transposed, one bit vector
+//! per bloom bit, so that a log filter only reads the few vectors of the bits it sets to find
+//! the blocks that may hold matching logs.
+
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+use ethereum_types::{Bloom, BloomInput};
+use scale_codec::{Decode, Encode};
+// Substrate
+use sp_core::{H160, H256};
+use sp_runtime::traits::Block as BlockT;
+
+use super::{Database, DbHash};
+
+/// Number of blocks in a section of the bloom bits index.
+pub const BLOOM_BITS_SECTION_SIZE: u64 = 4096;
+
+/// Number of bits of a logs bloom.
+const BLOOM_BITS: usize = 2048;
+/// Length in bytes of the bit vector of a section.
+const VECTOR_LEN: usize = BLOOM_BITS_SECTION_SIZE as usize / 8;
+
+const BIT_VECTOR_PREFIX: &[u8] = b"BLOOM_BITS_VECTOR";
+const SECTION_HEAD_PREFIX: &[u8] = b"BLOOM_BITS_HEAD";
+const PENDING_BLOOM_PREFIX: &[u8] = b"BLOOM_BITS_PENDING";
+const PENDING_HASHES_PREFIX: &[u8] = b"BLOOM_BITS_PENDING_HASHES";
+
+/// Bit vector of a section, stored as the offsets of its set bits when these are few.
+#[derive(Debug, Encode, Decode, PartialEq)]
+enum BitVector {
+	Sparse(Vec<u16>),
+	Dense(Vec<u8>),
+}
+
+impl BitVector {
+	fn new(bits: &[u8]) -> Self {
+		let offsets = (0..BLOOM_BITS_SECTION_SIZE as usize)
+			.filter(|offset| is_set(bits, *offset))
+			.map(|offset| offset as u16)
+			.collect::<Vec<_>>();
+		if offsets.len() * 2 < VECTOR_LEN {
+			BitVector::Sparse(offsets)
+		} else {
+			BitVector::Dense(bits.to_vec())
+		}
+	}
+
+	fn into_bits(self) -> Vec<u8> {
+		match self {
+			BitVector::Sparse(offsets) => {
+				let mut bits = vec![0u8; VECTOR_LEN];
+				for offset in offsets {
+					set(&mut bits, offset as usize);
+				}
+				bits
+			}
+			BitVector::Dense(bits) => bits,
+		}
+	}
+}
+
+fn is_set(bits: &[u8], offset: usize) -> bool {
+	bits[offset / 8] & (0x80 >> (offset % 8)) != 0
+}
+
+fn set(bits: &mut [u8], offset: usize) {
+	bits[offset / 8] |= 0x80 >> (offset % 8);
+}
+
+/// Bits set in a logs bloom.
+fn bloom_bits(bloom: &Bloom) -> impl Iterator<Item = u16> + '_ {
+	let bytes = bloom.as_bytes();
+	(0..BLOOM_BITS).filter_map(move |bit| {
+		(bytes[bytes.len() - 1 - bit / 8] & (1 << (bit % 8)) != 0).then_some(bit as u16)
+	})
+}
+
+/// Bits set in a logs bloom by an address or a topic.
+fn input_bits(input: &[u8]) -> Vec<u16> {
+	bloom_bits(&Bloom::from(BloomInput::Raw(input))).collect()
+}
+
+/// Log filter as bloom bits. A block matches when it matches every clause, and a clause when all
+/// the bits of any of its alternatives are set.
+fn filter_clauses(addresses: &[H160], topics: &[Vec<Option<H256>>]) -> Vec<Vec<Vec<u16>>> {
+	let mut clauses = Vec::new();
+	if !addresses.is_empty() {
+		clauses.push(
+			addresses
+				.iter()
+				.map(|address| input_bits(address.as_bytes()))
+				.collect(),
+		);
+	}
+	let alternatives = topics
+		.iter()
+		.map(|topics| {
+			topics
+				.iter()
+				.flatten()
+				.flat_map(|topic| input_bits(topic.as_bytes()))
+				.collect::<Vec<_>>()
+		})
+		.collect::<Vec<_>>();
+	// An alternative without topics matches any block.
+	if alternatives.iter().all(|bits| !bits.is_empty()) && !alternatives.is_empty() {
+		clauses.push(alternatives);
+	}
+	clauses
+}
+
+fn bit_vector_key(bit: u16, section: u64) -> Vec<u8> {
+	let mut key = BIT_VECTOR_PREFIX.to_vec();
+	key.extend_from_slice(&bit.to_be_bytes());
+	key.extend_from_slice(&section.to_be_bytes());
+	key
+}
+
+fn section_head_key(section: u64) -> Vec<u8> {
+	let mut key = SECTION_HEAD_PREFIX.to_vec();
+	key.extend_from_slice(&section.to_be_bytes());
+	key
+}
+
+fn pending_bloom_key<Hash: Encode>(block_hash: &Hash) -> Vec<u8> {
+	let mut key = PENDING_BLOOM_PREFIX.to_vec();
+	key.extend(block_hash.encode());
+	key
+}
+
+fn pending_hashes_key(number: u64) -> Vec<u8> {
+	let mut key = PENDING_HASHES_PREFIX.to_vec();
+	key.extend_from_slice(&number.to_be_bytes());
+	key
+}
+
+pub struct BloomBitsDb<Block: BlockT> {
+	pub(super) db: Arc<dyn Database<DbHash>>,
+	pub(super) _marker: PhantomData<Block>,
+}
+
+impl<Block: BlockT> BloomBitsDb<Block> {
+	/// Keeps the logs bloom of a block until its section is indexed. Nothing is kept for the blocks
+	/// of the sections already processed.
+	pub fn write_block_bloom(
+		&self,
+		number: u64,
+		block_hash: &Block::Hash,
+		bloom: &Bloom,
+	) -> Result<(), String> {
+		if number / BLOOM_BITS_SECTION_SIZE < self.next_section()? {
+			return Ok(());
+		}
+
+		let mut transaction = sp_database::Transaction::new();
+
+		let mut hashes = self.pending_hashes(number)?;
+		if !hashes.contains(block_hash) {
+			hashes.push(*block_hash);
+			transaction.set(
+				crate::columns::META,
+				&pending_hashes_key(number),
+				&hashes.encode(),
+			);
+		}
+		transaction.set(
+			crate::columns::META,
+			&pending_bloom_key(block_hash),
+			bloom.as_bytes(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
+	pub fn block_bloom(&self, block_hash: &Block::Hash) -> Result<Option<Bloom>, String> {
+		match self
+			.db
+			.get(crate::columns::META, &pending_bloom_key(block_hash))
+		{
+			Some(raw) if raw.len() == Bloom::len_bytes() => Ok(Some(Bloom::from_slice(&raw))),
+			Some(_) => Err("Invalid logs bloom length".to_string()),
+			None => Ok(None),
+		}
+	}
+
+	/// The first section not yet processed by the indexer.
+	pub fn next_section(&self) -> Result<u64, String> {
+		match self.db.get(
+			crate::columns::META,
+			crate::static_keys::BLOOM_BITS_NEXT_SECTION,
+		) {
+			Some(raw) => Ok(u64::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?),
+			None => Ok(0),
+		}
+	}
+
+	/// Indexes a section from the logs blooms of its canonical blocks, or only skips it when these
+	/// are not available. The blooms kept for all the blocks of the section, including the ones of
+	/// forks, are dropped either way.
+	pub fn write_section(
+		&self,
+		section: u64,
+		block_hashes: &[Block::Hash],
+		blooms: Option<&[Bloom]>,
+	) -> Result<(), String> {
+		let mut transaction = sp_database::Transaction::new();
+
+		if let Some(blooms) = blooms {
+			if blooms.len() as u64 != BLOOM_BITS_SECTION_SIZE || block_hashes.len() != blooms.len()
+			{
+				return Err(format!(
+					"Expected {} blocks in section {}, got {} blocks and {} blooms",
+					BLOOM_BITS_SECTION_SIZE,
+					section,
+					block_hashes.len(),
+					blooms.len()
+				));
+			}
+			let mut vectors = vec![vec![0u8; VECTOR_LEN]; BLOOM_BITS];
+			for (offset, bloom) in blooms.iter().enumerate() {
+				for bit in bloom_bits(bloom) {
+					set(&mut vectors[bit as usize], offset);
+				}
+			}
+			// Vectors without any bit set are not stored.
+			for (bit, vector) in vectors.iter().enumerate() {
+				if vector.iter().any(|byte| *byte != 0) {
+					transaction.set(
+						crate::columns::META,
+						&bit_vector_key(bit as u16, section),
+						&BitVector::new(vector).encode(),
+					);
+				}
+			}
+			transaction.set(
+				crate::columns::META,
+				&section_head_key(section),
+				&block_hashes[block_hashes.len() - 1].encode(),
+			);
+		}
+
+		for number in section * BLOOM_BITS_SECTION_SIZE..(section + 1) * BLOOM_BITS_SECTION_SIZE {
+			for block_hash in self.pending_hashes(number)? {
+				transaction.remove(crate::columns::META, &pending_bloom_key(&block_hash));
+			}
+			transaction.remove(crate::columns::META, &pending_hashes_key(number));
+		}
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::BLOOM_BITS_NEXT_SECTION,
+			&(section + 1).encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
+	/// Numbers of the blocks in the range whose logs bloom may match the addresses and topics, in
+	/// ascending order. All the blocks of the sections not indexed are returned.
+	pub fn filter_blocks(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: &[H160],
+		topics: &[Vec<Option<H256>>],
+	) -> Result<Vec<u64>, String> {
+		if from_block > to_block {
+			return Ok(Vec::new());
+		}
+		let clauses = filter_clauses(addresses, topics);
+		if clauses.is_empty() {
+			return Ok((from_block..=to_block).collect());
+		}
+
+		let mut blocks = Vec::new();
+		for section in from_block / BLOOM_BITS_SECTION_SIZE..=to_block / BLOOM_BITS_SECTION_SIZE {
+			let first = section * BLOOM_BITS_SECTION_SIZE;
+			let range = from_block.max(first)..=to_block.min(first + BLOOM_BITS_SECTION_SIZE - 1);
+			if !self
+				.db
+				.contains(crate::columns::META, &section_head_key(section))
+			{
+				blocks.extend(range);
+				continue;
+			}
+
+			let mut vectors = HashMap::new();
+			let mut matches = vec![0xffu8; VECTOR_LEN];
+			for clause in &clauses {
+				let mut clause_matches = vec![0u8; VECTOR_LEN];
+				for alternative in clause {
+					let mut alternative_matches = vec![0xffu8; VECTOR_LEN];
+					for bit in alternative {
+						if !vectors.contains_key(bit) {
+							vectors.insert(*bit, self.bit_vector(*bit, section)?);
+						}
+						for (byte, vector_byte) in alternative_matches.iter_mut().zip(&vectors[bit])
+						{
+							*byte &= vector_byte;
+						}
+					}
+					for (byte, alternative_byte) in
+						clause_matches.iter_mut().zip(&alternative_matches)
+					{
+						*byte |= alternative_byte;
+					}
+				}
+				for (byte, clause_byte) in matches.iter_mut().zip(&clause_matches) {
+					*byte &= clause_byte;
+				}
+			}
+			blocks.extend(range.filter(|number| is_set(&matches, (number - first) as usize)));
+		}
+
+		Ok(blocks)
+	}
+
+	/// Hashes of the blocks at the given height whose logs bloom is kept.
+	fn pending_hashes(&self, number: u64) -> Result<Vec<Block::Hash>, String> {
+		match self
+			.db
+			.get(crate::columns::META, &pending_hashes_key(number))
+		{
+			Some(raw) => Vec::<Block::Hash>::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e)),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	fn bit_vector(&self, bit: u16, section: u64) -> Result<Vec<u8>, String> {
+		match self
+			.db
+			.get(crate::columns::META, &bit_vector_key(bit, section))
+		{
+			Some(raw) => Ok(BitVector::decode(&mut &raw[..])
+				.map_err(|e| format!("{:?}", e))?
+				.into_bits()),
+			None => Ok(vec![0u8; VECTOR_LEN]),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::{
+		generic::{Block, Header},
+		traits::BlakeTwo256,
+	};
+
+	type OpaqueBlock =
+		Block<Header<u64, BlakeTwo256>, substrate_test_runtime_client::runtime::Extrinsic>;
+
+	fn bloom_bits_db() -> BloomBitsDb<OpaqueBlock> {
+		BloomBitsDb {
+			db: Arc::new(sp_database::MemDb::default()),
+			_marker: PhantomData,
+		}
+	}
+
+	fn block_hashes(section: u64) -> Vec<H256> {
+		(0..BLOOM_BITS_SECTION_SIZE)
+			.map(|offset| H256::from_low_u64_be(section * BLOOM_BITS_SECTION_SIZE + offset))
+			.collect()
+	}
+
+	#[test]
+	fn bit_vector_encoding_works() {
+		let mut sparse = vec![0u8; VECTOR_LEN];
+		set(&mut sparse, 0);
+		set(&mut sparse, 4095);
+		assert_eq!(BitVector::new(&sparse), BitVector::Sparse(vec![0, 4095]));
+		assert_eq!(BitVector::new(&sparse).into_bits(), sparse);
+
+		let dense = vec![0x55u8; VECTOR_LEN];
+		assert_eq!(BitVector::new(&dense), BitVector::Dense(dense.clone()));
+		assert_eq!(BitVector::new(&dense).into_bits(), dense);
+	}
+
+	#[test]
+	fn filter_blocks_works() {
+		let db = bloom_bits_db();
+		let alice = H160::repeat_byte(0x01);
+		let bob = H160::repeat_byte(0x02);
+		let topic = H256::repeat_byte(0x03);
+
+		let mut blooms = vec![Bloom::default(); BLOOM_BITS_SECTION_SIZE as usize];
+		blooms[10].accrue(BloomInput::Raw(alice.as_bytes()));
+		blooms[10].accrue(BloomInput::Raw(topic.as_bytes()));
+		blooms[20].accrue(BloomInput::Raw(bob.as_bytes()));
+		blooms[4095].accrue(BloomInput::Raw(alice.as_bytes()));
+		db.write_section(0, &block_hashes(0), Some(&blooms))
+			.expect("section is indexed");
+		assert_eq!(db.next_section(), Ok(1));
+
+		// Section 1 is not indexed, all its blocks are candidates.
+		assert_eq!(
+			db.filter_blocks(4000, 4100, &[alice], &[]),
+			Ok([4095].into_iter().chain(4096..=4100).collect())
+		);
+		assert_eq!(
+			db.filter_blocks(0, 4095, &[alice, bob], &[]),
+			Ok(vec![10, 20, 4095])
+		);
+		assert_eq!(
+			db.filter_blocks(0, 4095, &[alice], &[vec![None, Some(topic)]]),
+			Ok(vec![10])
+		);
+		assert_eq!(
+			db.filter_blocks(0, 4095, &[bob], &[vec![Some(topic)]]),
+			Ok(vec![])
+		);
+		// A filter without any address or topic matches every block.
+		assert_eq!(
+			db.filter_blocks(5, 9, &[], &[vec![None]]),
+			Ok((5..=9).collect())
+		);
+	}
+
+	#[test]
+	fn write_section_drops_block_blooms() {
+		let db = bloom_bits_db();
+		let hashes = block_hashes(0);
+		let mut bloom = Bloom::default();
+		bloom.accrue(BloomInput::Raw(&[1]));
+		db.write_block_bloom(0, &hashes[0], &bloom)
+			.expect("bloom is written");
+		assert_eq!(db.block_bloom(&hashes[0]), Ok(Some(bloom)));
+
+		// The blooms are not available, the section is skipped.
+		db.write_section(0, &hashes, None)
+			.expect("section is skipped");
+		assert_eq!(db.block_bloom(&hashes[0]), Ok(None));
+		assert_eq!(db.next_section(), Ok(1));
+		assert_eq!(
+			db.filter_blocks(0, 3, &[H160::repeat_byte(0x01)], &[]),
+			Ok(vec![0, 1, 2, 3])
+		);
+	}
+
+	#[test]
+	fn write_section_drops_fork_block_blooms() {
+		let db = bloom_bits_db();
+		let hashes = block_hashes(0);
+		let next_hashes = block_hashes(1);
+		let fork_hash = H256::repeat_byte(0xff);
+		let mut bloom = Bloom::default();
+		bloom.accrue(BloomInput::Raw(&[1]));
+		db.write_block_bloom(7, &hashes[7], &bloom)
+			.expect("bloom is written");
+		db.write_block_bloom(7, &fork_hash, &bloom)
+			.expect("bloom is written");
+		db.write_block_bloom(BLOOM_BITS_SECTION_SIZE, &next_hashes[0], &bloom)
+			.expect("bloom is written");
+		assert_eq!(db.pending_hashes(7), Ok(vec![hashes[7], fork_hash]));
+
+		let blooms = vec![Bloom::default(); BLOOM_BITS_SECTION_SIZE as usize];
+		db.write_section(0, &hashes, Some(&blooms))
+			.expect("section is indexed");
+		assert_eq!(db.block_bloom(&hashes[7]), Ok(None));
+		assert_eq!(db.block_bloom(&fork_hash), Ok(None));
+		assert_eq!(db.pending_hashes(7), Ok(vec![]));
+		// The blooms of the next sections are kept.
+		assert_eq!(db.block_bloom(&next_hashes[0]), Ok(Some(bloom)));
+
+		// Blocks of an indexed section, such as a fork imported late, are not kept.
+		db.write_block_bloom(8, &fork_hash, &bloom)
+			.expect("bloom is skipped");
+		assert_eq!(db.block_bloom(&fork_hash), Ok(None));
+		assert_eq!(db.pending_hashes(8), Ok(vec![]));
+	}
+}
@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod bloom_bits;
 mod parity_db_adapter;
 mod upgrade;
 mod utils;
@@ -37,7 +38,8 @@ use sp_runtime::traits::Block as BlockT;
 // Frontier
 use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA_CACHE};
 
-use crate::TransactionMetadata;
+pub use self::bloom_bits::{BloomBitsDb, BLOOM_BITS_SECTION_SIZE};
+use crate::{BlockData, TransactionMetadata};
 
 const DB_HASH_LEN: usize = 32;
 /// Hash type that this backend uses for the database.
@@ -60,12 +62,15 @@ pub(crate) mod columns {
 
 pub mod static_keys {
 	pub const CURRENT_SYNCING_TIPS: &[u8] = b"CURRENT_SYNCING_TIPS";
+	pub const BLOCK_DATA: &[u8] = b"BLOCK_DATA";
+	pub const BLOOM_BITS_NEXT_SECTION: &[u8] = b"BLOOM_BITS_NEXT_SECTION";
 }
 
 #[derive(Clone)]
 pub struct Backend<Block: BlockT> {
 	meta: Arc<MetaDb<Block>>,
 	mapping: Arc<MappingDb<Block>>,
+	bloom_bits: Arc<BloomBitsDb<Block>>,
 }
 
 #[async_trait::async_trait]
@@ -93,6 +98,62 @@ impl<Block: BlockT> crate::BackendReader<Block> for Backend<Block> {
 		Err("KeyValue db does not index logs".into())
 	}
 
+	async fn filter_traces(
+		&self,
+		_from_block: u64,
+		_to_block: u64,
+		_from_addresses: Vec<sp_core::H160>,
+		_to_addresses: Vec<sp_core::H160>,
+		_after: u64,
+		_count: Option<u64>,
+	) -> Result<Vec<crate::FilteredTrace<Block>>, String> {
+		Err("KeyValue db does not index traces".into())
+	}
+
+	async fn filter_blocks(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: Vec<sp_core::H160>,
+		topics: Vec<Vec<Option<H256>>>,
+	) -> Result<Option<Vec<u64>>, String> {
+		self.bloom_bits()
+			.filter_blocks(from_block, to_block, &addresses, &topics)
+			.map(Some)
+	}
+
+	async fn block_data(
+		&self,
+		substrate_block_hash: &Block::Hash,
+	) -> Result<Option<BlockData>, String> {
+		self.mapping().block_data(substrate_block_hash)
+	}
+
+	async fn search_transactions(
+		&self,
+		_address: sp_core::H160,
+		_block_number: u64,
+		_direction: crate::SearchDirection,
+		_page_size: u64,
+	) -> Result<Vec<crate::FilteredTransaction<Block>>, String> {
+		Err("KeyValue db does not index addresses".into())
+	}
+
+	async fn contract_creator(
+		&self,
+		_address: sp_core::H160,
+	) -> Result<Option<(sp_core::H160, crate::FilteredTransaction<Block>)>, String> {
+		Err("KeyValue db does not index addresses".into())
+	}
+
+	async fn transaction_by_sender_and_nonce(
+		&self,
+		_sender: sp_core::H160,
+		_nonce: sp_core::U256,
+	) -> Result<Option<crate::FilteredTransaction<Block>>, String> {
+		Err("KeyValue db does not index addresses".into())
+	}
+
 	fn is_indexed(&self) -> bool {
 		false
 	}
@@ -152,6 +213,10 @@ impl<Block: BlockT> Backend<Block> {
 				db: db.clone(),
 				_marker: PhantomData,
 			}),
+			bloom_bits: Arc::new(BloomBitsDb {
+				db: db.clone(),
+				_marker: PhantomData,
+			}),
 		})
 	}
 
@@ -162,6 +227,27 @@ impl<Block: BlockT> Backend<Block> {
 	pub fn meta(&self) -> &Arc<MetaDb<Block>> {
 		&self.meta
 	}
+
+	pub fn bloom_bits(&self) -> &Arc<BloomBitsDb<Block>> {
+		&self.bloom_bits
+	}
+
+	/// Forgets the blocks reverted from the substrate backend, so that they are neither served
+	/// nor expected to be synced anymore.
+	pub fn revert(&self, reverted: &[Block::Hash]) -> Result<(), String> {
+		let tips = self
+			.meta
+			.current_syncing_tips()?
+			.into_iter()
+			.filter(|tip| !reverted.contains(tip))
+			.collect();
+		self.meta.write_current_syncing_tips(tips)?;
+
+		for block_hash in reverted {
+			self.mapping.remove_block(block_hash)?;
+		}
+		Ok(())
+	}
 }
 
 pub struct MetaDb<Block: BlockT> {
@@ -283,6 +369,39 @@ impl<Block: BlockT> MappingDb<Block> {
 		}
 	}
 
+	pub fn block_data(&self, block_hash: &Block::Hash) -> Result<Option<BlockData>, String> {
+		match self
+			.db
+			.get(crate::columns::META, &block_data_key(block_hash))
+		{
+			Some(raw) => Ok(Some(
+				BlockData::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Keeps the Ethereum data of the block, to serve it once its state is pruned.
+	pub fn write_block_data(
+		&self,
+		block_hash: &Block::Hash,
+		data: &BlockData,
+	) -> Result<(), String> {
+		let mut transaction = sp_database::Transaction::new();
+
+		transaction.set(
+			crate::columns::META,
+			&block_data_key(block_hash),
+			&data.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
 	pub fn write_none(&self, block_hash: Block::Hash) -> Result<(), String> {
 		let _lock = self.write_lock.lock();
 
@@ -334,11 +453,15 @@ impl<Block: BlockT> MappingDb<Block> {
 			.enumerate()
 		{
 			let mut metadata = self.transaction_metadata(&ethereum_transaction_hash)?;
-			metadata.push(TransactionMetadata::<Block> {
+			let transaction_metadata = TransactionMetadata::<Block> {
 				block_hash: commitment.block_hash,
 				ethereum_block_hash: commitment.ethereum_block_hash,
 				ethereum_index: i as u32,
-			});
+			};
+			// The block may be synced again, e.g. when reindexed.
+			if !metadata.contains(&transaction_metadata) {
+				metadata.push(transaction_metadata);
+			}
 			transaction.set(
 				crate::columns::TRANSACTION_MAPPING,
 				&ethereum_transaction_hash.encode(),
@@ -358,4 +481,210 @@ impl<Block: BlockT> MappingDb<Block> {
 
 		Ok(())
 	}
+
+	/// Removes the block from the mapping.
+	///
+	/// The Ethereum hashes of the block are read from its block data, when there is none its
+	/// Ethereum block and transactions stay mapped to it. Readers skip them, as they only serve
+	/// canonical blocks.
+	pub fn remove_block(&self, block_hash: &Block::Hash) -> Result<(), String> {
+		let _lock = self.write_lock.lock();
+
+		let mut transaction = sp_database::Transaction::new();
+
+		if let Some(data) = self.block_data(block_hash)? {
+			let ethereum_block_hash = data.block.header.hash();
+			let substrate_hashes: Vec<Block::Hash> = self
+				.block_hash(&ethereum_block_hash)?
+				.unwrap_or_default()
+				.into_iter()
+				.filter(|hash| hash != block_hash)
+				.collect();
+			if substrate_hashes.is_empty() {
+				transaction.remove(crate::columns::BLOCK_MAPPING, &ethereum_block_hash.encode());
+			} else {
+				transaction.set(
+					crate::columns::BLOCK_MAPPING,
+					&ethereum_block_hash.encode(),
+					&substrate_hashes.encode(),
+				);
+			}
+
+			for ethereum_transaction in &data.block.transactions {
+				let ethereum_transaction_hash = ethereum_transaction.hash();
+				let metadata: Vec<TransactionMetadata<Block>> = self
+					.transaction_metadata(&ethereum_transaction_hash)?
+					.into_iter()
+					.filter(|metadata| &metadata.block_hash != block_hash)
+					.collect();
+				if metadata.is_empty() {
+					transaction.remove(
+						crate::columns::TRANSACTION_MAPPING,
+						&ethereum_transaction_hash.encode(),
+					);
+				} else {
+					transaction.set(
+						crate::columns::TRANSACTION_MAPPING,
+						&ethereum_transaction_hash.encode(),
+						&metadata.encode(),
+					);
+				}
+			}
+
+			transaction.remove(crate::columns::META, &block_data_key(block_hash));
+		}
+
+		transaction.remove(crate::columns::SYNCED_MAPPING, &block_hash.encode());
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+}
+
+/// The block data is kept in the meta column, keyed by the substrate block hash.
+fn block_data_key<Hash: Encode>(block_hash: &Hash) -> Vec<u8> {
+	let mut key = crate::static_keys::BLOCK_DATA.to_vec();
+	key.extend(block_hash.encode());
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum::{
+		LegacyTransaction, PartialHeader, TransactionAction, TransactionSignature, TransactionV2,
+	};
+	use sp_core::{H160, H64, U256};
+	use sp_runtime::{
+		generic::{Block, Header},
+		traits::BlakeTwo256,
+	};
+
+	type OpaqueBlock =
+		Block<Header<u64, BlakeTwo256>, substrate_test_runtime_client::runtime::Extrinsic>;
+
+	fn backend() -> Backend<OpaqueBlock> {
+		let db: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::default());
+		Backend {
+			mapping: Arc::new(MappingDb {
+				db: db.clone(),
+				write_lock: Arc::new(Mutex::new(())),
+				_marker: PhantomData,
+			}),
+			meta: Arc::new(MetaDb {
+				db: db.clone(),
+				_marker: PhantomData,
+			}),
+			bloom_bits: Arc::new(BloomBitsDb {
+				db,
+				_marker: PhantomData,
+			}),
+		}
+	}
+
+	fn block_data(number: u64) -> BlockData {
+		let transaction = TransactionV2::Legacy(LegacyTransaction {
+			nonce: U256::from(number),
+			gas_price: U256::one(),
+			gas_limit: U256::from(21_000),
+			action: TransactionAction::Call(H160::repeat_byte(0x01)),
+			value: U256::zero(),
+			input: Vec::new(),
+			signature: TransactionSignature::new(
+				27,
+				H256::from_low_u64_be(1),
+				H256::from_low_u64_be(1),
+			)
+			.unwrap(),
+		});
+		let header = PartialHeader {
+			parent_hash: H256::zero(),
+			beneficiary: H160::zero(),
+			state_root: H256::zero(),
+			receipts_root: H256::zero(),
+			logs_bloom: Default::default(),
+			difficulty: U256::zero(),
+			number: U256::from(number),
+			gas_limit: U256::zero(),
+			gas_used: U256::zero(),
+			timestamp: 0,
+			extra_data: Vec::new(),
+			mix_hash: H256::zero(),
+			nonce: H64::zero(),
+		};
+		BlockData {
+			block: ethereum::BlockV2::new(header, vec![transaction], Vec::new()),
+			receipts: Vec::new(),
+			statuses: Vec::new(),
+			is_eip1559: true,
+			base_fee: U256::one(),
+		}
+	}
+
+	fn sync(backend: &Backend<OpaqueBlock>, block_hash: H256, data: &BlockData) {
+		backend
+			.mapping()
+			.write_hashes(MappingCommitment {
+				block_hash,
+				ethereum_block_hash: data.block.header.hash(),
+				ethereum_transaction_hashes: data
+					.block
+					.transactions
+					.iter()
+					.map(|transaction| transaction.hash())
+					.collect(),
+			})
+			.unwrap();
+		backend
+			.mapping()
+			.write_block_data(&block_hash, data)
+			.unwrap();
+	}
+
+	#[test]
+	fn revert_forgets_the_reverted_blocks() {
+		let backend = backend();
+		let kept = H256::repeat_byte(0x01);
+		let reverted = H256::repeat_byte(0x02);
+		let (kept_data, reverted_data) = (block_data(1), block_data(2));
+		sync(&backend, kept, &kept_data);
+		sync(&backend, reverted, &reverted_data);
+		backend
+			.meta()
+			.write_current_syncing_tips(vec![kept, reverted])
+			.unwrap();
+
+		backend.revert(&[reverted]).unwrap();
+
+		let mapping = backend.mapping();
+		assert_eq!(backend.meta().current_syncing_tips(), Ok(vec![kept]));
+		assert_eq!(mapping.is_synced(&reverted), Ok(false));
+		assert_eq!(mapping.block_data(&reverted), Ok(None));
+		assert_eq!(
+			mapping.block_hash(&reverted_data.block.header.hash()),
+			Ok(None)
+		);
+		assert_eq!(
+			mapping
+				.transaction_metadata(&reverted_data.block.transactions[0].hash())
+				.map(|metadata| metadata.len()),
+			Ok(0)
+		);
+
+		// The other blocks are left untouched.
+		assert_eq!(mapping.is_synced(&kept), Ok(true));
+		assert_eq!(
+			mapping.block_hash(&kept_data.block.header.hash()),
+			Ok(Some(vec![kept]))
+		);
+		assert_eq!(
+			mapping
+				.transaction_metadata(&kept_data.block.transactions[0].hash())
+				.map(|metadata| metadata.len()),
+			Ok(1)
+		);
+	}
 }
@@ -21,7 +21,7 @@
 use scale_codec::{Decode, Encode};
 // Substrate
 pub use sc_client_db::DatabaseSource;
-use sp_core::H256;
+use sp_core::{H256, U256};
 use sp_runtime::traits::Block as BlockT;
 
 pub mod kv;
@@ -30,6 +30,9 @@ use kv::{columns, static_keys};
 #[cfg(feature = "sql")]
 pub mod sql;
 
+/// Maximum number of transactions requested by a page of `BackendReader::search_transactions`.
+pub const MAX_SEARCH_PAGE_SIZE: u64 = 25;
+
 #[derive(Clone)]
 pub enum Backend<Block: BlockT> {
 	KeyValue(kv::Backend<Block>),
@@ -37,6 +40,26 @@ pub enum Backend<Block: BlockT> {
 	Sql(sql::Backend<Block>),
 }
 
+impl<Block: BlockT<Hash = H256>> Backend<Block> {
+	/// Forgets the blocks reverted from the substrate backend, so that they are no longer
+	/// served.
+	pub async fn revert(&self, reverted: &[Block::Hash]) -> Result<(), String> {
+		match self {
+			Backend::KeyValue(backend) => backend.revert(reverted),
+			#[cfg(feature = "sql")]
+			Backend::Sql(backend) => {
+				for block_hash in reverted {
+					backend
+						.remove_block(*block_hash)
+						.await
+						.map_err(|e| format!("{:?}", e))?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
 #[derive(Clone, Encode, Debug, Decode, Eq, PartialEq)]
 pub struct TransactionMetadata<Block: BlockT> {
 	pub block_hash: Block::Hash,
@@ -54,6 +77,55 @@ pub struct FilteredLog<Block: BlockT> {
 	pub log_index: u32,
 }
 
+/// A call frame of a transaction trace, as indexed by the backend.
+#[derive(Debug, Eq, PartialEq)]
+pub struct FilteredTrace<Block: BlockT> {
+	pub substrate_block_hash: Block::Hash,
+	pub ethereum_block_hash: H256,
+	pub block_number: u32,
+	pub transaction_hash: H256,
+	pub transaction_index: u32,
+	/// Path of the frame in the call tree of the transaction.
+	pub trace_address: Vec<u32>,
+	/// Number of nested calls of the frame.
+	pub subtraces: u32,
+	/// The frame, without its nested calls.
+	pub trace: fp_evm::tracing::CallTrace,
+}
+
+/// A transaction, as indexed by the backend for its sender, recipient or created contract.
+#[derive(Debug, Eq, PartialEq)]
+pub struct FilteredTransaction<Block: BlockT> {
+	pub substrate_block_hash: Block::Hash,
+	pub ethereum_block_hash: H256,
+	pub block_number: u32,
+	pub transaction_hash: H256,
+	pub transaction_index: u32,
+}
+
+/// Direction of a search through the indexed blocks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchDirection {
+	/// Towards the genesis, most recent blocks first.
+	Before,
+	/// Towards the best block, oldest blocks first.
+	After,
+}
+
+/// The Ethereum data of a block, kept by the backend to serve it once the state of the block is
+/// pruned.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq)]
+pub struct BlockData {
+	pub block: ethereum::BlockV2,
+	pub receipts: Vec<ethereum::ReceiptV3>,
+	pub statuses: Vec<fp_rpc::TransactionStatus>,
+	/// Whether the receipts of the block report the gas used by each transaction, rather than the
+	/// cumulative gas used.
+	pub is_eip1559: bool,
+	/// The base fee of the block, pricing its EIP-1559 transactions.
+	pub base_fee: U256,
+}
+
 #[async_trait::async_trait]
 pub trait BackendReader<Block: BlockT> {
 	async fn block_hash(
@@ -74,5 +146,58 @@ pub trait BackendReader<Block: BlockT> {
 		topics: Vec<Vec<Option<H256>>>,
 	) -> Result<Vec<FilteredLog<Block>>, String>;
 
+	/// Call frames in the block range, in execution order. Frames are matched by their sender and
+	/// recipient, an empty list matching any address. At most 10001 frames are returned.
+	async fn filter_traces(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		from_addresses: Vec<sp_core::H160>,
+		to_addresses: Vec<sp_core::H160>,
+		after: u64,
+		count: Option<u64>,
+	) -> Result<Vec<FilteredTrace<Block>>, String>;
+
+	/// Numbers of the blocks in the range that may hold logs matching the addresses and topics,
+	/// in ascending order. `None` when the backend cannot narrow the range down.
+	async fn filter_blocks(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: Vec<sp_core::H160>,
+		topics: Vec<Vec<Option<H256>>>,
+	) -> Result<Option<Vec<u64>>, String>;
+
+	/// The Ethereum data kept for the block, if the backend stores it.
+	async fn block_data(
+		&self,
+		substrate_block_hash: &Block::Hash,
+	) -> Result<Option<BlockData>, String>;
+
+	/// Transactions sent by, sent to or creating the address, in the canonical blocks strictly
+	/// before or after `block_number`, in the order of the search. A page holds at least
+	/// `page_size` transactions unless the search is exhausted, and ends on a block boundary.
+	/// `page_size` is clamped between 1 and `MAX_SEARCH_PAGE_SIZE`.
+	async fn search_transactions(
+		&self,
+		address: sp_core::H160,
+		block_number: u64,
+		direction: SearchDirection,
+		page_size: u64,
+	) -> Result<Vec<FilteredTransaction<Block>>, String>;
+
+	/// The creator of the contract at the address, along with the transaction creating it.
+	async fn contract_creator(
+		&self,
+		address: sp_core::H160,
+	) -> Result<Option<(sp_core::H160, FilteredTransaction<Block>)>, String>;
+
+	/// The canonical transaction sent by the sender with the nonce.
+	async fn transaction_by_sender_and_nonce(
+		&self,
+		sender: sp_core::H160,
+		nonce: U256,
+	) -> Result<Option<FilteredTransaction<Block>>, String>;
+
 	fn is_indexed(&self) -> bool;
 }
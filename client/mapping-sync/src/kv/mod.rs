@@ -24,18 +24,21 @@ pub use worker::MappingSyncWorker;
 
 use std::sync::Arc;
 
+use ethereum_types::Bloom;
 // Substrate
 use sc_client_api::backend::{Backend, StorageProvider};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::{Backend as _, HeaderBackend};
 use sp_consensus::SyncOracle;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto, Zero};
 // Frontier
 use fc_storage::OverrideHandle;
 use fp_consensus::{FindLogError, Hashes, Log, PostLog, PreLog};
 use fp_rpc::EthereumRuntimeRPCApi;
 
-use crate::{EthereumBlockNotification, EthereumBlockNotificationSinks, SyncStrategy};
+use crate::{
+	EthereumBlockNotification, EthereumBlockNotificationSinks, MappingSyncMetrics, SyncStrategy,
+};
 
 pub fn sync_block<Block: BlockT, C, BE>(
 	client: &C,
@@ -48,6 +51,7 @@ where
 	BE: Backend<Block>,
 {
 	let substrate_block_hash = header.hash();
+	let number = UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number());
 	match fp_consensus::find_log(header.digest()) {
 		Ok(log) => {
 			let gen_from_hashes = |hashes: Hashes| -> fc_db::kv::MappingCommitment<Block> {
@@ -61,29 +65,42 @@ where
 				let hashes = Hashes::from_block(block);
 				gen_from_hashes(hashes)
 			};
+			// Keep the logs bloom for the bloom bits index.
+			let write_block = |block: ethereum::BlockV2| -> Result<(), String> {
+				backend.bloom_bits().write_block_bloom(
+					number,
+					&substrate_block_hash,
+					&block.header.logs_bloom,
+				)?;
+				let mapping_commitment = gen_from_block(block);
+				backend.mapping().write_hashes(mapping_commitment)
+			};
+			let current_block = || {
+				let schema = fc_storage::onchain_storage_schema(client, substrate_block_hash);
+				overrides
+					.schemas
+					.get(&schema)
+					.unwrap_or(&overrides.fallback)
+					.current_block(substrate_block_hash)
+			};
 
 			match log {
-				Log::Pre(PreLog::Block(block)) => {
-					let mapping_commitment = gen_from_block(block);
-					backend.mapping().write_hashes(mapping_commitment)
-				}
+				Log::Pre(PreLog::Block(block)) => write_block(block),
 				Log::Post(post_log) => match post_log {
 					PostLog::Hashes(hashes) => {
+						if let Some(block) = current_block() {
+							backend.bloom_bits().write_block_bloom(
+								number,
+								&substrate_block_hash,
+								&block.header.logs_bloom,
+							)?;
+						}
 						let mapping_commitment = gen_from_hashes(hashes);
 						backend.mapping().write_hashes(mapping_commitment)
 					}
-					PostLog::Block(block) => {
-						let mapping_commitment = gen_from_block(block);
-						backend.mapping().write_hashes(mapping_commitment)
-					}
+					PostLog::Block(block) => write_block(block),
 					PostLog::BlockHash(expect_eth_block_hash) => {
-						let schema =
-							fc_storage::onchain_storage_schema(client, substrate_block_hash);
-						let ethereum_block = overrides
-							.schemas
-							.get(&schema)
-							.unwrap_or(&overrides.fallback)
-							.current_block(substrate_block_hash);
+						let ethereum_block = current_block();
 						match ethereum_block {
 							Some(block) => {
 								let got_eth_block_hash = block.header.hash();
@@ -94,8 +111,7 @@ where
 										db state ({got_eth_block_hash:?})"
 									))
 								} else {
-									let mapping_commitment = gen_from_block(block);
-									backend.mapping().write_hashes(mapping_commitment)
+									write_block(block)
 								}
 							}
 							None => backend.mapping().write_none(substrate_block_hash),
@@ -162,10 +178,12 @@ pub fn sync_one_block<Block: BlockT, C, BE>(
 	frontier_backend: &fc_db::kv::Backend<Block>,
 	sync_from: <Block::Header as HeaderT>::Number,
 	strategy: SyncStrategy,
+	store_block_data: bool,
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
 	pubsub_notification_sinks: Arc<
 		EthereumBlockNotificationSinks<EthereumBlockNotification<Block>>,
 	>,
+	metrics: Option<&MappingSyncMetrics>,
 ) -> Result<bool, String>
 where
 	C: ProvideRuntimeApi<Block>,
@@ -208,6 +226,15 @@ where
 		}
 	};
 
+	if store_block_data {
+		write_block_data(
+			client,
+			overrides.as_ref(),
+			frontier_backend,
+			operating_header.hash(),
+		)?;
+	}
+
 	if operating_header.number() == &Zero::zero() {
 		sync_genesis_block(client, frontier_backend, &operating_header)?;
 
@@ -227,6 +254,12 @@ where
 			.meta()
 			.write_current_syncing_tips(current_syncing_tips)?;
 	}
+	if let Some(metrics) = metrics {
+		metrics.block_synced(
+			UniqueSaturatedInto::<u64>::unique_saturated_into(*operating_header.number()),
+			UniqueSaturatedInto::<u64>::unique_saturated_into(client.info().best_number),
+		);
+	}
 	// Notify on import and remove closed channels.
 	// Only notify when the node is node in major syncing.
 	let sinks = &mut pubsub_notification_sinks.lock();
@@ -252,10 +285,12 @@ pub fn sync_blocks<Block: BlockT, C, BE>(
 	limit: usize,
 	sync_from: <Block::Header as HeaderT>::Number,
 	strategy: SyncStrategy,
+	store_block_data: bool,
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
 	pubsub_notification_sinks: Arc<
 		EthereumBlockNotificationSinks<EthereumBlockNotification<Block>>,
 	>,
+	metrics: Option<&MappingSyncMetrics>,
 ) -> Result<bool, String>
 where
 	C: ProvideRuntimeApi<Block>,
@@ -274,14 +309,56 @@ where
 				frontier_backend,
 				sync_from,
 				strategy,
+				store_block_data,
 				sync_oracle.clone(),
 				pubsub_notification_sinks.clone(),
+				metrics,
 			)?;
 	}
 
 	Ok(synced_any)
 }
 
+/// Keeps the Ethereum data of the block in the frontier backend, while its state is available.
+fn write_block_data<Block: BlockT, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<Block>,
+	frontier_backend: &fc_db::kv::Backend<Block>,
+	block_hash: Block::Hash,
+) -> Result<(), String>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: EthereumRuntimeRPCApi<Block>,
+	C: HeaderBackend<Block> + StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	let schema = fc_storage::onchain_storage_schema(client, block_hash);
+	let handler = overrides
+		.schemas
+		.get(&schema)
+		.unwrap_or(&overrides.fallback);
+	if let (Some(block), Some(receipts), Some(statuses)) = (
+		handler.current_block(block_hash),
+		handler.current_receipts(block_hash),
+		handler.current_transaction_statuses(block_hash),
+	) {
+		frontier_backend.mapping().write_block_data(
+			&block_hash,
+			&fc_db::BlockData {
+				block,
+				receipts,
+				statuses,
+				is_eip1559: handler.is_eip1559(block_hash),
+				base_fee: client
+					.runtime_api()
+					.gas_price(block_hash)
+					.unwrap_or_default(),
+			},
+		)?;
+	}
+	Ok(())
+}
+
 pub fn fetch_header<Block: BlockT, BE>(
 	substrate_backend: &BE,
 	frontier_backend: &fc_db::kv::Backend<Block>,
@@ -303,3 +380,88 @@ where
 		Ok(None) | Err(_) => Err("Header not found".to_string()),
 	}
 }
+
+/// Indexes the bloom bits of the next section of blocks once all of them are finalized. Returns
+/// whether a section was processed.
+pub fn sync_bloom_bits<Block: BlockT, C, BE>(
+	client: &C,
+	overrides: Arc<OverrideHandle<Block>>,
+	frontier_backend: &fc_db::kv::Backend<Block>,
+) -> Result<bool, String>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	let section_size = fc_db::kv::BLOOM_BITS_SECTION_SIZE;
+	let section = frontier_backend.bloom_bits().next_section()?;
+	let first_number = section * section_size;
+	let last_number = first_number + section_size - 1;
+	if last_number
+		> UniqueSaturatedInto::<u64>::unique_saturated_into(client.info().finalized_number)
+	{
+		return Ok(false);
+	}
+
+	let mut block_hashes = Vec::with_capacity(section_size as usize);
+	let mut blooms = Some(Vec::with_capacity(section_size as usize));
+	for number in first_number..=last_number {
+		let block_hash = client
+			.hash(number.unique_saturated_into())
+			.map_err(|e| format!("{:?}", e))?;
+		let bloom = match block_hash {
+			Some(block_hash) => {
+				block_hashes.push(block_hash);
+				block_bloom(client, overrides.as_ref(), frontier_backend, block_hash)?
+			}
+			None => None,
+		};
+		match (blooms.as_mut(), bloom) {
+			(Some(blooms), Some(bloom)) => blooms.push(bloom),
+			(Some(_), None) => {
+				log::warn!(
+					target: "mapping-sync",
+					"Logs bloom of block #{} not available, skipping bloom bits section {}",
+					number,
+					section,
+				);
+				blooms = None;
+			}
+			(None, _) => (),
+		}
+	}
+
+	frontier_backend
+		.bloom_bits()
+		.write_section(section, &block_hashes, blooms.as_deref())?;
+	Ok(true)
+}
+
+fn block_bloom<Block: BlockT, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<Block>,
+	frontier_backend: &fc_db::kv::Backend<Block>,
+	block_hash: Block::Hash,
+) -> Result<Option<Bloom>, String>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	// Kept by the mapping sync, when the state of the block was available.
+	if let Some(bloom) = frontier_backend.bloom_bits().block_bloom(&block_hash)? {
+		return Ok(Some(bloom));
+	}
+	let header = client
+		.header(block_hash)
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Header not found".to_string())?;
+	if let Err(FindLogError::NotFound) = fp_consensus::find_log(header.digest()) {
+		return Ok(Some(Bloom::default()));
+	}
+	let schema = fc_storage::onchain_storage_schema(client, block_hash);
+	Ok(overrides
+		.schemas
+		.get(&schema)
+		.unwrap_or(&overrides.fallback)
+		.current_block(block_hash)
+		.map(|block| block.header.logs_bloom))
+}
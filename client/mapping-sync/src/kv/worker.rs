@@ -37,7 +37,7 @@ use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use fc_storage::OverrideHandle;
 use fp_rpc::EthereumRuntimeRPCApi;
 
-use crate::SyncStrategy;
+use crate::{MappingSyncMetrics, SyncStrategy};
 
 pub struct MappingSyncWorker<Block: BlockT, C, BE> {
 	import_notifications: ImportNotifications<Block>,
@@ -53,10 +53,12 @@ pub struct MappingSyncWorker<Block: BlockT, C, BE> {
 	retry_times: usize,
 	sync_from: <Block::Header as HeaderT>::Number,
 	strategy: SyncStrategy,
+	store_block_data: bool,
 
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
 	pubsub_notification_sinks:
 		Arc<crate::EthereumBlockNotificationSinks<crate::EthereumBlockNotification<Block>>>,
+	metrics: Option<MappingSyncMetrics>,
 }
 
 impl<Block: BlockT, C, BE> Unpin for MappingSyncWorker<Block, C, BE> {}
@@ -72,10 +74,12 @@ impl<Block: BlockT, C, BE> MappingSyncWorker<Block, C, BE> {
 		retry_times: usize,
 		sync_from: <Block::Header as HeaderT>::Number,
 		strategy: SyncStrategy,
+		store_block_data: bool,
 		sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
 		pubsub_notification_sinks: Arc<
 			crate::EthereumBlockNotificationSinks<crate::EthereumBlockNotification<Block>>,
 		>,
+		prometheus_registry: Option<prometheus_endpoint::Registry>,
 	) -> Self {
 		Self {
 			import_notifications,
@@ -91,9 +95,11 @@ impl<Block: BlockT, C, BE> MappingSyncWorker<Block, C, BE> {
 			retry_times,
 			sync_from,
 			strategy,
+			store_block_data,
 
 			sync_oracle,
 			pubsub_notification_sinks,
+			metrics: MappingSyncMetrics::from_registry(prometheus_registry),
 		}
 	}
 }
@@ -145,15 +151,29 @@ where
 				self.retry_times,
 				self.sync_from,
 				self.strategy,
+				self.store_block_data,
 				self.sync_oracle.clone(),
 				self.pubsub_notification_sinks.clone(),
+				self.metrics.as_ref(),
 			) {
 				Ok(have_next) => {
-					self.have_next = have_next;
+					let indexed_section = crate::kv::sync_bloom_bits(
+						self.client.as_ref(),
+						self.overrides.clone(),
+						self.frontier_backend.as_ref(),
+					)
+					.unwrap_or_else(|e| {
+						debug!(target: "mapping-sync", "Bloom bits indexing failed with error {:?}, retrying.", e);
+						false
+					});
+					self.have_next = have_next || indexed_section;
 					Poll::Ready(Some(()))
 				}
 				Err(e) => {
 					self.have_next = false;
+					if let Some(metrics) = &self.metrics {
+						metrics.sync_failed();
+					}
 					debug!(target: "mapping-sync", "Syncing failed with error {:?}, retrying.", e);
 					Poll::Ready(Some(()))
 				}
@@ -292,8 +312,10 @@ mod tests {
 				3,
 				0,
 				SyncStrategy::Normal,
+				false,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.for_each(|()| future::ready(()))
 			.await
@@ -430,8 +452,10 @@ mod tests {
 				3,
 				0,
 				SyncStrategy::Normal,
+				false,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.for_each(|()| future::ready(()))
 			.await
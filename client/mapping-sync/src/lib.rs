@@ -20,9 +20,12 @@
 #![allow(clippy::too_many_arguments)]
 
 pub mod kv;
+mod metrics;
 #[cfg(feature = "sql")]
 pub mod sql;
 
+pub use self::metrics::MappingSyncMetrics;
+
 use sp_api::BlockT;
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+
+/// Prometheus metrics of the mapping sync workers.
+#[derive(Clone)]
+pub struct MappingSyncMetrics {
+	synced_blocks: Counter<U64>,
+	sync_lag: Gauge<U64>,
+	sync_errors: Counter<U64>,
+}
+
+impl MappingSyncMetrics {
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			synced_blocks: register(
+				Counter::new(
+					"frontier_mapping_sync_synced_blocks",
+					"Number of blocks synced by the mapping sync worker.",
+				)?,
+				registry,
+			)?,
+			sync_lag: register(
+				Gauge::new(
+					"frontier_mapping_sync_lag",
+					"Distance from the best block of the last block synced by the mapping sync worker.",
+				)?,
+				registry,
+			)?,
+			sync_errors: register(
+				Counter::new(
+					"frontier_mapping_sync_errors",
+					"Number of failed mapping sync attempts.",
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Registers the metrics when a registry is given, logging registration failures.
+	pub(crate) fn from_registry(registry: Option<Registry>) -> Option<Self> {
+		match Self::register(&registry?) {
+			Ok(metrics) => Some(metrics),
+			Err(e) => {
+				log::error!(target: "mapping-sync", "Failed to register metrics: {:?}", e);
+				None
+			}
+		}
+	}
+
+	pub(crate) fn block_synced(&self, number: u64, best_number: u64) {
+		self.synced_blocks.inc();
+		self.sync_lag.set(best_number.saturating_sub(number));
+	}
+
+	pub(crate) fn sync_failed(&self) {
+		self.sync_errors.inc();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use prometheus_endpoint::prometheus::proto::MetricFamily;
+
+	fn metric<'a>(families: &'a [MetricFamily], name: &str) -> &'a MetricFamily {
+		families
+			.iter()
+			.find(|family| family.get_name() == name)
+			.expect("metric is registered")
+	}
+
+	#[test]
+	fn block_synced_and_sync_failed_update_the_metrics() {
+		let registry = Registry::new();
+		let metrics = MappingSyncMetrics::register(&registry).unwrap();
+
+		metrics.block_synced(8, 10);
+		metrics.block_synced(10, 10);
+		metrics.sync_failed();
+
+		let families = registry.gather();
+		let value = |name| {
+			let family = metric(&families, name);
+			let metric = &family.get_metric()[0];
+			if name == "frontier_mapping_sync_lag" {
+				metric.get_gauge().get_value()
+			} else {
+				metric.get_counter().get_value()
+			}
+		};
+		assert_eq!(value("frontier_mapping_sync_synced_blocks"), 2.0);
+		assert_eq!(value("frontier_mapping_sync_lag"), 0.0);
+		assert_eq!(value("frontier_mapping_sync_errors"), 1.0);
+	}
+
+	#[test]
+	fn registering_twice_fails() {
+		let registry = Registry::new();
+		assert!(MappingSyncMetrics::register(&registry).is_ok());
+		assert!(MappingSyncMetrics::register(&registry).is_err());
+		assert!(MappingSyncMetrics::from_registry(Some(registry)).is_none());
+	}
+}
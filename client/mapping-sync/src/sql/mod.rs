@@ -29,7 +29,9 @@ use sp_runtime::traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto};
 // Frontier
 use fp_rpc::EthereumRuntimeRPCApi;
 
-use crate::{EthereumBlockNotification, EthereumBlockNotificationSinks, SyncStrategy};
+use crate::{
+	EthereumBlockNotification, EthereumBlockNotificationSinks, MappingSyncMetrics, SyncStrategy,
+};
 
 /// Defines the commands for the sync worker.
 #[derive(Debug)]
@@ -81,6 +83,7 @@ where
 		pubsub_notification_sinks: Arc<
 			EthereumBlockNotificationSinks<EthereumBlockNotification<Block>>,
 		>,
+		metrics: Option<MappingSyncMetrics>,
 	) -> tokio::sync::mpsc::Sender<WorkerCommand> {
 		let (tx, mut rx) = tokio::sync::mpsc::channel(100);
 		tokio::task::spawn(async move {
@@ -103,6 +106,7 @@ where
 										substrate_backend.clone(),
 										indexer_backend.clone(),
 										parent_hash,
+										metrics.as_ref(),
 									)
 									.await;
 								}
@@ -119,6 +123,7 @@ where
 								substrate_backend.clone(),
 								indexer_backend.clone(),
 								leaf,
+								metrics.as_ref(),
 							)
 							.await;
 						}
@@ -129,6 +134,7 @@ where
 							substrate_backend.clone(),
 							indexer_backend.clone(),
 							block_hash,
+							metrics.as_ref(),
 						)
 						.await;
 						let sinks = &mut pubsub_notification_sinks.lock();
@@ -156,6 +162,13 @@ where
 							indexer_backend
 								.index_block_logs(client.clone(), block_hash)
 								.await;
+							indexer_backend.index_block_traces(block_hash).await;
+							indexer_backend
+								.index_block_data(client.clone(), block_hash)
+								.await;
+							indexer_backend
+								.index_block_addresses(client.clone(), block_hash)
+								.await;
 						}
 
 						// Fix any missing blocks
@@ -163,6 +176,7 @@ where
 							client.clone(),
 							substrate_backend.clone(),
 							indexer_backend.clone(),
+							metrics.as_ref(),
 						)
 						.await;
 					}
@@ -185,12 +199,14 @@ where
 		pubsub_notification_sinks: Arc<
 			EthereumBlockNotificationSinks<EthereumBlockNotification<Block>>,
 		>,
+		prometheus_registry: Option<prometheus_endpoint::Registry>,
 	) {
 		let tx = Self::spawn_worker(
 			client.clone(),
 			substrate_backend.clone(),
 			indexer_backend.clone(),
 			pubsub_notification_sinks.clone(),
+			MappingSyncMetrics::from_registry(prometheus_registry),
 		)
 		.await;
 
@@ -265,6 +281,33 @@ where
 	}
 }
 
+/// Index the block metadata, logs, traces, data and addresses of the provided block. Only the
+/// failure to insert the block metadata is reported.
+pub async fn index_block<Block, Backend, Client>(
+	client: Arc<Client>,
+	indexer_backend: &fc_db::sql::Backend<Block>,
+	hash: H256,
+) -> Result<(), String>
+where
+	Block: BlockT<Hash = H256>,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: EthereumRuntimeRPCApi<Block>,
+	Client: HeaderBackend<Block> + StorageProvider<Block, Backend> + 'static,
+	Backend: BackendT<Block> + 'static,
+	Backend::State: StateBackend<BlakeTwo256>,
+{
+	let inserted = indexer_backend
+		.insert_block_metadata(client.clone(), hash)
+		.await
+		.map_err(|e| e.to_string());
+	log::debug!(target: "frontier-sql", "Inserted block metadata {hash:?}");
+	indexer_backend.index_block_logs(client.clone(), hash).await;
+	indexer_backend.index_block_traces(hash).await;
+	indexer_backend.index_block_data(client.clone(), hash).await;
+	indexer_backend.index_block_addresses(client, hash).await;
+	inserted
+}
+
 /// Index the provided blocks. The function loops over the ancestors of the provided nodes
 /// until it encounters the genesis block, or a block that has already been imported, or
 /// is already in the active set. The `hashes` parameter is populated with any parent blocks
@@ -274,6 +317,7 @@ async fn index_block_and_ancestors<Block, Backend, Client>(
 	substrate_backend: Arc<Backend>,
 	indexer_backend: Arc<fc_db::sql::Backend<Block>>,
 	hash: H256,
+	metrics: Option<&MappingSyncMetrics>,
 ) where
 	Block: BlockT<Hash = H256>,
 	Client: ProvideRuntimeApi<Block>,
@@ -297,16 +341,23 @@ async fn index_block_and_ancestors<Block, Backend, Client>(
 		}
 
 		log::debug!(target: "frontier-sql", "🛠️  Importing {hash:?}");
-		let _ = indexer_backend
-			.insert_block_metadata(client.clone(), hash)
+		let inserted = index_block(client.clone(), indexer_backend.as_ref(), hash)
 			.await
 			.map_err(|e| {
 				log::error!(target: "frontier-sql", "{e}");
-			});
-		log::debug!(target: "frontier-sql", "Inserted block metadata");
-		indexer_backend.index_block_logs(client.clone(), hash).await;
+				if let Some(metrics) = metrics {
+					metrics.sync_failed();
+				}
+			})
+			.is_ok();
 
 		if let Ok(Some(header)) = blockchain_backend.header(hash) {
+			if let (true, Some(metrics)) = (inserted, metrics) {
+				metrics.block_synced(
+					UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number()),
+					UniqueSaturatedInto::<u64>::unique_saturated_into(client.info().best_number),
+				);
+			}
 			let parent_hash = header.parent_hash();
 			hashes.push(*parent_hash);
 		}
@@ -322,6 +373,7 @@ async fn index_canonical_block_and_ancestors<Block, Backend, Client>(
 	substrate_backend: Arc<Backend>,
 	indexer_backend: Arc<fc_db::sql::Backend<Block>>,
 	hash: H256,
+	metrics: Option<&MappingSyncMetrics>,
 ) where
 	Block: BlockT<Hash = H256>,
 	Client: ProvideRuntimeApi<Block>,
@@ -365,16 +417,23 @@ async fn index_canonical_block_and_ancestors<Block, Backend, Client>(
 
 		// Else, import the new block
 		log::debug!(target: "frontier-sql", "🛠️  Importing {hash:?}");
-		let _ = indexer_backend
-			.insert_block_metadata(client.clone(), hash)
+		let inserted = index_block(client.clone(), indexer_backend.as_ref(), hash)
 			.await
 			.map_err(|e| {
 				log::error!(target: "frontier-sql", "{e}");
-			});
-		log::debug!(target: "frontier-sql", "Inserted block metadata  {hash:?}");
-		indexer_backend.index_block_logs(client.clone(), hash).await;
+				if let Some(metrics) = metrics {
+					metrics.sync_failed();
+				}
+			})
+			.is_ok();
 
 		if let Ok(Some(header)) = blockchain_backend.header(hash) {
+			if let (true, Some(metrics)) = (inserted, metrics) {
+				metrics.block_synced(
+					UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number()),
+					UniqueSaturatedInto::<u64>::unique_saturated_into(client.info().best_number),
+				);
+			}
 			let parent_hash = header.parent_hash();
 			hashes.push(*parent_hash);
 		}
@@ -407,6 +466,7 @@ async fn index_missing_blocks<Block, Client, Backend>(
 	client: Arc<Client>,
 	substrate_backend: Arc<Backend>,
 	indexer_backend: Arc<fc_db::sql::Backend<Block>>,
+	metrics: Option<&MappingSyncMetrics>,
 ) where
 	Block: BlockT<Hash = H256>,
 	Client: ProvideRuntimeApi<Block>,
@@ -431,6 +491,7 @@ async fn index_missing_blocks<Block, Client, Backend>(
 				substrate_backend.clone(),
 				indexer_backend.clone(),
 				block_hash,
+				metrics,
 			)
 			.await;
 		} else {
@@ -579,6 +640,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -587,7 +650,11 @@ mod test {
 		.await
 		.expect("indexer pool to be created");
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Create 10 blocks, 2 receipts each, 1 log per receipt
 		let mut logs: Vec<(i32, fc_db::sql::Log)> = vec![];
@@ -691,6 +758,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.await
 		});
@@ -785,6 +853,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -793,7 +863,11 @@ mod test {
 		.await
 		.expect("indexer pool to be created");
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		let test_sync_oracle = TestSyncOracleNotSyncing {};
 		let pubsub_notification_sinks: EthereumBlockNotificationSinks<
@@ -821,6 +895,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.await
 		});
@@ -992,6 +1067,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1001,7 +1078,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let test_sync_oracle = TestSyncOracleNotSyncing {};
@@ -1027,6 +1108,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.await
 		});
@@ -1161,6 +1243,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1170,7 +1254,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Create 5 blocks, storing them newest first.
 		let mut parent_hash = client
@@ -1230,6 +1318,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
 				pubsub_notification_sinks_inner,
+				None,
 			)
 			.await
 		});
@@ -1310,6 +1399,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1319,7 +1410,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1342,6 +1437,7 @@ mod test {
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
@@ -1416,6 +1512,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1425,7 +1523,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1448,6 +1550,7 @@ mod test {
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
@@ -1533,6 +1636,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1542,7 +1647,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1565,6 +1674,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
@@ -1639,6 +1749,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1648,7 +1760,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1671,6 +1787,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
@@ -1756,6 +1873,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1765,7 +1884,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1788,6 +1911,7 @@ mod test {
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
@@ -1862,6 +1986,8 @@ mod test {
 				create_if_missing: true,
 				cache_size: 204800,
 				thread_count: 4,
+				store_block_data: false,
+				index_addresses: false,
 			}),
 			100,
 			None,
@@ -1871,7 +1997,11 @@ mod test {
 		.expect("indexer pool to be created");
 
 		// Pool
-		let pool = indexer_backend.pool().clone();
+		let pool = indexer_backend
+			.pool()
+			.as_sqlite()
+			.expect("sqlite backend")
+			.clone();
 
 		// Spawn indexer task
 		let pubsub_notification_sinks: crate::EthereumBlockNotificationSinks<
@@ -1894,6 +2024,7 @@ mod test {
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
 				pubsub_notification_sinks.clone(),
+				None,
 			)
 			.await
 		});
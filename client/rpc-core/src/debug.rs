@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Debug rpc interface.
+
+use ethereum_types::H256;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::*;
+
+/// Debug rpc interface.
+#[rpc(server)]
+#[async_trait]
+pub trait DebugApi {
+	/// Replays a transaction in the state of its block and returns its trace.
+	#[method(name = "debug_traceTransaction")]
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace>;
+
+	/// Executes a call on top of the given block and returns its trace.
+	#[method(name = "debug_traceCall")]
+	async fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace>;
+
+	/// Replays the transactions of a block in the state of its parent and returns their traces.
+	#[method(name = "debug_traceBlockByNumber")]
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumber,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>>;
+
+	/// Replays the transactions of a block in the state of its parent and returns their traces.
+	#[method(name = "debug_traceBlockByHash")]
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>>;
+}
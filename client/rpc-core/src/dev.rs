@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Dev rpc interface, compatible with the Anvil and Hardhat `evm_*`/`anvil_*` methods.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::*;
+
+/// Dev rpc interface, only available on manual seal nodes.
+#[rpc(server)]
+#[async_trait]
+pub trait DevApi {
+	/// Seals the given number of blocks (one by default, up to 1000).
+	#[method(name = "evm_mine")]
+	async fn mine(&self, blocks: Option<Index>) -> RpcResult<String>;
+
+	/// Moves the clock of the next blocks forward and returns the total offset in seconds.
+	#[method(name = "evm_increaseTime")]
+	fn increase_time(&self, seconds: Index) -> RpcResult<u64>;
+
+	/// Sets the timestamp, in seconds, of the next sealed block.
+	#[method(name = "evm_setNextBlockTimestamp")]
+	fn set_next_block_timestamp(&self, timestamp: Index) -> RpcResult<()>;
+
+	/// Records the current chain head and returns an id to revert to it.
+	#[method(name = "evm_snapshot")]
+	fn snapshot(&self) -> RpcResult<U256>;
+
+	/// Reverts the chain to a snapshot, dropping it and every later one.
+	#[method(name = "evm_revert")]
+	async fn revert(&self, id: U256) -> RpcResult<bool>;
+
+	/// Lets `eth_sendTransaction` send transactions from the given account without its key.
+	#[method(
+		name = "anvil_impersonateAccount",
+		aliases = ["hardhat_impersonateAccount"]
+	)]
+	fn impersonate_account(&self, address: H160) -> RpcResult<()>;
+
+	/// Stops impersonating the given account.
+	#[method(
+		name = "anvil_stopImpersonatingAccount",
+		aliases = ["hardhat_stopImpersonatingAccount"]
+	)]
+	fn stop_impersonating_account(&self, address: H160) -> RpcResult<()>;
+
+	/// Sets the balance of an account and seals a block.
+	#[method(name = "anvil_setBalance")]
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()>;
+
+	/// Sets the nonce of an account and seals a block.
+	#[method(name = "anvil_setNonce")]
+	async fn set_nonce(&self, address: H160, nonce: U256) -> RpcResult<()>;
+
+	/// Sets the code of an account and seals a block.
+	#[method(name = "anvil_setCode")]
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()>;
+
+	/// Sets a storage slot of an account and seals a block.
+	#[method(name = "anvil_setStorageAt")]
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool>;
+}
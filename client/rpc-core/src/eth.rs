@@ -166,6 +166,16 @@ pub trait EthApi {
 	#[method(name = "eth_getCode")]
 	async fn code_at(&self, address: H160, number: Option<BlockNumber>) -> RpcResult<Bytes>;
 
+	/// Returns the EIP-1186 account and storage proofs of the given address at given time
+	/// (block number). The proofs are Substrate trie read proofs of the block's state root.
+	#[method(name = "eth_getProof")]
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> RpcResult<EthAccount>;
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
@@ -177,6 +187,7 @@ pub trait EthApi {
 		request: CallRequest,
 		number: Option<BlockNumber>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes>;
 
 	/// Estimate gas needed for execution of given contract.
@@ -185,8 +196,28 @@ pub trait EthApi {
 		&self,
 		request: CallRequest,
 		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256>;
 
+	/// Simulates calls across one or more blocks, each call seeing the state left by the
+	/// previous ones.
+	#[method(name = "eth_simulateV1")]
+	async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> RpcResult<Vec<SimulatedBlock>>;
+
+	/// Creates an EIP-2930 access list for the given call, returning it along with the gas the
+	/// call uses when sent with it.
+	#[method(name = "eth_createAccessList")]
+	async fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> RpcResult<AccessListResult>;
+
 	// ########################################################################
 	// Fee
 	// ########################################################################
@@ -20,16 +20,24 @@
 
 pub mod types;
 
+mod debug;
+mod dev;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod ots;
+mod trace;
 mod txpool;
 mod web3;
 
 pub use self::{
+	debug::DebugApiServer,
+	dev::DevApiServer,
 	eth::{EthApiServer, EthFilterApiServer},
 	eth_pubsub::EthPubSubApiServer,
 	net::NetApiServer,
+	ots::OtsApiServer,
+	trace::TraceApiServer,
 	txpool::TxPoolApiServer,
 	web3::Web3ApiServer,
 };
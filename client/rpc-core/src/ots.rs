@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Otterscan rpc interface.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::*;
+
+/// Otterscan rpc interface, searching the addresses indexed by the SQL backend.
+#[rpc(server)]
+#[async_trait]
+pub trait OtsApi {
+	/// Returns the version of the Otterscan API implemented by the node.
+	#[method(name = "ots_getApiLevel")]
+	fn get_api_level(&self) -> RpcResult<u64>;
+
+	/// Returns whether the address holds code at the block.
+	#[method(name = "ots_hasCode")]
+	async fn has_code(&self, address: H160, number: BlockNumber) -> RpcResult<bool>;
+
+	/// Returns the block with its transaction count in place of its transactions, and its fees.
+	#[method(name = "ots_getBlockDetails")]
+	async fn get_block_details(&self, number: BlockNumber) -> RpcResult<Option<BlockDetails>>;
+
+	/// Returns the hash of the transaction sent by the sender with the nonce.
+	#[method(name = "ots_getTransactionBySenderAndNonce")]
+	async fn get_transaction_by_sender_and_nonce(
+		&self,
+		sender: H160,
+		nonce: U256,
+	) -> RpcResult<Option<H256>>;
+
+	/// Returns the creator of the contract and the transaction creating it.
+	#[method(name = "ots_getContractCreator")]
+	async fn get_contract_creator(&self, address: H160) -> RpcResult<Option<ContractCreator>>;
+
+	/// Returns a page of the transactions of the address in the blocks before the block, the most
+	/// recent first. A block number of `0` starts from the best block. The page size must not be
+	/// `0`, and is capped at 25 transactions.
+	#[method(name = "ots_searchTransactionsBefore")]
+	async fn search_transactions_before(
+		&self,
+		address: H160,
+		number: u64,
+		page_size: u64,
+	) -> RpcResult<TransactionsWithReceipts>;
+
+	/// Returns a page of the transactions of the address in the blocks after the block, the most
+	/// recent first. The page size must not be `0`, and is capped at 25 transactions.
+	#[method(name = "ots_searchTransactionsAfter")]
+	async fn search_transactions_after(
+		&self,
+		address: H160,
+		number: u64,
+		page_size: u64,
+	) -> RpcResult<TransactionsWithReceipts>;
+}
@@ -73,3 +73,21 @@ pub struct CallStateOverride {
 	/// executing the call.
 	pub state_diff: Option<BTreeMap<H256, H256>>,
 }
+
+// Block override
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+	/// Fake block number to execute the call in.
+	pub number: Option<U256>,
+	/// Fake block timestamp, in seconds.
+	#[serde(alias = "timestamp")]
+	pub time: Option<U256>,
+	/// Fake block author.
+	pub coinbase: Option<H160>,
+	/// Fake block base fee per gas.
+	pub base_fee: Option<U256>,
+	/// Fake block gas limit.
+	pub gas_limit: Option<U256>,
+}
@@ -18,6 +18,7 @@
 
 //! RPC types
 
+mod access_list;
 mod account_info;
 mod block;
 mod block_number;
@@ -27,8 +28,12 @@ mod fee;
 mod filter;
 mod index;
 mod log;
+mod ots;
 mod receipt;
+mod simulate;
 mod sync;
+mod trace;
+mod trace_filter;
 mod transaction;
 mod transaction_request;
 mod txpool;
@@ -37,11 +42,12 @@ mod work;
 pub mod pubsub;
 
 pub use self::{
+	access_list::AccessListResult,
 	account_info::{AccountInfo, EthAccount, ExtAccountInfo, RecoveredAccount, StorageProof},
 	block::{Block, BlockTransactions, Header, Rich, RichBlock, RichHeader},
 	block_number::BlockNumber,
 	bytes::Bytes,
-	call_request::{CallRequest, CallStateOverride},
+	call_request::{BlockOverrides, CallRequest, CallStateOverride},
 	fee::{FeeHistory, FeeHistoryCache, FeeHistoryCacheItem, FeeHistoryCacheLimit},
 	filter::{
 		Filter, FilterAddress, FilterChanges, FilterPool, FilterPoolItem, FilterType,
@@ -49,11 +55,24 @@ pub use self::{
 	},
 	index::Index,
 	log::Log,
+	ots::{
+		BlockDetails, BlockSummary, ContractCreator, Issuance, ReceiptWithTimestamp,
+		TransactionsWithReceipts,
+	},
 	receipt::Receipt,
+	simulate::{SimulateBlock, SimulateCallError, SimulatePayload, SimulatedBlock, SimulatedCall},
 	sync::{
 		ChainStatus, EthProtocolInfo, PeerCount, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 		Peers, PipProtocolInfo, SyncInfo, SyncStatus, TransactionStats,
 	},
+	trace::{
+		AccountState, CallFrame, PrestateTrace, StructLog, StructLogsTrace, Trace, TraceParams,
+		TracerOptions, TransactionTrace,
+	},
+	trace_filter::{
+		CallAction, CreateAction, LocalizedTrace, SuicideAction, TraceAction, TraceFilter,
+		TraceResult,
+	},
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
 	transaction_request::{TransactionMessage, TransactionRequest},
 	txpool::{Get, Summary, TransactionMap, TxPoolResult, TxPoolTransaction},
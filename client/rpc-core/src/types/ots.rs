@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use serde::Serialize;
+
+use crate::types::{Receipt, RichBlock, Transaction};
+
+/// Result of the `ots_getBlockDetails` method.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDetails {
+	pub block: BlockSummary,
+	pub issuance: Issuance,
+	/// Sum of the fees paid by the transactions of the block.
+	pub total_fees: U256,
+}
+
+/// A block without its transactions.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockSummary {
+	#[serde(flatten)]
+	pub block: RichBlock,
+	pub transaction_count: usize,
+}
+
+/// Rewards minted by a block, always zero as blocks are not mined.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Issuance {
+	pub block_reward: U256,
+	pub uncle_reward: U256,
+	pub issuance: U256,
+}
+
+/// Result of the `ots_getContractCreator` method.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ContractCreator {
+	/// Hash of the transaction creating the contract.
+	pub hash: H256,
+	pub creator: H160,
+}
+
+/// A page of the transactions of an address, with their receipts.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsWithReceipts {
+	pub txs: Vec<Transaction>,
+	pub receipts: Vec<ReceiptWithTimestamp>,
+	/// Whether the page holds the most recent transactions.
+	pub first_page: bool,
+	/// Whether the page holds the oldest transactions.
+	pub last_page: bool,
+}
+
+/// A receipt, with the timestamp of its block in seconds.
+#[derive(Debug, Serialize)]
+pub struct ReceiptWithTimestamp {
+	#[serde(flatten)]
+	pub receipt: Receipt,
+	pub timestamp: u64,
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockOverrides, Bytes, CallRequest, CallStateOverride, Log};
+
+/// Payload of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// Blocks to simulate, in order.
+	pub block_state_calls: Vec<SimulateBlock>,
+	/// Whether to run the calls with the checks of a real transaction. Not supported.
+	#[serde(default)]
+	pub validation: bool,
+}
+
+/// Block of calls to simulate.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// Overrides of the simulated block environment.
+	pub block_overrides: Option<BlockOverrides>,
+	/// State overrides applied before the calls of the block.
+	pub state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	/// Calls executed in the block, each seeing the state left by the previous ones.
+	#[serde(default)]
+	pub calls: Vec<CallRequest>,
+}
+
+/// Result of a simulated block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+	/// Block number
+	pub number: U256,
+	/// Hash of the simulated block header
+	pub hash: H256,
+	/// Hash of the parent block
+	pub parent_hash: H256,
+	/// Timestamp, in seconds
+	pub timestamp: U256,
+	/// Gas limit
+	pub gas_limit: U256,
+	/// Gas used by the calls
+	pub gas_used: U256,
+	/// Block author
+	pub miner: H160,
+	/// Base fee per gas
+	pub base_fee_per_gas: U256,
+	/// Results of the calls
+	pub calls: Vec<SimulatedCall>,
+}
+
+/// Result of a simulated call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+	/// Returned data, or the deployed code for contract creations
+	pub return_data: Bytes,
+	/// Logs emitted by the call
+	pub logs: Vec<Log>,
+	/// Gas used
+	pub gas_used: U256,
+	/// 1 if the call succeeded, 0 otherwise
+	pub status: U64,
+	/// Why the call failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulateCallError>,
+}
+
+/// Error of a failed simulated call.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SimulateCallError {
+	/// Error code, 3 for reverts
+	pub code: i32,
+	/// Error message
+	pub message: String,
+	/// Data returned by a revert
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub data: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn simulate_payload_deserialize() {
+		let payload: SimulatePayload = serde_json::from_str(
+			r#"{
+				"blockStateCalls": [
+					{
+						"blockOverrides": { "number": "0x10" },
+						"stateOverrides": {
+							"0x0000000000000000000000000000000000000001": { "balance": "0x1" }
+						},
+						"calls": [
+							{ "from": "0x0000000000000000000000000000000000000002", "data": "0x01" }
+						]
+					},
+					{}
+				]
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(payload.block_state_calls.len(), 2);
+		assert!(!payload.validation);
+		let block = &payload.block_state_calls[0];
+		assert_eq!(
+			block.block_overrides.as_ref().unwrap().number,
+			Some(U256::from(16))
+		);
+		assert_eq!(block.state_overrides.as_ref().unwrap().len(), 1);
+		assert_eq!(block.calls[0].data, Some(Bytes(vec![1])));
+		assert!(payload.block_state_calls[1].calls.is_empty());
+	}
+
+	#[test]
+	fn simulated_call_serialize() {
+		let call = SimulatedCall {
+			return_data: Bytes(vec![]),
+			logs: vec![],
+			gas_used: U256::from(21000),
+			status: U64::from(0),
+			error: Some(SimulateCallError {
+				code: 3,
+				message: "VM Exception while processing transaction: revert".into(),
+				data: Some(Bytes(vec![])),
+			}),
+		};
+
+		assert_eq!(
+			serde_json::to_string(&call).unwrap(),
+			r#"{"returnData":"0x","logs":[],"gasUsed":"0x5208","status":"0x0","error":{"code":3,"message":"VM Exception while processing transaction: revert","data":"0x"}}"#
+		);
+	}
+}
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bytes;
+
+/// Options of the `debug_trace*` methods, as accepted by geth.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+	/// Name of the tracer, the struct logger is used if not set.
+	pub tracer: Option<String>,
+	/// Options of the named tracer.
+	pub tracer_config: Option<TracerOptions>,
+	pub disable_stack: Option<bool>,
+	pub disable_memory: Option<bool>,
+	pub enable_memory: Option<bool>,
+	pub disable_storage: Option<bool>,
+	pub enable_return_data: Option<bool>,
+	/// Accepted for compatibility, tracing is not interrupted.
+	pub timeout: Option<String>,
+}
+
+/// Options of the named tracers.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerOptions {
+	/// `callTracer`: only return the top-level call.
+	pub only_top_call: Option<bool>,
+	/// `prestateTracer`: return the state before and after the execution.
+	pub diff_mode: Option<bool>,
+}
+
+/// Result of a `debug_trace*` method.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Trace {
+	StructLogs(StructLogsTrace),
+	CallTracer(CallFrame),
+	Prestate(PrestateTrace),
+}
+
+/// Trace of a transaction of a block, as returned by `debug_traceBlock*`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+	pub tx_hash: H256,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub result: Option<Trace>,
+	/// Set instead of `result` when the transaction could not be traced.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// Output of the struct logger.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogsTrace {
+	pub gas: u64,
+	pub failed: bool,
+	/// Hex encoded, without prefix.
+	pub return_value: String,
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// A single executed opcode.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+	pub pc: u64,
+	pub op: String,
+	pub gas: u64,
+	pub gas_cost: u64,
+	pub depth: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// 32 bytes words, hex encoded without prefix.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Hex encoded slots and values, without prefix.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub return_data: Option<Bytes>,
+}
+
+/// A call frame of the `callTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub to: Option<H160>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Bytes,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub revert_reason: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+/// Output of the `prestateTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	Prestate(BTreeMap<H160, AccountState>),
+	Diff {
+		pre: BTreeMap<H160, AccountState>,
+		post: BTreeMap<H160, AccountState>,
+	},
+}
+
+/// State of an account touched by a transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	#[test]
+	fn trace_params_deserialization() {
+		let s = r#"{"tracer": "callTracer", "tracerConfig": {"onlyTopCall": true}}"#;
+		let deserialized: TraceParams = serde_json::from_str(s).unwrap();
+		assert_eq!(
+			deserialized,
+			TraceParams {
+				tracer: Some("callTracer".into()),
+				tracer_config: Some(TracerOptions {
+					only_top_call: Some(true),
+					..Default::default()
+				}),
+				..Default::default()
+			}
+		);
+	}
+
+	#[test]
+	fn call_frame_serialization() {
+		let frame = CallFrame {
+			call_type: "CALL".into(),
+			from: H160::repeat_byte(0x11),
+			to: Some(H160::repeat_byte(0x22)),
+			value: Some(U256::zero()),
+			gas: U256::from(21000),
+			gas_used: U256::from(21000),
+			input: Bytes(vec![]),
+			output: None,
+			error: None,
+			revert_reason: None,
+			calls: vec![],
+		};
+		assert_eq!(
+			serde_json::to_string(&frame).unwrap(),
+			r#"{"type":"CALL","from":"0x1111111111111111111111111111111111111111","to":"0x2222222222222222222222222222222222222222","value":"0x0","gas":"0x5208","gasUsed":"0x5208","input":"0x"}"#
+		);
+	}
+
+	#[test]
+	fn prestate_diff_serialization() {
+		let trace = PrestateTrace::Diff {
+			pre: BTreeMap::from([(
+				H160::repeat_byte(0x11),
+				AccountState {
+					balance: Some(U256::from(10)),
+					nonce: Some(1),
+					..Default::default()
+				},
+			)]),
+			post: BTreeMap::from([(
+				H160::repeat_byte(0x11),
+				AccountState {
+					nonce: Some(2),
+					storage: BTreeMap::from([(H256::zero(), H256::repeat_byte(0x01))]),
+					..Default::default()
+				},
+			)]),
+		};
+		assert_eq!(
+			serde_json::to_string(&trace).unwrap(),
+			r#"{"pre":{"0x1111111111111111111111111111111111111111":{"balance":"0xa","nonce":1}},"post":{"0x1111111111111111111111111111111111111111":{"nonce":2,"storage":{"0x0000000000000000000000000000000000000000000000000000000000000000":"0x0101010101010101010101010101010101010101010101010101010101010101"}}}}"#
+		);
+	}
+
+	#[test]
+	fn failed_transaction_trace_serialization() {
+		let trace = TransactionTrace {
+			tx_hash: H256::repeat_byte(0x11),
+			result: None,
+			error: Some("execution fatal: Other(\"gas limit reached\")".into()),
+		};
+		assert_eq!(
+			serde_json::to_string(&trace).unwrap(),
+			r#"{"txHash":"0x1111111111111111111111111111111111111111111111111111111111111111","error":"execution fatal: Other(\"gas limit reached\")"}"#
+		);
+	}
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockNumber, Bytes};
+
+/// Filter of the `trace_filter` method.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+	/// From this block, defaults to the best block.
+	pub from_block: Option<BlockNumber>,
+	/// To this block, defaults to the best block.
+	pub to_block: Option<BlockNumber>,
+	/// Sent from these addresses, any address if not set.
+	pub from_address: Option<Vec<H160>>,
+	/// Sent to these addresses, any address if not set.
+	pub to_address: Option<Vec<H160>>,
+	/// Number of traces to skip.
+	pub after: Option<u64>,
+	/// Maximum number of traces to return.
+	pub count: Option<u64>,
+}
+
+/// A call frame, flattened with its position in the transaction's call tree.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+	pub action: TraceAction,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub result: Option<TraceResult>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	pub block_hash: H256,
+	pub block_number: u64,
+	pub transaction_hash: H256,
+	pub transaction_position: u32,
+	pub subtraces: u32,
+	pub trace_address: Vec<u32>,
+	/// `call`, `create` or `suicide`.
+	#[serde(rename = "type")]
+	pub trace_type: String,
+}
+
+/// Action of a call frame.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+	Call(CallAction),
+	Create(CreateAction),
+	Suicide(SuicideAction),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+	pub from: H160,
+	pub to: H160,
+	pub value: U256,
+	pub gas: U256,
+	pub input: Bytes,
+	/// `call`, `callcode`, `delegatecall` or `staticcall`.
+	pub call_type: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+	pub from: H160,
+	pub value: U256,
+	pub gas: U256,
+	pub init: Bytes,
+	/// `create` or `create2`.
+	pub creation_method: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuicideAction {
+	pub address: H160,
+	pub refund_address: H160,
+	pub balance: U256,
+}
+
+/// Result of a successful call frame.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+	Call {
+		#[serde(rename = "gasUsed")]
+		gas_used: U256,
+		output: Bytes,
+	},
+	Create {
+		#[serde(rename = "gasUsed")]
+		gas_used: U256,
+		code: Bytes,
+		address: H160,
+	},
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	#[test]
+	fn trace_filter_deserialization() {
+		let s = r#"{"fromBlock": "0x1", "toBlock": "latest", "toAddress": ["0x1111111111111111111111111111111111111111"], "count": 10}"#;
+		let deserialized: TraceFilter = serde_json::from_str(s).unwrap();
+		assert_eq!(
+			deserialized,
+			TraceFilter {
+				from_block: Some(BlockNumber::Num(1)),
+				to_block: Some(BlockNumber::Latest),
+				to_address: Some(vec![H160::repeat_byte(0x11)]),
+				count: Some(10),
+				..Default::default()
+			}
+		);
+	}
+
+	#[test]
+	fn localized_trace_serialization() {
+		let trace = LocalizedTrace {
+			action: TraceAction::Call(CallAction {
+				from: H160::repeat_byte(0x11),
+				to: H160::repeat_byte(0x22),
+				value: U256::zero(),
+				gas: U256::from(21000),
+				input: Bytes(vec![]),
+				call_type: "call".into(),
+			}),
+			result: Some(TraceResult::Call {
+				gas_used: U256::zero(),
+				output: Bytes(vec![]),
+			}),
+			error: None,
+			block_hash: H256::zero(),
+			block_number: 1,
+			transaction_hash: H256::zero(),
+			transaction_position: 0,
+			subtraces: 0,
+			trace_address: vec![],
+			trace_type: "call".into(),
+		};
+		assert_eq!(
+			serde_json::to_string(&trace).unwrap(),
+			r#"{"action":{"from":"0x1111111111111111111111111111111111111111","to":"0x2222222222222222222222222222222222222222","value":"0x0","gas":"0x5208","input":"0x","callType":"call"},"result":{"gasUsed":"0x0","output":"0x"},"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":1,"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionPosition":0,"subtraces":0,"traceAddress":[],"type":"call"}"#
+		);
+	}
+}
@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of runtime traces to the geth JSON format.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, U256};
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::RpcResult;
+use sp_runtime::SaturatedConversion;
+// Frontier
+use fc_rpc_core::types::{
+	AccountState, Bytes, CallFrame, PrestateTrace, StructLog, StructLogsTrace, Trace, TraceParams,
+	TracerOptions,
+};
+use fp_evm::tracing::{CallTrace, CallType, StructLoggerConfig, TraceResult, TracerConfig};
+
+use crate::err;
+
+/// Geth error code for invalid parameters.
+const INVALID_PARAMS: i32 = -32602;
+
+/// Builds the runtime tracer configuration from the RPC parameters.
+pub fn tracer_config(params: Option<TraceParams>) -> RpcResult<TracerConfig> {
+	let params = params.unwrap_or_default();
+	let TracerOptions {
+		only_top_call,
+		diff_mode,
+	} = params.tracer_config.unwrap_or_default();
+
+	match params.tracer.as_deref() {
+		None => Ok(TracerConfig::StructLogger(StructLoggerConfig {
+			disable_stack: params.disable_stack.unwrap_or(false),
+			// Memory capture is opt-in in recent geth versions, opt-out in older ones.
+			disable_memory: match (params.enable_memory, params.disable_memory) {
+				(Some(enable_memory), _) => !enable_memory,
+				(None, Some(disable_memory)) => disable_memory,
+				(None, None) => true,
+			},
+			disable_storage: params.disable_storage.unwrap_or(false),
+			enable_return_data: params.enable_return_data.unwrap_or(false),
+		})),
+		Some("callTracer") => Ok(TracerConfig::CallTracer {
+			only_top_call: only_top_call.unwrap_or(false),
+		}),
+		Some("prestateTracer") => Ok(TracerConfig::PrestateTracer {
+			diff_mode: diff_mode.unwrap_or(false),
+		}),
+		Some(tracer) => Err(err(
+			INVALID_PARAMS,
+			format!("tracer {} is not supported", tracer),
+			None,
+		)),
+	}
+}
+
+/// Converts a runtime trace to its JSON representation.
+pub fn trace(result: TraceResult) -> Trace {
+	match result {
+		TraceResult::StructLogger(result) => Trace::StructLogs(StructLogsTrace {
+			gas: result.gas.saturated_into(),
+			failed: result.failed,
+			return_value: hex::encode(result.return_value),
+			struct_logs: result
+				.struct_logs
+				.into_iter()
+				.map(|log| StructLog {
+					pc: log.pc,
+					op: opcode_name(log.op),
+					gas: log.gas,
+					gas_cost: log.gas_cost,
+					depth: log.depth,
+					error: log.error.as_ref().map(exit_error),
+					stack: log.stack.map(|stack| {
+						stack
+							.into_iter()
+							.map(|word| U256::from_big_endian(word.as_bytes()))
+							.collect()
+					}),
+					memory: log
+						.memory
+						.map(|memory| memory.chunks(32).map(hex::encode).collect()),
+					storage: log.storage.map(|storage| {
+						storage
+							.into_iter()
+							.map(|(key, value)| (hex::encode(key), hex::encode(value)))
+							.collect()
+					}),
+					return_data: log.return_data.map(Bytes),
+				})
+				.collect(),
+		}),
+		TraceResult::CallTracer(call) => Trace::CallTracer(call_frame(call)),
+		TraceResult::Prestate(prestate) => Trace::Prestate(prestate_trace(prestate)),
+	}
+}
+
+fn prestate_trace(prestate: fp_evm::tracing::PrestateTrace) -> PrestateTrace {
+	let accounts = |accounts: BTreeMap<H160, fp_evm::tracing::AccountState>| {
+		accounts
+			.into_iter()
+			.map(|(address, state)| {
+				let state = AccountState {
+					balance: state.balance,
+					// Like geth, zero nonces are omitted.
+					nonce: state
+						.nonce
+						.map(|nonce| nonce.saturated_into())
+						.filter(|nonce| *nonce != 0),
+					code: state.code.map(Bytes),
+					storage: state.storage,
+				};
+				(address, state)
+			})
+			.collect()
+	};
+
+	match prestate {
+		fp_evm::tracing::PrestateTrace::Prestate(state) => PrestateTrace::Prestate(accounts(state)),
+		fp_evm::tracing::PrestateTrace::Diff { pre, post } => PrestateTrace::Diff {
+			pre: accounts(pre),
+			post: accounts(post),
+		},
+	}
+}
+
+fn call_frame(call: CallTrace) -> CallFrame {
+	let (error, revert_reason) = match &call.exit_reason {
+		Some(ExitReason::Succeed(_)) | None => (None, None),
+		Some(reason @ ExitReason::Revert(_)) => {
+			(Some(exit_error(reason)), revert_reason(&call.output))
+		}
+		Some(reason) => (Some(exit_error(reason)), None),
+	};
+	let value = match call.call_type {
+		CallType::DelegateCall | CallType::StaticCall => None,
+		_ => Some(call.value),
+	};
+	// Only reverts return data on failure.
+	let output = match &call.exit_reason {
+		Some(ExitReason::Succeed(_) | ExitReason::Revert(_)) if !call.output.is_empty() => {
+			Some(Bytes(call.output))
+		}
+		_ => None,
+	};
+
+	CallFrame {
+		call_type: call_type_name(call.call_type).into(),
+		from: call.from,
+		to: Some(call.to),
+		value,
+		gas: call.gas.into(),
+		gas_used: call.gas_used.into(),
+		input: Bytes(call.input),
+		output,
+		error,
+		revert_reason,
+		calls: call.calls.into_iter().map(call_frame).collect(),
+	}
+}
+
+fn call_type_name(call_type: CallType) -> &'static str {
+	match call_type {
+		CallType::Call => "CALL",
+		CallType::CallCode => "CALLCODE",
+		CallType::DelegateCall => "DELEGATECALL",
+		CallType::StaticCall => "STATICCALL",
+		CallType::Create => "CREATE",
+		CallType::Create2 => "CREATE2",
+		CallType::SelfDestruct => "SELFDESTRUCT",
+	}
+}
+
+/// Formats the exit reason the way geth formats VM errors.
+pub fn exit_error(reason: &ExitReason) -> String {
+	match reason {
+		ExitReason::Succeed(_) => String::new(),
+		ExitReason::Revert(_) => "execution reverted".into(),
+		ExitReason::Error(error) => match error {
+			ExitError::OutOfGas => "out of gas".into(),
+			ExitError::StackUnderflow => "stack underflow".into(),
+			ExitError::StackOverflow => "stack limit reached 1024".into(),
+			ExitError::InvalidJump => "invalid jump destination".into(),
+			ExitError::InvalidRange => "return data out of bounds".into(),
+			ExitError::DesignatedInvalid => "invalid opcode: INVALID".into(),
+			ExitError::CallTooDeep => "max call depth exceeded".into(),
+			ExitError::CreateCollision => "contract address collision".into(),
+			ExitError::CreateContractLimit => "max code size exceeded".into(),
+			ExitError::InvalidCode(_) => "invalid code: must not begin with 0xef".into(),
+			ExitError::OutOfFund => "insufficient balance for transfer".into(),
+			ExitError::Other(message) => message.to_string(),
+			error => format!("{:?}", error),
+		},
+		ExitReason::Fatal(fatal) => format!("fatal: {:?}", fatal),
+	}
+}
+
+/// Decodes the message of a `Error(string)` revert.
+pub fn revert_reason(data: &[u8]) -> Option<String> {
+	const LEN_START: usize = 36;
+	const MESSAGE_START: usize = 68;
+
+	if data.len() <= MESSAGE_START {
+		return None;
+	}
+	let message_len = U256::from(&data[LEN_START..MESSAGE_START]).saturated_into::<usize>();
+	let message_end = MESSAGE_START.saturating_add(message_len);
+	let body = data.get(MESSAGE_START..message_end)?;
+	std::str::from_utf8(body)
+		.ok()
+		.map(|reason| reason.to_string())
+}
+
+/// Mnemonic of an opcode, as printed by geth.
+pub fn opcode_name(opcode: u8) -> String {
+	let name = match opcode {
+		0x00 => "STOP",
+		0x01 => "ADD",
+		0x02 => "MUL",
+		0x03 => "SUB",
+		0x04 => "DIV",
+		0x05 => "SDIV",
+		0x06 => "MOD",
+		0x07 => "SMOD",
+		0x08 => "ADDMOD",
+		0x09 => "MULMOD",
+		0x0a => "EXP",
+		0x0b => "SIGNEXTEND",
+		0x10 => "LT",
+		0x11 => "GT",
+		0x12 => "SLT",
+		0x13 => "SGT",
+		0x14 => "EQ",
+		0x15 => "ISZERO",
+		0x16 => "AND",
+		0x17 => "OR",
+		0x18 => "XOR",
+		0x19 => "NOT",
+		0x1a => "BYTE",
+		0x1b => "SHL",
+		0x1c => "SHR",
+		0x1d => "SAR",
+		0x20 => "KECCAK256",
+		0x30 => "ADDRESS",
+		0x31 => "BALANCE",
+		0x32 => "ORIGIN",
+		0x33 => "CALLER",
+		0x34 => "CALLVALUE",
+		0x35 => "CALLDATALOAD",
+		0x36 => "CALLDATASIZE",
+		0x37 => "CALLDATACOPY",
+		0x38 => "CODESIZE",
+		0x39 => "CODECOPY",
+		0x3a => "GASPRICE",
+		0x3b => "EXTCODESIZE",
+		0x3c => "EXTCODECOPY",
+		0x3d => "RETURNDATASIZE",
+		0x3e => "RETURNDATACOPY",
+		0x3f => "EXTCODEHASH",
+		0x40 => "BLOCKHASH",
+		0x41 => "COINBASE",
+		0x42 => "TIMESTAMP",
+		0x43 => "NUMBER",
+		0x44 => "DIFFICULTY",
+		0x45 => "GASLIMIT",
+		0x46 => "CHAINID",
+		0x47 => "SELFBALANCE",
+		0x48 => "BASEFEE",
+		0x50 => "POP",
+		0x51 => "MLOAD",
+		0x52 => "MSTORE",
+		0x53 => "MSTORE8",
+		0x54 => "SLOAD",
+		0x55 => "SSTORE",
+		0x56 => "JUMP",
+		0x57 => "JUMPI",
+		0x58 => "PC",
+		0x59 => "MSIZE",
+		0x5a => "GAS",
+		0x5b => "JUMPDEST",
+		0x5f => "PUSH0",
+		0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+		0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+		0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+		0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+		0xf0 => "CREATE",
+		0xf1 => "CALL",
+		0xf2 => "CALLCODE",
+		0xf3 => "RETURN",
+		0xf4 => "DELEGATECALL",
+		0xf5 => "CREATE2",
+		0xfa => "STATICCALL",
+		0xfd => "REVERT",
+		0xfe => "INVALID",
+		0xff => "SELFDESTRUCT",
+		_ => return format!("opcode {:#x} not defined", opcode),
+	};
+	name.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn opcode_names() {
+		assert_eq!(opcode_name(0x00), "STOP");
+		assert_eq!(opcode_name(0x60), "PUSH1");
+		assert_eq!(opcode_name(0x7f), "PUSH32");
+		assert_eq!(opcode_name(0x8f), "DUP16");
+		assert_eq!(opcode_name(0x90), "SWAP1");
+		assert_eq!(opcode_name(0xa4), "LOG4");
+		assert_eq!(opcode_name(0x0c), "opcode 0xc not defined");
+	}
+}
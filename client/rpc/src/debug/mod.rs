@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+pub mod format;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H256, U256};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Substrate
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	BlockBackend,
+};
+use sp_api::{ApiExt, Core, HeaderT, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_rpc_core::{types::*, DebugApiServer};
+use fp_rpc::{DebugRuntimeApi, EthereumRuntimeRPCApi};
+
+use crate::{
+	eth::{fee_details, EthBlockDataCacheTask},
+	frontier_backend_client, internal_err,
+};
+
+/// Debug API implementation.
+pub struct Debug<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Debug<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			block_data_cache,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> Debug<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + BlockBackend<B>,
+	C::Api: EthereumRuntimeRPCApi<B> + DebugRuntimeApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace> {
+		let tracer = format::tracer_config(params)?;
+
+		let (ethereum_block_hash, index) = frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			transaction_hash,
+			true,
+		)
+		.await?
+		.ok_or_else(|| internal_err("transaction not found"))?;
+
+		let substrate_hash = frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			ethereum_block_hash,
+		)
+		.await?
+		.ok_or_else(|| internal_err("block not found"))?;
+
+		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
+		let transaction = self
+			.block_data_cache
+			.current_block(schema, substrate_hash)
+			.await
+			.and_then(|block| block.transactions.get(index as usize).cloned())
+			.ok_or_else(|| internal_err("transaction not found"))?;
+
+		let header = self
+			.client
+			.header(substrate_hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block header not found"))?;
+		let extrinsics = self
+			.client
+			.block_body(substrate_hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block body not found"))?;
+		let parent_hash = *header.parent_hash();
+
+		let api = self.client.runtime_api();
+		if !api
+			.has_api::<dyn DebugRuntimeApi<B>>(parent_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+		{
+			return Err(internal_err("debug api not supported by the runtime"));
+		}
+
+		// Replay the block up to the transaction on top of its parent state.
+		api.initialize_block(parent_hash, &header)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		let trace = api
+			.trace_transaction(parent_hash, extrinsics, &transaction, tracer)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		Ok(format::trace(trace))
+	}
+
+	async fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace> {
+		let tracer = format::tracer_config(params)?;
+
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+		let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+
+		let substrate_hash = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)
+		.await?
+		{
+			Some(id) => self
+				.client
+				.expect_block_hash_from_id(&id)
+				.map_err(|_| internal_err("header not found"))?,
+			None => self.client.info().best_hash,
+		};
+
+		let api = self.client.runtime_api();
+		if !api
+			.has_api::<dyn DebugRuntimeApi<B>>(substrate_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+		{
+			return Err(internal_err("debug api not supported by the runtime"));
+		}
+
+		// Default to the block gas limit, like `eth_call`.
+		let gas_limit = match gas {
+			Some(gas) => gas,
+			None => api
+				.current_block(substrate_hash)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.map(|block| block.header.gas_limit)
+				.unwrap_or_else(|| U256::from(u64::MAX)),
+		};
+
+		let trace = api
+			.trace_call(
+				substrate_hash,
+				from.unwrap_or_default(),
+				to,
+				data.map(|d| d.0).unwrap_or_default(),
+				value.unwrap_or_default(),
+				gas_limit,
+				details.max_fee_per_gas,
+				details.max_priority_fee_per_gas,
+				nonce,
+				access_list.map(|list| {
+					list.into_iter()
+						.map(|item| (item.address, item.storage_keys))
+						.collect()
+				}),
+				tracer,
+			)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		Ok(format::trace(trace))
+	}
+
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumber,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+		.await?
+		.ok_or_else(|| internal_err("tracing the pending block is not supported"))?;
+		let substrate_hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err("block not found"))?;
+
+		self.trace_block(substrate_hash, params).await
+	}
+
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		let substrate_hash = frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			hash,
+		)
+		.await?
+		.ok_or_else(|| internal_err("block not found"))?;
+
+		self.trace_block(substrate_hash, params).await
+	}
+
+	async fn trace_block(
+		&self,
+		substrate_hash: B::Hash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		let tracer = format::tracer_config(params)?;
+
+		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
+		let block = self
+			.block_data_cache
+			.current_block(schema, substrate_hash)
+			.await
+			.ok_or_else(|| internal_err("block not found"))?;
+
+		let header = self
+			.client
+			.header(substrate_hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block header not found"))?;
+		let extrinsics = self
+			.client
+			.block_body(substrate_hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block body not found"))?;
+		let parent_hash = *header.parent_hash();
+
+		let api = self.client.runtime_api();
+		if !api
+			.has_api::<dyn DebugRuntimeApi<B>>(parent_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+		{
+			return Err(internal_err("debug api not supported by the runtime"));
+		}
+
+		// Replay the whole block on top of its parent state.
+		api.initialize_block(parent_hash, &header)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		let traces = api
+			.trace_block(parent_hash, extrinsics, tracer)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		if traces.len() != block.transactions.len() {
+			return Err(internal_err("traced transactions do not match the block"));
+		}
+
+		Ok(block
+			.transactions
+			.iter()
+			.zip(traces)
+			.map(|(transaction, trace)| match trace {
+				Ok(trace) => TransactionTrace {
+					tx_hash: transaction.hash(),
+					result: Some(format::trace(trace)),
+					error: None,
+				},
+				Err(err) => TransactionTrace {
+					tx_hash: transaction.hash(),
+					result: None,
+					error: Some(format!("execution fatal: {:?}", err)),
+				},
+			})
+			.collect())
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> DebugApiServer for Debug<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + BlockBackend<B>,
+	C::Api: EthereumRuntimeRPCApi<B> + DebugRuntimeApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace> {
+		self.trace_transaction(transaction_hash, params).await
+	}
+
+	async fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace> {
+		self.trace_call(request, number, params).await
+	}
+
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumber,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		self.trace_block_by_number(number, params).await
+	}
+
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		self.trace_block_by_hash(hash, params).await
+	}
+}
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{
+	collections::BTreeSet,
+	sync::{Arc, Mutex},
+};
+
+use ethereum_types::{H160, H256, U256};
+use futures::{
+	channel::{mpsc, oneshot},
+	SinkExt,
+};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Substrate
+use sc_client_api::backend::Backend;
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+// Frontier
+use fc_rpc_core::{types::*, DevApiServer};
+use pallet_evm_dev::DevStateOp;
+
+use crate::internal_err;
+
+/// Maximum number of blocks a single `evm_mine` request can seal.
+const MAX_MINED_BLOCKS: usize = 1000;
+
+/// Block time and state changes controlled by the dev rpc methods.
+///
+/// The same handle is given to the `Dev` rpc handler and to the manual seal inherent data
+/// providers, which consume it when building the next block.
+#[derive(Clone, Default)]
+pub struct DevState(Arc<Mutex<DevStateInner>>);
+
+#[derive(Default)]
+struct DevStateInner {
+	clock: Clock,
+	pending: Vec<DevStateOp>,
+	impersonated: BTreeSet<H160>,
+	snapshots: Vec<Snapshot>,
+	last_snapshot_id: U256,
+}
+
+/// Timestamps are in milliseconds.
+#[derive(Clone, Default)]
+struct Clock {
+	last: u64,
+	next: Option<u64>,
+	pending_increase: u64,
+	total_increase: u64,
+}
+
+struct Snapshot {
+	id: U256,
+	number: u64,
+	clock: Clock,
+}
+
+impl DevState {
+	/// Returns the timestamp of the next block, `slot_duration` after the previous one unless
+	/// moved by `evm_increaseTime` or `evm_setNextBlockTimestamp`.
+	pub fn next_timestamp(&self, slot_duration: u64) -> u64 {
+		let mut inner = self.inner();
+		let clock = &mut inner.clock;
+		let timestamp = match clock.next.take() {
+			Some(timestamp) => timestamp,
+			None => clock.last + slot_duration + clock.pending_increase,
+		};
+		clock.pending_increase = 0;
+		clock.last = timestamp;
+		timestamp
+	}
+
+	/// Hands the queued state changes over to the next block.
+	pub fn inherent_data_provider(&self) -> pallet_evm_dev::InherentDataProvider {
+		let mut inner = self.inner();
+		pallet_evm_dev::InherentDataProvider(std::mem::take(&mut inner.pending))
+	}
+
+	pub(crate) fn impersonated_accounts(&self) -> Vec<H160> {
+		self.inner().impersonated.iter().copied().collect()
+	}
+
+	fn inner(&self) -> std::sync::MutexGuard<'_, DevStateInner> {
+		self.0.lock().expect("dev state lock poisoned")
+	}
+}
+
+/// Dev rpc handler, backed by the manual seal authorship task.
+pub struct Dev<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	/// Reverted along with `backend`, so that it does not serve the reverted blocks.
+	frontier_backend: fc_db::Backend<B>,
+	command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+	state: DevState,
+}
+
+impl<B: BlockT, C, BE> Dev<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		frontier_backend: fc_db::Backend<B>,
+		command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+		state: DevState,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			frontier_backend,
+			command_sink,
+			state,
+		}
+	}
+}
+
+impl<B, C, BE> Dev<B, C, BE>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn seal_block(&self) -> RpcResult<()> {
+		let (sender, receiver) = oneshot::channel();
+		self.command_sink
+			.clone()
+			.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				// Unfinalized blocks can still be reverted by `evm_revert`.
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			})
+			.await
+			.map_err(|err| internal_err(format!("manual seal unavailable: {}", err)))?;
+
+		match receiver.await {
+			Ok(Ok(_)) => Ok(()),
+			Ok(Err(err)) => Err(internal_err(format!("failed to seal block: {}", err))),
+			Err(_) => Err(internal_err("manual seal task dropped the request")),
+		}
+	}
+
+	async fn set_state(&self, op: DevStateOp) -> RpcResult<()> {
+		self.state.inner().pending.push(op);
+		self.seal_block().await
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> DevApiServer for Dev<B, C, BE>
+where
+	B: BlockT<Hash = H256>,
+	C: HeaderBackend<B> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn mine(&self, blocks: Option<Index>) -> RpcResult<String> {
+		let blocks = blocks.map(|blocks| blocks.value()).unwrap_or(1);
+		if blocks > MAX_MINED_BLOCKS {
+			return Err(internal_err(format!(
+				"too many blocks to mine (up to {} are allowed)",
+				MAX_MINED_BLOCKS
+			)));
+		}
+		for _ in 0..blocks {
+			self.seal_block().await?;
+		}
+		Ok("0x0".to_string())
+	}
+
+	fn increase_time(&self, seconds: Index) -> RpcResult<u64> {
+		let increase = (seconds.value() as u64).saturating_mul(1000);
+		let mut inner = self.state.inner();
+		inner.clock.pending_increase = inner.clock.pending_increase.saturating_add(increase);
+		inner.clock.total_increase = inner.clock.total_increase.saturating_add(increase);
+		Ok(inner.clock.total_increase / 1000)
+	}
+
+	fn set_next_block_timestamp(&self, timestamp: Index) -> RpcResult<()> {
+		let timestamp = (timestamp.value() as u64).saturating_mul(1000);
+		let mut inner = self.state.inner();
+		if timestamp <= inner.clock.last {
+			return Err(internal_err(format!(
+				"timestamp {} is not after the latest block timestamp {}",
+				timestamp / 1000,
+				inner.clock.last / 1000
+			)));
+		}
+		inner.clock.next = Some(timestamp);
+		inner.clock.pending_increase = 0;
+		Ok(())
+	}
+
+	fn snapshot(&self) -> RpcResult<U256> {
+		let number =
+			UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number);
+		let mut inner = self.state.inner();
+		inner.last_snapshot_id = inner.last_snapshot_id.saturating_add(U256::one());
+		let snapshot = Snapshot {
+			id: inner.last_snapshot_id,
+			number,
+			clock: inner.clock.clone(),
+		};
+		inner.snapshots.push(snapshot);
+		Ok(inner.last_snapshot_id)
+	}
+
+	async fn revert(&self, id: U256) -> RpcResult<bool> {
+		let snapshot = {
+			let mut inner = self.state.inner();
+			let position = match inner
+				.snapshots
+				.iter()
+				.position(|snapshot| snapshot.id == id)
+			{
+				Some(position) => position,
+				None => return Ok(false),
+			};
+			// A snapshot can be reverted to once, and later snapshots are dropped with it.
+			inner.snapshots.split_off(position).remove(0)
+		};
+
+		let info = self.client.info();
+		let best = UniqueSaturatedInto::<u64>::unique_saturated_into(info.best_number);
+		if best > snapshot.number {
+			let blocks = best - snapshot.number;
+			// The reverted blocks, from the best one down.
+			let mut reverted_hashes = Vec::new();
+			let mut hash = info.best_hash;
+			for _ in 0..blocks {
+				reverted_hashes.push(hash);
+				hash = *self
+					.client
+					.header(hash)
+					.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+					.ok_or_else(|| internal_err("header not found"))?
+					.parent_hash();
+			}
+
+			let (reverted, _) = self
+				.backend
+				.revert(blocks.unique_saturated_into(), false)
+				.map_err(|err| internal_err(format!("failed to revert blocks: {}", err)))?;
+			let reverted = UniqueSaturatedInto::<u64>::unique_saturated_into(reverted);
+			self.frontier_backend
+				.revert(&reverted_hashes[..reverted as usize])
+				.await
+				.map_err(|err| {
+					internal_err(format!("failed to revert the frontier db: {}", err))
+				})?;
+			if reverted != blocks {
+				return Err(internal_err(format!(
+					"reverted {} of {} blocks, the rest are finalized",
+					reverted, blocks
+				)));
+			}
+		}
+
+		// Keep block time moving forward, so that re-sealed blocks never collide with the
+		// reverted ones.
+		let mut inner = self.state.inner();
+		let last = inner.clock.last;
+		inner.clock = Clock {
+			last,
+			..snapshot.clock
+		};
+		Ok(true)
+	}
+
+	fn impersonate_account(&self, address: H160) -> RpcResult<()> {
+		self.state.inner().impersonated.insert(address);
+		Ok(())
+	}
+
+	fn stop_impersonating_account(&self, address: H160) -> RpcResult<()> {
+		self.state.inner().impersonated.remove(&address);
+		Ok(())
+	}
+
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()> {
+		self.set_state(DevStateOp::SetBalance { address, balance })
+			.await
+	}
+
+	async fn set_nonce(&self, address: H160, nonce: U256) -> RpcResult<()> {
+		self.set_state(DevStateOp::SetNonce { address, nonce })
+			.await
+	}
+
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()> {
+		self.set_state(DevStateOp::SetCode {
+			address,
+			code: code.into_vec(),
+		})
+		.await
+	}
+
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool> {
+		let mut index = H256::default();
+		slot.to_big_endian(index.as_bytes_mut());
+		self.set_state(DevStateOp::SetStorage {
+			address,
+			index,
+			value,
+		})
+		.await?;
+		Ok(true)
+	}
+}
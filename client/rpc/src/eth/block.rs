@@ -70,7 +70,12 @@ where
 			.current_transaction_statuses(schema, substrate_hash)
 			.await;
 
-		let base_fee = client.runtime_api().gas_price(substrate_hash).ok();
+		let base_fee = frontier_backend_client::base_fee::<B, C>(
+			client.as_ref(),
+			backend.as_ref(),
+			substrate_hash,
+		)
+		.await?;
 
 		match (block, statuses) {
 			(Some(block), Some(statuses)) => {
@@ -127,7 +132,12 @@ where
 					.current_transaction_statuses(schema, substrate_hash)
 					.await;
 
-				let base_fee = client.runtime_api().gas_price(substrate_hash).ok();
+				let base_fee = frontier_backend_client::base_fee::<B, C>(
+					client.as_ref(),
+					backend.as_ref(),
+					substrate_hash,
+				)
+				.await?;
 
 				match (block, statuses) {
 					(Some(block), Some(statuses)) => {
@@ -216,11 +226,9 @@ where
 		};
 		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
 		let block = self
-			.overrides
-			.schemas
-			.get(&schema)
-			.unwrap_or(&self.overrides.fallback)
-			.current_block(substrate_hash);
+			.block_data_cache
+			.current_block(schema, substrate_hash)
+			.await;
 
 		match block {
 			Some(block) => Ok(Some(U256::from(block.transactions.len()))),
@@ -255,11 +263,9 @@ where
 			.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
 		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
 		let block = self
-			.overrides
-			.schemas
-			.get(&schema)
-			.unwrap_or(&self.overrides.fallback)
-			.current_block(substrate_hash);
+			.block_data_cache
+			.current_block(schema, substrate_hash)
+			.await;
 
 		match block {
 			Some(block) => Ok(Some(U256::from(block.transactions.len()))),
@@ -79,6 +79,7 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 	pub fn new(
 		spawn_handle: SpawnTaskHandle,
 		overrides: Arc<OverrideHandle<B>>,
+		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 		blocks_cache_max_size: usize,
 		statuses_cache_max_size: usize,
 		prometheus_registry: Option<prometheus_endpoint::Registry>,
@@ -120,14 +121,14 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 						&mut blocks_cache,
 						&mut awaiting_blocks,
 						Arc::clone(&overrides),
+						Arc::clone(&backend),
 						block_hash,
 						schema,
 						response_tx,
 						task_tx.clone(),
-						move |handler| FetchedCurrentBlock {
-							block_hash,
-							block: handler.current_block(block_hash),
-						},
+						move |handler| handler.current_block(block_hash),
+						|data| data.block,
+						move |block| FetchedCurrentBlock { block_hash, block },
 					),
 					FetchedCurrentBlock { block_hash, block } => {
 						if let Some(wait_list) = awaiting_blocks.remove(&block_hash) {
@@ -150,13 +151,16 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 						&mut statuses_cache,
 						&mut awaiting_statuses,
 						Arc::clone(&overrides),
+						Arc::clone(&backend),
 						block_hash,
 						schema,
 						response_tx,
 						task_tx.clone(),
-						move |handler| FetchedCurrentTransactionStatuses {
+						move |handler| handler.current_transaction_statuses(block_hash),
+						|data| data.statuses,
+						move |statuses| FetchedCurrentTransactionStatuses {
 							block_hash,
-							statuses: handler.current_transaction_statuses(block_hash),
+							statuses,
 						},
 					),
 					FetchedCurrentTransactionStatuses {
@@ -180,20 +184,27 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 		Self(outer_task_tx)
 	}
 
-	fn request_current<T, F>(
+	fn request_current<T, F, D, M>(
 		spawn_handle: &SpawnTaskHandle,
 		cache: &mut LRUCacheByteLimited<B::Hash, T>,
 		wait_list: &mut WaitList<B::Hash, T>,
 		overrides: Arc<OverrideHandle<B>>,
+		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 		block_hash: B::Hash,
 		schema: EthereumStorageSchema,
 		response_tx: oneshot::Sender<Option<T>>,
 		task_tx: mpsc::Sender<EthBlockDataCacheMessage<B>>,
 		handler_call: F,
+		from_block_data: D,
+		into_message: M,
 	) where
-		T: Clone + scale_codec::Encode,
-		F: FnOnce(&Box<dyn StorageOverride<B>>) -> EthBlockDataCacheMessage<B>,
+		T: Clone + scale_codec::Encode + Send + 'static,
+		F: FnOnce(&Box<dyn StorageOverride<B>>) -> Option<T>,
 		F: Send + 'static,
+		D: FnOnce(fc_db::BlockData) -> T,
+		D: Send + 'static,
+		M: FnOnce(Option<T>) -> EthBlockDataCacheMessage<B>,
+		M: Send + 'static,
 	{
 		// Data is cached, we respond immediately.
 		if let Some(data) = cache.get(&block_hash).cloned() {
@@ -219,7 +230,19 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 				.get(&schema)
 				.unwrap_or(&overrides.fallback);
 
-			let message = handler_call(handler);
+			let mut data = handler_call(handler);
+			// The state of the block may have been pruned, fall back to
+			// the block data persisted by the mapping sync.
+			if data.is_none() {
+				data = backend
+					.block_data(&block_hash)
+					.await
+					.ok()
+					.flatten()
+					.map(from_block_data);
+			}
+
+			let message = into_message(data);
 			let _ = task_tx.send(message).await;
 		});
 	}
@@ -282,7 +305,13 @@ where
 		client: Arc<C>,
 		filter_pool: Arc<Mutex<BTreeMap<U256, FilterPoolItem>>>,
 		retain_threshold: u64,
+		prometheus_registry: Option<prometheus_endpoint::Registry>,
 	) {
+		if let Some(registry) = prometheus_registry {
+			if let Err(e) = register_filter_pool_size(filter_pool.clone(), &registry) {
+				log::error!(target: "eth-cache", "Failed to register metrics: {:?}", e);
+			}
+		}
 		let mut notification_st = client.import_notification_stream();
 
 		while let Some(notification) = notification_st.next().await {
@@ -301,7 +330,13 @@ where
 		overrides: Arc<OverrideHandle<B>>,
 		fee_history_cache: FeeHistoryCache,
 		block_limit: u64,
+		prometheus_registry: Option<prometheus_endpoint::Registry>,
 	) {
+		let size = register_size_gauge(
+			"frontier_eth_fee_history_cache_size",
+			"Number of blocks in the eth fee history cache.",
+			prometheus_registry,
+		);
 		struct TransactionHelper {
 			gas_used: u64,
 			effective_reward: u64,
@@ -418,6 +453,9 @@ where
 					let key = first_out - i;
 					fee_history_cache.remove(&key);
 				}
+				if let Some(size) = &size {
+					size.set(fee_history_cache.len() as u64);
+				}
 			}
 		};
 
@@ -450,3 +488,101 @@ where
 		}
 	}
 }
+
+/// Number of filters in the filter pool, read when the metrics are gathered so that the gauge
+/// follows the filters being installed and uninstalled, not only the pruning on block import.
+#[derive(Clone)]
+struct FilterPoolSize(FilterPool);
+
+impl prometheus_endpoint::MetricSource for FilterPoolSize {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		if let Ok(filter_pool) = self.0.lock() {
+			set(&[], filter_pool.len() as u64);
+		}
+	}
+}
+
+fn register_filter_pool_size(
+	filter_pool: FilterPool,
+	registry: &prometheus_endpoint::Registry,
+) -> Result<(), prometheus_endpoint::PrometheusError> {
+	prometheus_endpoint::register(
+		prometheus_endpoint::SourcedGauge::new(
+			&prometheus_endpoint::Opts::new(
+				"frontier_eth_filter_pool_size",
+				"Number of filters in the eth filter pool.",
+			),
+			FilterPoolSize(filter_pool),
+		)?,
+		registry,
+	)?;
+	Ok(())
+}
+
+/// Registers a gauge tracking the size of an eth task structure, when a registry is given.
+fn register_size_gauge(
+	name: &str,
+	help: &str,
+	prometheus_registry: Option<prometheus_endpoint::Registry>,
+) -> Option<prometheus_endpoint::Gauge<prometheus_endpoint::U64>> {
+	let registry = prometheus_registry?;
+	match prometheus_endpoint::Gauge::new(name, help)
+		.and_then(|gauge| prometheus_endpoint::register(gauge, &registry))
+	{
+		Ok(gauge) => Some(gauge),
+		Err(e) => {
+			log::error!(target: "eth-cache", "Failed to register metrics: {:?}", e);
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashSet;
+
+	fn gauge_value(registry: &prometheus_endpoint::Registry, name: &str) -> Option<f64> {
+		registry
+			.gather()
+			.iter()
+			.find(|family| family.get_name() == name)
+			.map(|family| family.get_metric()[0].get_gauge().get_value())
+	}
+
+	#[test]
+	fn filter_pool_size_follows_the_filter_pool() {
+		let registry = prometheus_endpoint::Registry::new();
+		let filter_pool: FilterPool = Arc::new(Mutex::new(BTreeMap::new()));
+		register_filter_pool_size(filter_pool.clone(), &registry).unwrap();
+		assert_eq!(
+			gauge_value(&registry, "frontier_eth_filter_pool_size"),
+			Some(0.0)
+		);
+
+		// Installing filters is reported without waiting for a block import.
+		for key in 0..2u64 {
+			filter_pool.lock().unwrap().insert(
+				U256::from(key),
+				FilterPoolItem {
+					last_poll: BlockNumber::Num(1),
+					filter_type: FilterType::Block,
+					at_block: 1,
+					pending_transaction_hashes: HashSet::new(),
+				},
+			);
+		}
+		assert_eq!(
+			gauge_value(&registry, "frontier_eth_filter_pool_size"),
+			Some(2.0)
+		);
+
+		filter_pool.lock().unwrap().remove(&U256::zero());
+		assert_eq!(
+			gauge_value(&registry, "frontier_eth_filter_pool_size"),
+			Some(1.0)
+		);
+	}
+}
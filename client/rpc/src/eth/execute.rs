@@ -18,6 +18,7 @@
 
 use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
 
+use ethereum::AccessListItem;
 use ethereum_types::{H160, H256, U256};
 use evm::{ExitError, ExitReason};
 use jsonrpsee::core::RpcResult;
@@ -28,7 +29,7 @@ use sc_transaction_pool::ChainApi;
 use sp_api::{ApiExt, CallApiAt, CallApiAtParams, ProvideRuntimeApi, StorageTransactionCache};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
-use sp_core::ExecutionContext;
+use sp_core::{hashing::keccak_256, ExecutionContext};
 use sp_io::hashing::{blake2_128, twox_128};
 use sp_runtime::{traits::Block as BlockT, DispatchError, SaturatedConversion};
 use sp_state_machine::OverlayedChanges;
@@ -46,6 +47,9 @@ use crate::{
 /// Default JSONRPC error code return by geth
 pub const JSON_RPC_ERROR_DEFAULT: i32 = -32000;
 
+/// Maximum number of executions `eth_createAccessList` does for the access list to stabilize.
+const MAX_ACCESS_LIST_ROUNDS: usize = 10;
+
 /// Allow to adapt a request for `estimate_gas`.
 /// Can be used to estimate gas of some contracts using a different function
 /// in the case the normal gas estimation doesn't work.
@@ -78,6 +82,7 @@ where
 		request: CallRequest,
 		number: Option<BlockNumber>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes> {
 		let CallRequest {
 			from,
@@ -131,6 +136,7 @@ where
 		} else {
 			return Err(internal_err("failed to retrieve Runtime Api version"));
 		};
+		let block_overrides = runtime_block_overrides(api_version, block_overrides)?;
 
 		let block = if api_version > 1 {
 			api.current_block(substrate_hash)
@@ -143,10 +149,15 @@ where
 			legacy_block.map(|block| block.into())
 		};
 
-		let block_gas_limit = block
-			.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
-			.header
-			.gas_limit;
+		let block_gas_limit = match block_overrides.as_ref().and_then(|o| o.gas_limit) {
+			Some(gas_limit) => gas_limit,
+			None => {
+				block
+					.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+					.header
+					.gas_limit
+			}
+		};
 		let max_gas_limit = block_gas_limit * self.execute_gas_limit_multiplier;
 
 		// use given gas limit or query current block's limit
@@ -210,9 +221,9 @@ where
 
 					error_on_execution_failure(&info.exit_reason, &info.value)?;
 					Ok(Bytes(info.value))
-				} else if api_version == 4 || api_version == 5 {
+				} else if api_version >= 4 {
 					// Post-london + access list support
-					let encoded_params = Encode::encode(&(
+					let mut encoded_params = Encode::encode(&(
 						&from.unwrap_or_default(),
 						&to,
 						&data,
@@ -230,61 +241,36 @@ where
 								.collect::<Vec<(sp_core::H160, Vec<H256>)>>(),
 						),
 					));
-					let overlayed_changes = self.create_overrides_overlay(
-						substrate_hash,
-						api_version,
-						state_overrides,
-					)?;
-					let storage_transaction_cache =
-						RefCell::<StorageTransactionCache<B, C::StateBackend>>::default();
-					let params = CallApiAtParams {
-						at: substrate_hash,
-						function: "EthereumRuntimeRPCApi_call",
-						arguments: encoded_params,
-						overlayed_changes: &RefCell::new(overlayed_changes),
-						storage_transaction_cache: &storage_transaction_cache,
-						context: ExecutionContext::OffchainCall(None),
-						recorder: &None,
-					};
+					if api_version >= 6 {
+						block_overrides.encode_to(&mut encoded_params);
+					}
 
 					let value = if api_version == 4 {
 						let info = self
-							.client
-							.call_api_at(params)
-							.and_then(|r| {
-								Result::map_err(
-									<Result<ExecutionInfo::<Vec<u8>>, DispatchError> as Decode>::decode(&mut &r[..]),
-									|error| sp_api::ApiError::FailedToDecodeReturnValue {
-										function: "EthereumRuntimeRPCApi_call",
-										error,
-									},
-								)
-							})
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.call_api_at_with_overrides::<Result<ExecutionInfo<Vec<u8>>, DispatchError>>(
+								substrate_hash,
+								api_version,
+								"EthereumRuntimeRPCApi_call",
+								encoded_params,
+								state_overrides,
+							)?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
 
 						error_on_execution_failure(&info.exit_reason, &info.value)?;
 						info.value
-					} else if api_version == 5 {
+					} else {
 						let info = self
-							.client
-							.call_api_at(params)
-							.and_then(|r| {
-								Result::map_err(
-									<Result<ExecutionInfoV2::<Vec<u8>>, DispatchError> as Decode>::decode(&mut &r[..]),
-									|error| sp_api::ApiError::FailedToDecodeReturnValue {
-										function: "EthereumRuntimeRPCApi_call",
-										error,
-									},
-								)
-							})
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.execute_api_at_with_overrides::<Vec<u8>>(
+								substrate_hash,
+								api_version,
+								"EthereumRuntimeRPCApi_call",
+								encoded_params,
+								state_overrides,
+							)?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
 
 						error_on_execution_failure(&info.exit_reason, &info.value)?;
 						info.value
-					} else {
-						unreachable!("invalid version");
 					};
 
 					Ok(Bytes(value))
@@ -371,7 +357,37 @@ where
 				} else if api_version == 5 {
 					// Post-london + access list support
 					let access_list = access_list.unwrap_or_default();
-					let info = api
+					#[allow(deprecated)]
+					let info = api.create_before_version_6(
+						substrate_hash,
+						from.unwrap_or_default(),
+						data,
+						value.unwrap_or_default(),
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						false,
+						Some(
+							access_list
+								.into_iter()
+								.map(|item| (item.address, item.storage_keys))
+								.collect(),
+						),
+					)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+					error_on_execution_failure(&info.exit_reason, &[])?;
+
+					let code = api
+						.account_code_at(substrate_hash, info.value)
+						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+					Ok(Bytes(code))
+				} else if api_version >= 6 {
+					// Post-london + access list + block overrides support
+					let access_list = access_list.unwrap_or_default();
+					let (info, _) = api
 						.create(
 							substrate_hash,
 							from.unwrap_or_default(),
@@ -388,6 +404,7 @@ where
 									.map(|item| (item.address, item.storage_keys))
 									.collect(),
 							),
+							block_overrides,
 						)
 						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -409,6 +426,8 @@ where
 		&self,
 		request: CallRequest,
 		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256> {
 		let client = Arc::clone(&self.client);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
@@ -438,6 +457,21 @@ where
 			}
 		};
 
+		let api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+		// The state overrides are applied to the calls of the runtime api since version 4.
+		if state_overrides.is_some() && api_version < 4 {
+			return Err(internal_err(
+				"state overrides are not supported by the runtime",
+			));
+		}
+		let block_overrides = runtime_block_overrides(api_version, block_overrides)?;
+
 		// Adapt request for gas estimation.
 		let request = EC::EstimateGasAdapter::adapt_request(request);
 
@@ -448,9 +482,15 @@ where
 		};
 		if is_simple_transfer {
 			if let Some(to) = request.to {
-				let to_code = api
-					.account_code_at(substrate_hash, to)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+				let to_code = match state_overrides
+					.as_ref()
+					.and_then(|overrides| overrides.get(&to)?.code.as_ref())
+				{
+					Some(code) => code.clone().into_vec(),
+					None => api
+						.account_code_at(substrate_hash, to)
+						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?,
+				};
 				if to_code.is_empty() {
 					return Ok(MIN_GAS_PER_TX);
 				}
@@ -470,13 +510,16 @@ where
 			)
 		};
 
-		let block_gas_limit = {
-			let schema = fc_storage::onchain_storage_schema(client.as_ref(), substrate_hash);
-			let block = block_data_cache.current_block(schema, substrate_hash).await;
-			block
-				.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
-				.header
-				.gas_limit
+		let block_gas_limit = match block_overrides.as_ref().and_then(|o| o.gas_limit) {
+			Some(gas_limit) => gas_limit,
+			None => {
+				let schema = fc_storage::onchain_storage_schema(client.as_ref(), substrate_hash);
+				let block = block_data_cache.current_block(schema, substrate_hash).await;
+				block
+					.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+					.header
+					.gas_limit
+			}
 		};
 
 		let max_gas_limit = block_gas_limit * self.execute_gas_limit_multiplier;
@@ -504,10 +547,17 @@ where
 		if let Some(from) = request.from {
 			let gas_price = gas_price.unwrap_or_default();
 			if gas_price > U256::zero() {
-				let balance = api
-					.account_basic(substrate_hash, from)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.balance;
+				let balance = match state_overrides
+					.as_ref()
+					.and_then(|overrides| overrides.get(&from)?.balance)
+				{
+					Some(balance) => balance,
+					None => {
+						api.account_basic(substrate_hash, from)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.balance
+					}
+				};
 				let mut available = balance;
 				if let Some(value) = request.value {
 					if value > available {
@@ -634,27 +684,36 @@ where
 							(info.exit_reason, info.value, info.used_gas)
 						} else {
 							// Post-london + access list support
-							let access_list = access_list.unwrap_or_default();
-							let info = api.call(
-								substrate_hash,
-								from.unwrap_or_default(),
-								to,
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-								Some(
+							let mut encoded_params = Encode::encode(&(
+								&from.unwrap_or_default(),
+								&to,
+								&data,
+								&value.unwrap_or_default(),
+								&gas_limit,
+								&max_fee_per_gas,
+								&max_priority_fee_per_gas,
+								&nonce,
+								&estimate_mode,
+								&Some(
 									access_list
+										.unwrap_or_default()
 										.into_iter()
 										.map(|item| (item.address, item.storage_keys))
-										.collect(),
+										.collect::<Vec<(sp_core::H160, Vec<H256>)>>(),
 								),
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+							));
+							if api_version >= 6 {
+								block_overrides.encode_to(&mut encoded_params);
+							}
+							let info = self
+								.execute_api_at_with_overrides::<Vec<u8>>(
+									substrate_hash,
+									api_version,
+									"EthereumRuntimeRPCApi_call",
+									encoded_params,
+									state_overrides.clone(),
+								)?
+								.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
 
 							(info.exit_reason, info.value, info.used_gas.effective)
 						}
@@ -722,26 +781,35 @@ where
 							(info.exit_reason, Vec::new(), info.used_gas)
 						} else {
 							// Post-london + access list support
-							let access_list = access_list.unwrap_or_default();
-							let info = api.create(
-								substrate_hash,
-								from.unwrap_or_default(),
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-								Some(
+							let mut encoded_params = Encode::encode(&(
+								&from.unwrap_or_default(),
+								&data,
+								&value.unwrap_or_default(),
+								&gas_limit,
+								&max_fee_per_gas,
+								&max_priority_fee_per_gas,
+								&nonce,
+								&estimate_mode,
+								&Some(
 									access_list
+										.unwrap_or_default()
 										.into_iter()
 										.map(|item| (item.address, item.storage_keys))
-										.collect(),
+										.collect::<Vec<(sp_core::H160, Vec<H256>)>>(),
 								),
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+							));
+							if api_version >= 6 {
+								block_overrides.encode_to(&mut encoded_params);
+							}
+							let info = self
+								.execute_api_at_with_overrides::<H160>(
+									substrate_hash,
+									api_version,
+									"EthereumRuntimeRPCApi_create",
+									encoded_params,
+									state_overrides.clone(),
+								)?
+								.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
 
 							(info.exit_reason, Vec::new(), info.used_gas.effective)
 						}
@@ -753,15 +821,6 @@ where
 					used_gas,
 				})
 			};
-		let api_version = if let Ok(Some(api_version)) =
-			client
-				.runtime_api()
-				.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
-		{
-			api_version
-		} else {
-			return Err(internal_err("failed to retrieve Runtime Api version"));
-		};
 
 		// Verify that the transaction succeed with highest capacity
 		let cap = highest;
@@ -874,6 +933,288 @@ where
 		}
 	}
 
+	pub async fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> RpcResult<AccessListResult> {
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+
+		let (max_fee_per_gas, max_priority_fee_per_gas) = {
+			let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+			(details.max_fee_per_gas, details.max_priority_fee_per_gas)
+		};
+
+		let substrate_hash = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)
+		.await?
+		{
+			Some(id) => self
+				.client
+				.expect_block_hash_from_id(&id)
+				.map_err(|_| crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None))?,
+			// Not mapped in the db, execute on top of the best block.
+			None => self.client.info().best_hash,
+		};
+
+		let api = self.client.runtime_api();
+		let api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+		// `call` and `create` return the storage they access since version 6.
+		if api_version < 6 {
+			return Err(internal_err(
+				"eth_createAccessList is not supported by the runtime",
+			));
+		}
+
+		let block_gas_limit = api
+			.current_block(substrate_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+			.header
+			.gas_limit;
+		let max_gas_limit = block_gas_limit * self.execute_gas_limit_multiplier;
+
+		// use given gas limit or query current block's limit
+		let gas_limit = match gas {
+			Some(amount) => {
+				if amount > max_gas_limit {
+					return Err(internal_err(format!(
+						"provided gas limit is too high (can be up to {}x the block gas limit)",
+						self.execute_gas_limit_multiplier
+					)));
+				}
+				amount
+			}
+			None => max_gas_limit,
+		};
+
+		let from = from.unwrap_or_default();
+		let value = value.unwrap_or_default();
+		let data = data.map(|d| d.0).unwrap_or_default();
+		// The sender and the callee, or the created contract, are always warm, they are only
+		// listed when their storage is accessed.
+		let callee = match to {
+			Some(to) => to,
+			None => {
+				let nonce = match nonce {
+					Some(nonce) => nonce,
+					None => {
+						api.account_basic(substrate_hash, from)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.nonce
+					}
+				};
+				let mut stream = rlp::RlpStream::new_list(2);
+				stream.append(&from);
+				stream.append(&nonce);
+				H160::from_slice(&keccak_256(&stream.out())[12..])
+			}
+		};
+
+		let mut access_list: Vec<(H160, Vec<H256>)> = access_list
+			.unwrap_or_default()
+			.into_iter()
+			.map(|item| (item.address, item.storage_keys))
+			.collect();
+
+		// Sending the call with an access list changes its gas costs, which may change the state
+		// it accesses, so execute it again until the access list is stable.
+		let mut rounds = 0;
+		let used_gas = loop {
+			if rounds == MAX_ACCESS_LIST_ROUNDS {
+				return Err(internal_err(format!(
+					"access list did not converge after {} rounds",
+					MAX_ACCESS_LIST_ROUNDS
+				)));
+			}
+			rounds += 1;
+
+			let (exit_reason, output, used_gas, accessed) = match to {
+				Some(to) => {
+					let (info, accessed) = self
+						.call_api_at::<Result<(ExecutionInfoV2<Vec<u8>>, Vec<(H160, Vec<H256>)>), DispatchError>>(
+							substrate_hash,
+							"EthereumRuntimeRPCApi_call",
+							Encode::encode(&(
+								&from,
+								&to,
+								&data,
+								&value,
+								&gas_limit,
+								&max_fee_per_gas,
+								&max_priority_fee_per_gas,
+								&nonce,
+								&false,
+								&Some(access_list.clone()),
+								&None::<fp_evm::BlockOverrides>,
+							)),
+							&RefCell::new(OverlayedChanges::default()),
+						)?
+						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+					(
+						info.exit_reason,
+						info.value,
+						info.used_gas.effective,
+						accessed,
+					)
+				}
+				None => {
+					let (info, accessed) = self
+						.call_api_at::<Result<(ExecutionInfoV2<H160>, Vec<(H160, Vec<H256>)>), DispatchError>>(
+							substrate_hash,
+							"EthereumRuntimeRPCApi_create",
+							Encode::encode(&(
+								&from,
+								&data,
+								&value,
+								&gas_limit,
+								&max_fee_per_gas,
+								&max_priority_fee_per_gas,
+								&nonce,
+								&false,
+								&Some(access_list.clone()),
+								&None::<fp_evm::BlockOverrides>,
+							)),
+							&RefCell::new(OverlayedChanges::default()),
+						)?
+						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+					(
+						info.exit_reason,
+						Vec::new(),
+						info.used_gas.effective,
+						accessed,
+					)
+				}
+			};
+			error_on_execution_failure(&exit_reason, &output)?;
+
+			// Precompiles are already left out by the runtime.
+			let accessed: Vec<(H160, Vec<H256>)> = accessed
+				.into_iter()
+				.filter(|(address, storage_keys)| {
+					!storage_keys.is_empty() || (*address != from && *address != callee)
+				})
+				.collect();
+			if accessed == access_list {
+				break used_gas;
+			}
+			access_list = accessed;
+		};
+
+		Ok(AccessListResult {
+			access_list: access_list
+				.into_iter()
+				.map(|(address, storage_keys)| AccessListItem {
+					address,
+					storage_keys,
+				})
+				.collect(),
+			gas_used: used_gas,
+		})
+	}
+
+	/// Calls `EthereumRuntimeRPCApi_call` or `EthereumRuntimeRPCApi_create`, as `function`, of a
+	/// runtime api of version 5 or later on top of the given state overrides. The accessed storage
+	/// returned since version 6 is left out.
+	fn execute_api_at_with_overrides<T: Decode>(
+		&self,
+		block_hash: B::Hash,
+		api_version: u32,
+		function: &'static str,
+		arguments: Vec<u8>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> RpcResult<Result<ExecutionInfoV2<T>, DispatchError>> {
+		if api_version >= 6 {
+			self.call_api_at_with_overrides::<Result<(ExecutionInfoV2<T>, Vec<(H160, Vec<H256>)>), DispatchError>>(
+				block_hash,
+				api_version,
+				function,
+				arguments,
+				state_overrides,
+			)
+			.map(|result| result.map(|(info, _)| info))
+		} else {
+			self.call_api_at_with_overrides(
+				block_hash,
+				api_version,
+				function,
+				arguments,
+				state_overrides,
+			)
+		}
+	}
+
+	/// Calls the runtime api `function` through `CallApiAt`, on top of the given state overrides.
+	fn call_api_at_with_overrides<R: Decode>(
+		&self,
+		block_hash: B::Hash,
+		api_version: u32,
+		function: &'static str,
+		arguments: Vec<u8>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> RpcResult<R> {
+		let overlayed_changes =
+			self.create_overrides_overlay(block_hash, api_version, state_overrides)?;
+		self.call_api_at(
+			block_hash,
+			function,
+			arguments,
+			&RefCell::new(overlayed_changes),
+		)
+	}
+
+	/// Calls the runtime api `function` through `CallApiAt`, on top of `overlayed_changes`. The
+	/// changes made by the call are kept in `overlayed_changes`.
+	pub(super) fn call_api_at<R: Decode>(
+		&self,
+		block_hash: B::Hash,
+		function: &'static str,
+		arguments: Vec<u8>,
+		overlayed_changes: &RefCell<OverlayedChanges>,
+	) -> RpcResult<R> {
+		let storage_transaction_cache =
+			RefCell::<StorageTransactionCache<B, C::StateBackend>>::default();
+		let params = CallApiAtParams {
+			at: block_hash,
+			function,
+			arguments,
+			overlayed_changes,
+			storage_transaction_cache: &storage_transaction_cache,
+			context: ExecutionContext::OffchainCall(None),
+			recorder: &None,
+		};
+
+		self.client
+			.call_api_at(params)
+			.and_then(|r| {
+				R::decode(&mut &r[..]).map_err(|error| {
+					sp_api::ApiError::FailedToDecodeReturnValue { function, error }
+				})
+			})
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+	}
+
 	/// Given an address mapped `CallStateOverride`, creates `OverlayedChanges` to be used for
 	/// `CallApiAt` eth_call.
 	fn create_overrides_overlay(
@@ -883,12 +1224,29 @@ where
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
 	) -> RpcResult<OverlayedChanges> {
 		let mut overlayed_changes = OverlayedChanges::default();
+		self.apply_state_overrides(
+			&mut overlayed_changes,
+			block_hash,
+			api_version,
+			state_overrides,
+		)?;
+		Ok(overlayed_changes)
+	}
+
+	/// Applies an address mapped `CallStateOverride` on top of `overlayed_changes`.
+	pub(super) fn apply_state_overrides(
+		&self,
+		overlayed_changes: &mut OverlayedChanges,
+		block_hash: B::Hash,
+		api_version: u32,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> RpcResult<()> {
 		if let Some(state_overrides) = state_overrides {
 			for (address, state_override) in state_overrides {
 				if EC::RuntimeStorageOverride::is_enabled() {
 					EC::RuntimeStorageOverride::set_overlayed_changes(
 						self.client.as_ref(),
-						&mut overlayed_changes,
+						overlayed_changes,
 						block_hash,
 						api_version,
 						address,
@@ -954,7 +1312,27 @@ where
 			}
 		}
 
-		Ok(overlayed_changes)
+		Ok(())
+	}
+}
+
+/// Converts the requested `BlockOverrides`, which the runtime api supports since version 6.
+fn runtime_block_overrides(
+	api_version: u32,
+	block_overrides: Option<BlockOverrides>,
+) -> RpcResult<Option<fp_evm::BlockOverrides>> {
+	match block_overrides {
+		Some(_) if api_version < 6 => Err(internal_err(
+			"block overrides are not supported by the runtime",
+		)),
+		Some(overrides) => Ok(Some(fp_evm::BlockOverrides {
+			number: overrides.number,
+			timestamp: overrides.time,
+			coinbase: overrides.coinbase,
+			base_fee_per_gas: overrides.base_fee,
+			gas_limit: overrides.gas_limit,
+		})),
+		None => Ok(None),
 	}
 }
 
@@ -971,27 +1349,7 @@ pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> RpcResult
 				&[],
 			))
 		}
-		ExitReason::Revert(_) => {
-			const LEN_START: usize = 36;
-			const MESSAGE_START: usize = 68;
-
-			let mut message = "VM Exception while processing transaction: revert".to_string();
-			// A minimum size of error function selector (4) + offset (32) + string length (32)
-			// should contain a utf-8 encoded revert reason.
-			if data.len() > MESSAGE_START {
-				let message_len =
-					U256::from(&data[LEN_START..MESSAGE_START]).saturated_into::<usize>();
-				let message_end = MESSAGE_START.saturating_add(message_len);
-
-				if data.len() >= message_end {
-					let body: &[u8] = &data[MESSAGE_START..message_end];
-					if let Ok(reason) = std::str::from_utf8(body) {
-						message = format!("{} {}", message, reason);
-					}
-				}
-			}
-			Err(crate::internal_err_with_data(message, data))
-		}
+		ExitReason::Revert(_) => Err(crate::internal_err_with_data(revert_message(data), data)),
 		ExitReason::Fatal(e) => Err(crate::internal_err_with_data(
 			format!("evm fatal: {:?}", e),
 			&[],
@@ -999,13 +1357,35 @@ pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> RpcResult
 	}
 }
 
-struct FeeDetails {
-	gas_price: Option<U256>,
-	max_fee_per_gas: Option<U256>,
-	max_priority_fee_per_gas: Option<U256>,
+/// Error message of a reverted execution, including the revert reason found in `data` if any.
+pub(crate) fn revert_message(data: &[u8]) -> String {
+	const LEN_START: usize = 36;
+	const MESSAGE_START: usize = 68;
+
+	let mut message = "VM Exception while processing transaction: revert".to_string();
+	// A minimum size of error function selector (4) + offset (32) + string length (32)
+	// should contain a utf-8 encoded revert reason.
+	if data.len() > MESSAGE_START {
+		let message_len = U256::from(&data[LEN_START..MESSAGE_START]).saturated_into::<usize>();
+		let message_end = MESSAGE_START.saturating_add(message_len);
+
+		if data.len() >= message_end {
+			let body: &[u8] = &data[MESSAGE_START..message_end];
+			if let Ok(reason) = std::str::from_utf8(body) {
+				message = format!("{} {}", message, reason);
+			}
+		}
+	}
+	message
+}
+
+pub(crate) struct FeeDetails {
+	pub(crate) gas_price: Option<U256>,
+	pub(crate) max_fee_per_gas: Option<U256>,
+	pub(crate) max_priority_fee_per_gas: Option<U256>,
 }
 
-fn fee_details(
+pub(crate) fn fee_details(
 	request_gas_price: Option<U256>,
 	request_max_fee: Option<U256>,
 	request_priority: Option<U256>,
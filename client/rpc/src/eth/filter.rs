@@ -19,7 +19,7 @@
 use std::{collections::HashSet, marker::PhantomData, sync::Arc, time};
 
 use ethereum::BlockV2 as EthereumBlock;
-use ethereum_types::{H256, U256};
+use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::{async_trait, RpcResult};
 // Substrate
 use sc_client_api::backend::{Backend, StorageProvider};
@@ -29,7 +29,7 @@ use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, NumberFor, One, Saturating, UniqueSaturatedInto},
+	traits::{Block as BlockT, NumberFor, UniqueSaturatedInto},
 };
 // Frontier
 use fc_rpc_core::{types::*, EthFilterApiServer};
@@ -329,6 +329,7 @@ where
 				} else {
 					let _ = filter_range_logs(
 						client.as_ref(),
+						backend.as_ref(),
 						&block_data_cache,
 						&mut ret,
 						max_past_logs,
@@ -408,6 +409,7 @@ where
 		} else {
 			let _ = filter_range_logs(
 				client.as_ref(),
+				backend.as_ref(),
 				&block_data_cache,
 				&mut ret,
 				max_past_logs,
@@ -497,6 +499,7 @@ where
 			} else {
 				let _ = filter_range_logs(
 					client.as_ref(),
+					backend.as_ref(),
 					&block_data_cache,
 					&mut ret,
 					max_past_logs,
@@ -536,28 +539,7 @@ where
 	let max_duration = time::Duration::from_secs(10);
 	let begin_request = time::Instant::now();
 
-	let topics_input = if filter.topics.is_some() {
-		let filtered_params = FilteredParams::new(Some(filter.clone()));
-		Some(filtered_params.flat_topics)
-	} else {
-		None
-	};
-
-	// Normalize filter data
-	let addresses = match &filter.address {
-		Some(VariadicValue::Single(item)) => vec![*item],
-		Some(VariadicValue::Multiple(items)) => items.clone(),
-		_ => vec![],
-	};
-	let topics = topics_input
-		.unwrap_or_default()
-		.iter()
-		.map(|flat| match flat {
-			VariadicValue::Single(item) => vec![*item],
-			VariadicValue::Multiple(items) => items.clone(),
-			_ => vec![],
-		})
-		.collect::<Vec<Vec<Option<H256>>>>();
+	let (addresses, topics) = normalize_filter(filter);
 
 	let time_prepare = timer_prepare.elapsed().as_millis();
 	let timer_fetch = Instant::now();
@@ -657,8 +639,35 @@ where
 	Ok(())
 }
 
+/// Normalize filter data into the addresses and flattened topics the backends filter on.
+fn normalize_filter(filter: &Filter) -> (Vec<H160>, Vec<Vec<Option<H256>>>) {
+	let topics_input = if filter.topics.is_some() {
+		let filtered_params = FilteredParams::new(Some(filter.clone()));
+		Some(filtered_params.flat_topics)
+	} else {
+		None
+	};
+
+	let addresses = match &filter.address {
+		Some(VariadicValue::Single(item)) => vec![*item],
+		Some(VariadicValue::Multiple(items)) => items.clone(),
+		_ => vec![],
+	};
+	let topics = topics_input
+		.unwrap_or_default()
+		.iter()
+		.map(|flat| match flat {
+			VariadicValue::Single(item) => vec![*item],
+			VariadicValue::Multiple(items) => items.clone(),
+			_ => vec![],
+		})
+		.collect::<Vec<Vec<Option<H256>>>>();
+	(addresses, topics)
+}
+
 async fn filter_range_logs<B: BlockT, C, BE>(
 	client: &C,
+	backend: &(dyn fc_db::BackendReader<B> + Send + Sync),
 	block_data_cache: &EthBlockDataCacheTask<B>,
 	ret: &mut Vec<Log>,
 	max_past_logs: u32,
@@ -677,7 +686,21 @@ where
 	let max_duration = time::Duration::from_secs(10);
 	let begin_request = time::Instant::now();
 
-	let mut current_number = from;
+	let from: u64 = from.unique_saturated_into();
+	let to: u64 = to.unique_saturated_into();
+
+	// Narrow the range down to the blocks that may hold matching logs, when the backend keeps
+	// an index of the logs blooms.
+	let (addresses, topics) = normalize_filter(filter);
+	let blocks: Box<dyn Iterator<Item = u64> + Send> =
+		match backend.filter_blocks(from, to, addresses, topics).await {
+			Ok(Some(blocks)) => Box::new(blocks.into_iter()),
+			Ok(None) => Box::new(from..=to),
+			Err(err) => {
+				log::debug!(target: "rpc", "Failed to filter blocks by logs bloom: {}", err);
+				Box::new(from..=to)
+			}
+		};
 
 	// Pre-calculate BloomInput for reuse.
 	let topics_input = if filter.topics.is_some() {
@@ -689,8 +712,8 @@ where
 	let address_bloom_filter = FilteredParams::adresses_bloom_filter(&filter.address);
 	let topics_bloom_filter = FilteredParams::topics_bloom_filter(&topics_input);
 
-	while current_number <= to {
-		let id = BlockId::Number(current_number);
+	for number in blocks {
+		let id = BlockId::Number(number.unique_saturated_into());
 		let substrate_hash = client
 			.expect_block_hash_from_id(&id)
 			.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
@@ -724,11 +747,6 @@ where
 				max_duration.as_secs()
 			)));
 		}
-		if current_number == to {
-			break;
-		} else {
-			current_number = current_number.saturating_add(One::one());
-		}
 	}
 	Ok(())
 }
@@ -24,6 +24,7 @@ mod fee;
 mod filter;
 pub mod format;
 mod mining;
+mod simulate;
 mod state;
 mod submit;
 mod transaction;
@@ -34,7 +35,10 @@ use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
 use ethereum_types::{H160, H256, H512, H64, U256, U64};
 use jsonrpsee::core::{async_trait, RpcResult};
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider,
+};
 use sc_network_sync::SyncingService;
 use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
@@ -53,6 +57,7 @@ use fp_rpc::{
 
 use crate::{internal_err, public_key, signer::EthSigner};
 
+pub(crate) use self::execute::fee_details;
 pub use self::{
 	cache::{EthBlockDataCacheTask, EthTask},
 	execute::EstimateGasAdapter,
@@ -174,7 +179,7 @@ where
 	B: BlockT,
 	C: CallApiAt<B> + ProvideRuntimeApi<B>,
 	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B> + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
@@ -318,6 +323,15 @@ where
 		self.code_at(address, number).await
 	}
 
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> RpcResult<EthAccount> {
+		self.proof(address, storage_keys, number).await
+	}
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
@@ -327,16 +341,37 @@ where
 		request: CallRequest,
 		number: Option<BlockNumber>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes> {
-		self.call(request, number, state_overrides).await
+		self.call(request, number, state_overrides, block_overrides)
+			.await
 	}
 
 	async fn estimate_gas(
 		&self,
 		request: CallRequest,
 		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256> {
-		self.estimate_gas(request, number).await
+		self.estimate_gas(request, number, state_overrides, block_overrides)
+			.await
+	}
+
+	async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> RpcResult<Vec<SimulatedBlock>> {
+		self.simulate_v1(payload, number).await
+	}
+
+	async fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> RpcResult<AccessListResult> {
+		self.create_access_list(request, number).await
 	}
 
 	// ########################################################################
@@ -398,7 +433,7 @@ where
 	}
 }
 
-fn rich_block_build(
+pub(crate) fn rich_block_build(
 	block: EthereumBlock,
 	statuses: Vec<Option<TransactionStatus>>,
 	hash: Option<H256>,
@@ -473,7 +508,7 @@ fn rich_block_build(
 	}
 }
 
-fn transaction_build(
+pub(crate) fn transaction_build(
 	ethereum_transaction: EthereumTransaction,
 	block: Option<EthereumBlock>,
 	status: Option<TransactionStatus>,
@@ -499,9 +534,13 @@ fn transaction_build(
 		}
 	}
 
-	let pubkey = match public_key(&ethereum_transaction) {
-		Ok(p) => Some(p),
-		Err(_e) => None,
+	// Impersonated transactions carry a placeholder signature, nothing can be recovered from it.
+	let (sender, pubkey) = match fp_ethereum::impersonated_source(&ethereum_transaction) {
+		Some(source) => (Some(source), None),
+		None => match public_key(&ethereum_transaction) {
+			Ok(pk) => (Some(H160::from(H256::from(keccak_256(&pk)))), Some(pk)),
+			Err(_e) => (None, None),
+		},
 	};
 
 	// Block hash.
@@ -517,15 +556,9 @@ fn transaction_build(
 		))
 	});
 	// From.
-	transaction.from = status.as_ref().map_or(
-		{
-			match pubkey {
-				Some(pk) => H160::from(H256::from(keccak_256(&pk))),
-				_ => H160::default(),
-			}
-		},
-		|status| status.from,
-	);
+	transaction.from = status
+		.as_ref()
+		.map_or(sender.unwrap_or_default(), |status| status.from);
 	// To.
 	transaction.to = status.as_ref().map_or(
 		{
@@ -549,6 +582,143 @@ fn transaction_build(
 	transaction
 }
 
+/// Builds the receipt of the transaction at `index` in the block. The gas used by the receipts
+/// is cumulative from `is_eip1559` on, `base_fee` being used to price EIP-1559 transactions.
+pub(crate) fn receipt_build(
+	block: &EthereumBlock,
+	statuses: &[TransactionStatus],
+	receipts: &[ethereum::ReceiptV3],
+	is_eip1559: bool,
+	index: usize,
+	base_fee: U256,
+) -> RpcResult<Receipt> {
+	let block_hash = H256::from(keccak_256(&rlp::encode(&block.header)));
+	let receipt = receipts[index].clone();
+
+	let (logs, logs_bloom, status_code, cumulative_gas_used, gas_used) = if !is_eip1559 {
+		// Pre-london frontier update stored receipts require cumulative gas calculation.
+		match receipt {
+			ethereum::ReceiptV3::Legacy(ref d) => {
+				let index = core::cmp::min(receipts.len(), index + 1);
+				let cumulative_gas: u32 = receipts[..index]
+					.iter()
+					.map(|r| match r {
+						ethereum::ReceiptV3::Legacy(d) => Ok(d.used_gas.as_u32()),
+						_ => Err(internal_err(format!(
+							"Unknown receipt for request {}",
+							block_hash
+						))),
+					})
+					.sum::<RpcResult<u32>>()?;
+				(
+					d.logs.clone(),
+					d.logs_bloom,
+					d.status_code,
+					U256::from(cumulative_gas),
+					d.used_gas,
+				)
+			}
+			_ => {
+				return Err(internal_err(format!(
+					"Unknown receipt for request {}",
+					block_hash
+				)))
+			}
+		}
+	} else {
+		match receipt {
+			ethereum::ReceiptV3::Legacy(ref d)
+			| ethereum::ReceiptV3::EIP2930(ref d)
+			| ethereum::ReceiptV3::EIP1559(ref d) => {
+				let cumulative_gas = d.used_gas;
+				let gas_used = if index > 0 {
+					let previous_receipt = receipts[index - 1].clone();
+					let previous_gas_used = match previous_receipt {
+						ethereum::ReceiptV3::Legacy(d)
+						| ethereum::ReceiptV3::EIP2930(d)
+						| ethereum::ReceiptV3::EIP1559(d) => d.used_gas,
+					};
+					cumulative_gas.saturating_sub(previous_gas_used)
+				} else {
+					cumulative_gas
+				};
+				(
+					d.logs.clone(),
+					d.logs_bloom,
+					d.status_code,
+					cumulative_gas,
+					gas_used,
+				)
+			}
+		}
+	};
+
+	let status = statuses[index].clone();
+	let mut cumulative_receipts = receipts.to_vec();
+	cumulative_receipts.truncate((status.transaction_index + 1) as usize);
+	let transaction = block.transactions[index].clone();
+	let effective_gas_price = match transaction {
+		EthereumTransaction::Legacy(t) => t.gas_price,
+		EthereumTransaction::EIP2930(t) => t.gas_price,
+		EthereumTransaction::EIP1559(t) => base_fee
+			.checked_add(t.max_priority_fee_per_gas)
+			.unwrap_or_else(U256::max_value)
+			.min(t.max_fee_per_gas),
+	};
+
+	Ok(Receipt {
+		transaction_hash: Some(status.transaction_hash),
+		transaction_index: Some(status.transaction_index.into()),
+		block_hash: Some(block_hash),
+		from: Some(status.from),
+		to: status.to,
+		block_number: Some(block.header.number),
+		cumulative_gas_used,
+		gas_used: Some(gas_used),
+		contract_address: status.contract_address,
+		logs: {
+			let mut pre_receipts_log_index = None;
+			if cumulative_receipts.len() > 0 {
+				cumulative_receipts.truncate(cumulative_receipts.len() - 1);
+				pre_receipts_log_index = Some(
+					cumulative_receipts
+						.iter()
+						.map(|r| match r {
+							ethereum::ReceiptV3::Legacy(d)
+							| ethereum::ReceiptV3::EIP2930(d)
+							| ethereum::ReceiptV3::EIP1559(d) => d.logs.len() as u32,
+						})
+						.sum::<u32>(),
+				);
+			}
+			logs.iter()
+				.enumerate()
+				.map(|(i, log)| Log {
+					address: log.address,
+					topics: log.topics.clone(),
+					data: Bytes(log.data.clone()),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(U256::from((pre_receipts_log_index.unwrap_or(0)) + i as u32)),
+					transaction_log_index: Some(U256::from(i)),
+					removed: false,
+				})
+				.collect()
+		},
+		status_code: Some(U64::from(status_code)),
+		logs_bloom,
+		state_root: None,
+		effective_gas_price,
+		transaction_type: match receipt {
+			ethereum::ReceiptV3::Legacy(_) => U256::from(0),
+			ethereum::ReceiptV3::EIP2930(_) => U256::from(1),
+			ethereum::ReceiptV3::EIP1559(_) => U256::from(2),
+		},
+	})
+}
+
 fn pending_runtime_api<'a, B: BlockT, C, BE, A: ChainApi>(
 	client: &'a C,
 	graph: &'a Pool<A>,
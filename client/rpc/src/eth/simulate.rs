@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2023 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+
+use ethereum::{BlockV2 as EthereumBlock, PartialHeader};
+use ethereum_types::{H160, H256, H64, U256, U64};
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::RpcResult;
+use scale_codec::Encode;
+// Substrate
+use sc_client_api::backend::{Backend, StorageProvider};
+use sc_transaction_pool::ChainApi;
+use sp_api::{ApiExt, CallApiAt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Block as BlockT, DispatchError};
+use sp_state_machine::OverlayedChanges;
+// Frontier
+use fc_rpc_core::types::*;
+use fp_evm::{CallInfo, CreateInfo};
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use crate::{
+	eth::{
+		execute::{revert_message, JSON_RPC_ERROR_DEFAULT},
+		fee_details, Eth, EthConfig,
+	},
+	frontier_backend_client, internal_err,
+};
+
+/// Maximum number of blocks a single `eth_simulateV1` request can simulate.
+const MAX_SIMULATED_BLOCKS: usize = 256;
+/// Maximum number of calls a single `eth_simulateV1` request can simulate, over all its blocks.
+const MAX_SIMULATED_CALLS: usize = 1000;
+
+/// Error code of the simulated calls that reverted.
+const REVERT_ERROR_CODE: i32 = 3;
+/// Error code of the simulated calls that failed in the EVM.
+const VM_ERROR_CODE: i32 = -32015;
+
+struct CallOutcome {
+	exit_reason: ExitReason,
+	return_data: Vec<u8>,
+	used_gas: U256,
+	logs: Vec<ethereum::Log>,
+}
+
+impl<B, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> Eth<B, C, P, CT, BE, A, EC>
+where
+	B: BlockT,
+	C: CallApiAt<B> + ProvideRuntimeApi<B>,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+	A: ChainApi<Block = B> + 'static,
+{
+	pub async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> RpcResult<Vec<SimulatedBlock>> {
+		let SimulatePayload {
+			block_state_calls,
+			validation,
+		} = payload;
+		if validation {
+			return Err(internal_err("validation mode is not supported"));
+		}
+		if block_state_calls.len() > MAX_SIMULATED_BLOCKS {
+			return Err(internal_err(format!(
+				"too many blocks to simulate (up to {} are allowed)",
+				MAX_SIMULATED_BLOCKS
+			)));
+		}
+		let calls_count: usize = block_state_calls
+			.iter()
+			.map(|block| block.calls.len())
+			.sum();
+		if calls_count > MAX_SIMULATED_CALLS {
+			return Err(internal_err(format!(
+				"too many calls to simulate (up to {} are allowed)",
+				MAX_SIMULATED_CALLS
+			)));
+		}
+
+		let substrate_hash = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)
+		.await?
+		{
+			Some(id) => self
+				.client
+				.expect_block_hash_from_id(&id)
+				.map_err(|_| crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None))?,
+			// Not mapped in the db, simulate on top of the best block.
+			None => self.client.info().best_hash,
+		};
+
+		let api = self.client.runtime_api();
+		let api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+		if api_version < 6 {
+			return Err(internal_err(
+				"eth_simulateV1 is not supported by the runtime",
+			));
+		}
+
+		let parent = api
+			.current_block(substrate_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.ok_or_else(|| internal_err("block unavailable, cannot simulate on top of it"))?
+			.header;
+		let parent_base_fee = api
+			.gas_price(substrate_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		// Every block is simulated in the same overlay, so that each call sees the state left by
+		// the previous ones.
+		let overlayed_changes = RefCell::new(OverlayedChanges::default());
+
+		let mut parent_hash = parent.hash();
+		let mut number = parent.number;
+		let mut timestamp = U256::from(parent.timestamp / 1000);
+		let mut blocks = Vec::with_capacity(block_state_calls.len());
+		for SimulateBlock {
+			block_overrides,
+			state_overrides,
+			calls,
+		} in block_state_calls
+		{
+			let block_overrides = block_overrides.unwrap_or_default();
+			number = match block_overrides.number {
+				Some(override_number) if override_number <= number => {
+					return Err(internal_err("simulated block numbers must be increasing"))
+				}
+				Some(override_number) => override_number,
+				None => number.saturating_add(U256::one()),
+			};
+			timestamp = match block_overrides.time {
+				Some(time) if time <= timestamp => {
+					return Err(internal_err(
+						"simulated block timestamps must be increasing",
+					))
+				}
+				Some(time) => time,
+				None => timestamp.saturating_add(U256::one()),
+			};
+			let environment = fp_evm::BlockOverrides {
+				number: Some(number),
+				timestamp: Some(timestamp),
+				coinbase: Some(block_overrides.coinbase.unwrap_or(parent.beneficiary)),
+				base_fee_per_gas: Some(block_overrides.base_fee.unwrap_or(parent_base_fee)),
+				gas_limit: Some(block_overrides.gas_limit.unwrap_or(parent.gas_limit)),
+			};
+			let gas_limit = environment.gas_limit.unwrap_or_default();
+
+			self.apply_state_overrides(
+				&mut overlayed_changes.borrow_mut(),
+				substrate_hash,
+				api_version,
+				state_overrides,
+			)?;
+
+			let mut gas_used = U256::zero();
+			let mut outcomes = Vec::with_capacity(calls.len());
+			for request in calls {
+				let available_gas = gas_limit.saturating_sub(gas_used);
+				let call_gas_limit = request.gas.unwrap_or(available_gas);
+				if call_gas_limit > available_gas {
+					return Err(internal_err("simulated block gas limit reached"));
+				}
+
+				let outcome = self.simulate_call(
+					substrate_hash,
+					request,
+					call_gas_limit,
+					&environment,
+					&overlayed_changes,
+				)?;
+				gas_used = gas_used.saturating_add(outcome.used_gas);
+				outcomes.push(outcome);
+			}
+
+			let block = EthereumBlock::new(
+				PartialHeader {
+					parent_hash,
+					beneficiary: environment.coinbase.unwrap_or_default(),
+					state_root: H256::zero(),
+					receipts_root: H256::zero(),
+					logs_bloom: Default::default(),
+					difficulty: U256::zero(),
+					number,
+					gas_limit,
+					gas_used,
+					timestamp: timestamp.low_u64(),
+					extra_data: Vec::new(),
+					mix_hash: H256::zero(),
+					nonce: H64::zero(),
+				},
+				Vec::new(),
+				Vec::new(),
+			);
+			let hash = block.header.hash();
+
+			let mut log_index = 0;
+			let calls = outcomes
+				.into_iter()
+				.enumerate()
+				.map(|(transaction_index, outcome)| {
+					let logs = outcome
+						.logs
+						.into_iter()
+						.enumerate()
+						.map(|(transaction_log_index, log)| {
+							log_index += 1;
+							Log {
+								address: log.address,
+								topics: log.topics,
+								data: Bytes(log.data),
+								block_hash: Some(hash),
+								block_number: Some(number),
+								transaction_hash: None,
+								transaction_index: Some(U256::from(transaction_index)),
+								log_index: Some(U256::from(log_index - 1)),
+								transaction_log_index: Some(U256::from(transaction_log_index)),
+								removed: false,
+							}
+						})
+						.collect();
+
+					SimulatedCall {
+						error: call_error(&outcome.exit_reason, &outcome.return_data),
+						status: U64::from(outcome.exit_reason.is_succeed() as u8),
+						return_data: Bytes(outcome.return_data),
+						logs,
+						gas_used: outcome.used_gas,
+					}
+				})
+				.collect();
+
+			blocks.push(SimulatedBlock {
+				number,
+				hash,
+				parent_hash,
+				timestamp,
+				gas_limit,
+				gas_used,
+				miner: block.header.beneficiary,
+				base_fee_per_gas: environment.base_fee_per_gas.unwrap_or_default(),
+				calls,
+			});
+			parent_hash = hash;
+		}
+
+		Ok(blocks)
+	}
+
+	/// Executes `request` in the simulated block `environment`, on top of `overlayed_changes`.
+	fn simulate_call(
+		&self,
+		block_hash: B::Hash,
+		request: CallRequest,
+		gas_limit: U256,
+		environment: &fp_evm::BlockOverrides,
+		overlayed_changes: &RefCell<OverlayedChanges>,
+	) -> RpcResult<CallOutcome> {
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+
+		let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+		let data = data.map(|d| d.0).unwrap_or_default();
+		let access_list = Some(
+			access_list
+				.unwrap_or_default()
+				.into_iter()
+				.map(|item| (item.address, item.storage_keys))
+				.collect::<Vec<(H160, Vec<H256>)>>(),
+		);
+
+		match to {
+			Some(to) => {
+				let (info, _) = self
+					.call_api_at::<Result<(CallInfo, Vec<(H160, Vec<H256>)>), DispatchError>>(
+						block_hash,
+						"EthereumRuntimeRPCApi_call",
+						(
+							from.unwrap_or_default(),
+							to,
+							data,
+							value.unwrap_or_default(),
+							gas_limit,
+							details.max_fee_per_gas,
+							details.max_priority_fee_per_gas,
+							nonce,
+							false,
+							access_list,
+							Some(environment),
+						)
+							.encode(),
+						overlayed_changes,
+					)?
+					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+				Ok(CallOutcome {
+					exit_reason: info.exit_reason,
+					return_data: info.value,
+					used_gas: info.used_gas.effective,
+					logs: info.logs,
+				})
+			}
+			None => {
+				let (info, _) = self
+					.call_api_at::<Result<(CreateInfo, Vec<(H160, Vec<H256>)>), DispatchError>>(
+						block_hash,
+						"EthereumRuntimeRPCApi_create",
+						(
+							from.unwrap_or_default(),
+							data,
+							value.unwrap_or_default(),
+							gas_limit,
+							details.max_fee_per_gas,
+							details.max_priority_fee_per_gas,
+							nonce,
+							false,
+							access_list,
+							Some(environment),
+						)
+							.encode(),
+						overlayed_changes,
+					)?
+					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+				// Creations return the deployed code, which lives in the overlay.
+				let return_data = if info.exit_reason.is_succeed() {
+					self.call_api_at::<Vec<u8>>(
+						block_hash,
+						"EthereumRuntimeRPCApi_account_code_at",
+						info.value.encode(),
+						overlayed_changes,
+					)?
+				} else {
+					Vec::new()
+				};
+
+				Ok(CallOutcome {
+					exit_reason: info.exit_reason,
+					return_data,
+					used_gas: info.used_gas.effective,
+					logs: info.logs,
+				})
+			}
+		}
+	}
+}
+
+fn call_error(exit_reason: &ExitReason, data: &[u8]) -> Option<SimulateCallError> {
+	let (code, message) = match exit_reason {
+		ExitReason::Succeed(_) => return None,
+		ExitReason::Revert(_) => {
+			return Some(SimulateCallError {
+				code: REVERT_ERROR_CODE,
+				message: revert_message(data),
+				data: Some(Bytes(data.to_vec())),
+			})
+		}
+		ExitReason::Error(ExitError::OutOfGas) => (VM_ERROR_CODE, "out of gas".to_string()),
+		ExitReason::Error(e) => (VM_ERROR_CODE, format!("evm error: {:?}", e)),
+		ExitReason::Fatal(e) => (VM_ERROR_CODE, format!("evm fatal: {:?}", e)),
+	};
+
+	Some(SimulateCallError {
+		code,
+		message,
+		data: None,
+	})
+}
@@ -18,30 +18,37 @@
 
 use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::RpcResult;
-use scale_codec::Encode;
+use scale_codec::{Decode, Encode};
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider, StorageKey,
+};
 use sc_transaction_pool::ChainApi;
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_core::hashing::keccak_256;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 // Frontier
 use fc_rpc_core::types::*;
-use fp_rpc::EthereumRuntimeRPCApi;
+use fp_rpc::{EthereumRuntimeRPCApi, RuntimeStorageOverride};
 
 use crate::{
 	eth::{pending_runtime_api, Eth, EthConfig},
 	frontier_backend_client, internal_err,
 };
 
+/// Maximum number of storage slots a single `eth_getProof` request can prove.
+const MAX_PROOF_STORAGE_KEYS: usize = 1024;
+
 impl<B, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> Eth<B, C, P, CT, BE, A, EC>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B>,
 	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B> + 'static,
 	A: ChainApi<Block = B> + 'static,
@@ -201,4 +208,118 @@ where
 			Ok(Bytes(vec![]))
 		}
 	}
+
+	pub async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> RpcResult<EthAccount> {
+		if number == Some(BlockNumber::Pending) {
+			return Err(internal_err(
+				"proofs of the pending block are not supported",
+			));
+		}
+		if storage_keys.len() > MAX_PROOF_STORAGE_KEYS {
+			return Err(internal_err(format!(
+				"too many storage keys to prove (up to {} are allowed)",
+				MAX_PROOF_STORAGE_KEYS
+			)));
+		}
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)
+		.await?
+		.ok_or_else(|| internal_err("header not found"))?;
+		let substrate_hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+		let state_root = self
+			.client
+			.header(substrate_hash)
+			.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			.ok_or_else(|| internal_err("header not found"))?
+			.state_root()
+			.as_ref()
+			.to_vec();
+
+		let basic = self
+			.client
+			.runtime_api()
+			.account_basic(substrate_hash, address)
+			.map_err(|err| {
+				internal_err(format!("fetch runtime account basic failed: {:?}", err))
+			})?;
+
+		// The nonce and balance are proven through the runtime account, when its storage key is
+		// known to the node.
+		let code_key = fp_proof::account_code_key(address);
+		let mut account_keys = vec![code_key.clone()];
+		let account_id = EC::RuntimeStorageOverride::into_account_id_bytes(address);
+		if !account_id.is_empty() {
+			account_keys.push(fp_proof::system_account_key(&account_id));
+		}
+		let account_proof = self.read_proof(substrate_hash, &account_keys)?;
+
+		let code = self
+			.read_storage(substrate_hash, code_key)?
+			.map(|value| Vec::<u8>::decode(&mut &value[..]))
+			.transpose()
+			.map_err(|err| internal_err(format!("decode account code failed: {:?}", err)))?
+			.unwrap_or_default();
+
+		let mut storage_proof = Vec::with_capacity(storage_keys.len());
+		for key in storage_keys {
+			let index = {
+				let mut bytes = [0u8; 32];
+				key.to_big_endian(&mut bytes);
+				H256::from(bytes)
+			};
+			let storage_key = fp_proof::account_storage_key(address, index);
+			let value = self
+				.read_storage(substrate_hash, storage_key.clone())?
+				.map(|value| H256::decode(&mut &value[..]))
+				.transpose()
+				.map_err(|err| internal_err(format!("decode storage value failed: {:?}", err)))?
+				.unwrap_or_default();
+
+			storage_proof.push(StorageProof {
+				key,
+				value: U256::from_big_endian(value.as_bytes()),
+				proof: self.read_proof(substrate_hash, &[storage_key])?,
+			});
+		}
+
+		Ok(EthAccount {
+			address,
+			balance: basic.balance,
+			nonce: basic.nonce,
+			code_hash: H256::from(keccak_256(&code)),
+			// All the EVM storage is part of the Substrate state trie.
+			storage_hash: H256::from_slice(&state_root),
+			account_proof,
+			storage_proof,
+		})
+	}
+
+	fn read_proof(&self, hash: B::Hash, keys: &[Vec<u8>]) -> RpcResult<Vec<Bytes>> {
+		Ok(self
+			.client
+			.read_proof(hash, &mut keys.iter().map(|key| key.as_slice()))
+			.map_err(|err| internal_err(format!("read proof failed: {:?}", err)))?
+			.into_iter_nodes()
+			.map(Bytes)
+			.collect())
+	}
+
+	fn read_storage(&self, hash: B::Hash, key: Vec<u8>) -> RpcResult<Option<Vec<u8>>> {
+		Ok(self
+			.client
+			.storage(hash, &StorageKey(key))
+			.map_err(|err| internal_err(format!("read storage failed: {:?}", err)))?
+			.map(|data| data.0))
+	}
 }
@@ -214,7 +214,7 @@ where
 		let transaction: ethereum::TransactionV2 = match ethereum::EnvelopedDecodable::decode(slice)
 		{
 			Ok(transaction) => transaction,
-			Err(_) => return Err(internal_err("decode transaction failed")),
+			Err(_) => return Err(internal_err(decode_error(slice))),
 		};
 
 		let transaction_hash = transaction.hash();
@@ -283,3 +283,15 @@ where
 			.await
 	}
 }
+
+/// Explains why a raw transaction cannot be decoded, naming the typed transactions (EIP-2718)
+/// that are valid on Ethereum but not supported here.
+fn decode_error(slice: &[u8]) -> String {
+	match slice[0] {
+		0x03 => "EIP-4844 blob transactions are not supported".to_string(),
+		0x04 => "EIP-7702 set code transactions are not supported".to_string(),
+		// Legacy transactions are RLP lists, whose first byte is at least 0xc0.
+		tx_type @ 0x05..=0x7f => format!("transaction type {:#04x} is not supported", tx_type),
+		_ => "decode transaction failed".to_string(),
+	}
+}
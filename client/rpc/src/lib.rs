@@ -26,26 +26,34 @@
 )]
 #![deny(unused_crate_dependencies)]
 
+mod debug;
+mod dev;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod ots;
 mod signer;
+mod trace;
 mod txpool;
 mod web3;
 
 pub use self::{
+	debug::Debug,
+	dev::{Dev, DevState},
 	eth::{format, EstimateGasAdapter, Eth, EthBlockDataCacheTask, EthConfig, EthFilter, EthTask},
 	eth_pubsub::{EthPubSub, EthereumSubIdProvider},
 	net::Net,
-	signer::{EthDevSigner, EthSigner},
+	ots::Ots,
+	signer::{EthDevSigner, EthImpersonationSigner, EthSigner},
+	trace::Trace,
 	txpool::TxPool,
 	web3::Web3,
 };
 
 pub use ethereum::TransactionV2 as EthereumTransaction;
 pub use fc_rpc_core::{
-	EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer, TxPoolApiServer,
-	Web3ApiServer,
+	DebugApiServer, DevApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer,
+	NetApiServer, OtsApiServer, TraceApiServer, TxPoolApiServer, Web3ApiServer,
 };
 pub use fc_storage::{
 	OverrideHandle, RuntimeApiStorageOverride, SchemaV1Override, SchemaV2Override,
@@ -63,6 +71,7 @@ pub mod frontier_backend_client {
 		backend::{Backend, StorageProvider},
 		StorageKey,
 	};
+	use sp_api::ProvideRuntimeApi;
 	use sp_blockchain::HeaderBackend;
 	use sp_io::hashing::{blake2_128, twox_128};
 	use sp_runtime::{
@@ -72,6 +81,8 @@ pub mod frontier_backend_client {
 	use sp_state_machine::OverlayedChanges;
 	// Frontier
 	use fc_rpc_core::types::BlockNumber;
+	use fc_storage::StorageOverride;
+	use fp_rpc::EthereumRuntimeRPCApi;
 
 	/// Implements a default runtime storage override.
 	/// It assumes that the balances and nonces are stored in pallet `system.account`, and
@@ -278,6 +289,61 @@ pub mod frontier_backend_client {
 				|meta| Ok(Some((meta.ethereum_block_hash, meta.ethereum_index))),
 			)
 	}
+
+	/// The base fee of the block, read from the block data persisted by the backend once the state
+	/// of the block is pruned.
+	pub async fn base_fee<B: BlockT, C>(
+		client: &C,
+		backend: &(dyn fc_db::BackendReader<B> + Send + Sync),
+		substrate_hash: B::Hash,
+	) -> RpcResult<Option<U256>>
+	where
+		B: BlockT,
+		C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
+		C::Api: EthereumRuntimeRPCApi<B>,
+	{
+		let base_fee = client.runtime_api().gas_price(substrate_hash).ok();
+		if base_fee.is_some() {
+			return Ok(base_fee);
+		}
+		Ok(backend
+			.block_data(&substrate_hash)
+			.await
+			.map_err(|err| internal_err(format!("fetch block data failed: {:?}", err)))?
+			.map(|data| data.base_fee))
+	}
+
+	/// The receipts of the block, whether they are EIP-1559 receipts and the base fee of the
+	/// block, read from the block data persisted by the backend once the state of the block is
+	/// pruned.
+	pub async fn receipts<B: BlockT, C>(
+		client: &C,
+		handler: &dyn StorageOverride<B>,
+		backend: &(dyn fc_db::BackendReader<B> + Send + Sync),
+		substrate_hash: B::Hash,
+	) -> RpcResult<Option<(Vec<ethereum::ReceiptV3>, bool, U256)>>
+	where
+		B: BlockT,
+		C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
+		C::Api: EthereumRuntimeRPCApi<B>,
+	{
+		if let Some(receipts) = handler.current_receipts(substrate_hash) {
+			let base_fee = client
+				.runtime_api()
+				.gas_price(substrate_hash)
+				.unwrap_or_default();
+			return Ok(Some((
+				receipts,
+				handler.is_eip1559(substrate_hash),
+				base_fee,
+			)));
+		}
+		Ok(backend
+			.block_data(&substrate_hash)
+			.await
+			.map_err(|err| internal_err(format!("fetch block data failed: {:?}", err)))?
+			.map(|data| (data.receipts, data.is_eip1559, data.base_fee)))
+	}
 }
 
 pub fn err<T: ToString>(code: i32, message: T, data: Option<&[u8]>) -> jsonrpsee::core::Error {
@@ -331,6 +397,18 @@ pub fn public_key(transaction: &EthereumTransaction) -> Result<[u8; 64], sp_io::
 	sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg)
 }
 
+/// The sender of a transaction, taken from the placeholder signature of impersonated
+/// transactions or recovered from the signature otherwise.
+pub fn transaction_sender(transaction: &EthereumTransaction) -> Option<ethereum_types::H160> {
+	if let Some(source) = fp_ethereum::impersonated_source(transaction) {
+		return Some(source);
+	}
+	let pubkey = public_key(transaction).ok()?;
+	Some(ethereum_types::H160::from(ethereum_types::H256::from(
+		sp_core::hashing::keccak_256(&pubkey),
+	)))
+}
+
 #[cfg(test)]
 mod tests {
 	use std::{path::PathBuf, sync::Arc};
@@ -468,4 +546,26 @@ mod tests {
 			b2_hash,
 		);
 	}
+
+	#[test]
+	fn transaction_sender_reads_the_source_of_impersonated_transactions() {
+		let source = sp_core::H160::repeat_byte(0x22);
+		let (r, s) = fp_ethereum::impersonation_signature(source);
+		let transaction = super::EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
+			chain_id: 42,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 0.into(),
+			max_fee_per_gas: 0.into(),
+			gas_limit: 21_000.into(),
+			action: ethereum::TransactionAction::Call(sp_core::H160::repeat_byte(0x33)),
+			value: 1.into(),
+			input: vec![],
+			access_list: vec![],
+			odd_y_parity: false,
+			r,
+			s,
+		});
+
+		assert_eq!(super::transaction_sender(&transaction), Some(source));
+	}
 }
@@ -0,0 +1,407 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum::BlockV2 as EthereumBlock;
+use ethereum_types::{H160, H256, U256};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Substrate
+use sc_client_api::backend::{Backend, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_db::{FilteredTransaction, SearchDirection};
+use fc_rpc_core::{
+	types::{
+		BlockDetails, BlockNumber, BlockSummary, BlockTransactions, ContractCreator, Issuance,
+		ReceiptWithTimestamp, TransactionsWithReceipts,
+	},
+	OtsApiServer,
+};
+use fc_storage::OverrideHandle;
+use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
+
+use crate::{
+	eth::{receipt_build, rich_block_build, transaction_build},
+	frontier_backend_client, internal_err, EthBlockDataCacheTask,
+};
+
+/// Version of the Otterscan API implemented.
+const API_LEVEL: u64 = 8;
+
+/// Otterscan API implementation, searching the addresses indexed by the SQL backend.
+pub struct Ots<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+	overrides: Arc<OverrideHandle<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Ots<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		overrides: Arc<OverrideHandle<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			overrides,
+			block_data_cache,
+			_marker: PhantomData,
+		}
+	}
+}
+
+/// The Ethereum data of a block needed to build its transactions and receipts.
+struct BlockContents {
+	block: EthereumBlock,
+	statuses: Vec<TransactionStatus>,
+	receipts: Vec<ethereum::ReceiptV3>,
+	is_eip1559: bool,
+	base_fee: U256,
+}
+
+impl<B, C, BE> Ots<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B>,
+{
+	fn ensure_indexed(&self) -> RpcResult<()> {
+		if !self.backend.is_indexed() {
+			return Err(internal_err(
+				"addresses are only indexed by the SQL backend",
+			));
+		}
+		Ok(())
+	}
+
+	async fn substrate_hash(&self, number: BlockNumber) -> RpcResult<Option<B::Hash>> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+		.await?
+		{
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		self.client
+			.expect_block_hash_from_id(&id)
+			.map(Some)
+			.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))
+	}
+
+	async fn block_contents(&self, substrate_hash: B::Hash) -> RpcResult<Option<BlockContents>> {
+		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
+		let handler = self
+			.overrides
+			.schemas
+			.get(&schema)
+			.unwrap_or(&self.overrides.fallback);
+
+		let block = self
+			.block_data_cache
+			.current_block(schema, substrate_hash)
+			.await;
+		let statuses = self
+			.block_data_cache
+			.current_transaction_statuses(schema, substrate_hash)
+			.await;
+		// The state of the block may have been pruned.
+		let receipts = frontier_backend_client::receipts::<B, C>(
+			self.client.as_ref(),
+			handler.as_ref(),
+			self.backend.as_ref(),
+			substrate_hash,
+		)
+		.await?;
+
+		match (block, statuses, receipts) {
+			(Some(block), Some(statuses), Some((receipts, is_eip1559, base_fee))) => {
+				Ok(Some(BlockContents {
+					block,
+					statuses,
+					receipts,
+					is_eip1559,
+					base_fee,
+				}))
+			}
+			_ => Ok(None),
+		}
+	}
+
+	async fn has_code(&self, address: H160, number: BlockNumber) -> RpcResult<bool> {
+		let substrate_hash = match self.substrate_hash(number).await? {
+			Some(hash) => hash,
+			None => return Ok(false),
+		};
+		let schema = fc_storage::onchain_storage_schema(self.client.as_ref(), substrate_hash);
+
+		Ok(self
+			.overrides
+			.schemas
+			.get(&schema)
+			.unwrap_or(&self.overrides.fallback)
+			.account_code_at(substrate_hash, address)
+			.map_or(false, |code| !code.is_empty()))
+	}
+
+	async fn get_block_details(&self, number: BlockNumber) -> RpcResult<Option<BlockDetails>> {
+		let substrate_hash = match self.substrate_hash(number).await? {
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+		let contents = match self.block_contents(substrate_hash).await? {
+			Some(contents) => contents,
+			None => return Ok(None),
+		};
+
+		let mut total_fees = U256::zero();
+		for index in 0..contents.block.transactions.len() {
+			let receipt = receipt_build(
+				&contents.block,
+				&contents.statuses,
+				&contents.receipts,
+				contents.is_eip1559,
+				index,
+				contents.base_fee,
+			)?;
+			total_fees = total_fees.saturating_add(
+				receipt
+					.gas_used
+					.unwrap_or_default()
+					.saturating_mul(receipt.effective_gas_price),
+			);
+		}
+
+		let transaction_count = contents.block.transactions.len();
+		let mut block = rich_block_build(
+			contents.block,
+			contents.statuses.into_iter().map(Some).collect(),
+			None,
+			false,
+			Some(contents.base_fee),
+			false,
+		);
+		block.inner.transactions = BlockTransactions::Hashes(vec![]);
+
+		Ok(Some(BlockDetails {
+			block: BlockSummary {
+				block,
+				transaction_count,
+			},
+			issuance: Issuance::default(),
+			total_fees,
+		}))
+	}
+
+	async fn get_transaction_by_sender_and_nonce(
+		&self,
+		sender: H160,
+		nonce: U256,
+	) -> RpcResult<Option<H256>> {
+		self.ensure_indexed()?;
+		Ok(self
+			.backend
+			.transaction_by_sender_and_nonce(sender, nonce)
+			.await
+			.map_err(internal_err)?
+			.map(|transaction| transaction.transaction_hash))
+	}
+
+	async fn get_contract_creator(&self, address: H160) -> RpcResult<Option<ContractCreator>> {
+		self.ensure_indexed()?;
+		Ok(self
+			.backend
+			.contract_creator(address)
+			.await
+			.map_err(internal_err)?
+			.map(|(creator, transaction)| ContractCreator {
+				hash: transaction.transaction_hash,
+				creator,
+			}))
+	}
+
+	async fn search_transactions(
+		&self,
+		address: H160,
+		number: u64,
+		direction: SearchDirection,
+		page_size: u64,
+	) -> RpcResult<TransactionsWithReceipts> {
+		self.ensure_indexed()?;
+		if page_size == 0 {
+			return Err(internal_err("page size must be greater than 0"));
+		}
+		let page_size = page_size.min(fc_db::MAX_SEARCH_PAGE_SIZE);
+		let from_block = match direction {
+			SearchDirection::Before if number == 0 => u64::MAX,
+			_ => number,
+		};
+		let mut transactions = self
+			.backend
+			.search_transactions(address, from_block, direction, page_size)
+			.await
+			.map_err(internal_err)?;
+		let exhausted = (transactions.len() as u64) < page_size;
+		let (first_page, last_page) = match direction {
+			SearchDirection::Before => (number == 0, exhausted),
+			SearchDirection::After => {
+				// Pages are listed with the most recent transactions first.
+				transactions.reverse();
+				(exhausted, number == 0)
+			}
+		};
+
+		let (txs, receipts) = self.transactions_with_receipts(transactions).await?;
+		Ok(TransactionsWithReceipts {
+			txs,
+			receipts,
+			first_page,
+			last_page,
+		})
+	}
+
+	async fn transactions_with_receipts(
+		&self,
+		transactions: Vec<FilteredTransaction<B>>,
+	) -> RpcResult<(
+		Vec<fc_rpc_core::types::Transaction>,
+		Vec<ReceiptWithTimestamp>,
+	)> {
+		let mut txs = Vec::with_capacity(transactions.len());
+		let mut receipts = Vec::with_capacity(transactions.len());
+		// Transactions of a block are contiguous, its contents are loaded once.
+		let mut current: Option<(B::Hash, BlockContents)> = None;
+		for transaction in transactions {
+			let contents = match current {
+				Some((hash, ref contents)) if hash == transaction.substrate_block_hash => contents,
+				_ => {
+					let contents = self
+						.block_contents(transaction.substrate_block_hash)
+						.await?
+						.ok_or_else(|| {
+							internal_err(format!(
+								"Missing data of block {}",
+								transaction.ethereum_block_hash
+							))
+						})?;
+					&current
+						.insert((transaction.substrate_block_hash, contents))
+						.1
+				}
+			};
+
+			let index = transaction.transaction_index as usize;
+			let ethereum_transaction =
+				contents
+					.block
+					.transactions
+					.get(index)
+					.cloned()
+					.ok_or_else(|| {
+						internal_err(format!(
+							"Missing transaction {}",
+							transaction.transaction_hash
+						))
+					})?;
+			txs.push(transaction_build(
+				ethereum_transaction,
+				Some(contents.block.clone()),
+				contents.statuses.get(index).cloned(),
+				Some(contents.base_fee),
+			));
+			receipts.push(ReceiptWithTimestamp {
+				receipt: receipt_build(
+					&contents.block,
+					&contents.statuses,
+					&contents.receipts,
+					contents.is_eip1559,
+					index,
+					contents.base_fee,
+				)?,
+				timestamp: contents.block.header.timestamp / 1000,
+			});
+		}
+		Ok((txs, receipts))
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> OtsApiServer for Ots<B, C, BE>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	fn get_api_level(&self) -> RpcResult<u64> {
+		Ok(API_LEVEL)
+	}
+
+	async fn has_code(&self, address: H160, number: BlockNumber) -> RpcResult<bool> {
+		self.has_code(address, number).await
+	}
+
+	async fn get_block_details(&self, number: BlockNumber) -> RpcResult<Option<BlockDetails>> {
+		self.get_block_details(number).await
+	}
+
+	async fn get_transaction_by_sender_and_nonce(
+		&self,
+		sender: H160,
+		nonce: U256,
+	) -> RpcResult<Option<H256>> {
+		self.get_transaction_by_sender_and_nonce(sender, nonce)
+			.await
+	}
+
+	async fn get_contract_creator(&self, address: H160) -> RpcResult<Option<ContractCreator>> {
+		self.get_contract_creator(address).await
+	}
+
+	async fn search_transactions_before(
+		&self,
+		address: H160,
+		number: u64,
+		page_size: u64,
+	) -> RpcResult<TransactionsWithReceipts> {
+		self.search_transactions(address, number, SearchDirection::Before, page_size)
+			.await
+	}
+
+	async fn search_transactions_after(
+		&self,
+		address: H160,
+		number: u64,
+		page_size: u64,
+	) -> RpcResult<TransactionsWithReceipts> {
+		self.search_transactions(address, number, SearchDirection::After, page_size)
+			.await
+	}
+}
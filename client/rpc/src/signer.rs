@@ -24,7 +24,7 @@ use sp_core::hashing::keccak_256;
 // Frontier
 use fc_rpc_core::types::TransactionMessage;
 
-use crate::internal_err;
+use crate::{internal_err, DevState};
 
 /// A generic Ethereum signer.
 pub trait EthSigner: Send + Sync {
@@ -161,3 +161,85 @@ impl EthSigner for EthDevSigner {
 		transaction.ok_or_else(|| internal_err("signer not available"))
 	}
 }
+
+/// Signs the transactions of impersonated accounts with a placeholder signature naming the
+/// sender, see `fp_ethereum::impersonation_signature`.
+///
+/// Only runtimes allowing impersonation accept these transactions.
+pub struct EthImpersonationSigner {
+	state: DevState,
+}
+
+impl EthImpersonationSigner {
+	pub fn new(state: DevState) -> Self {
+		Self { state }
+	}
+}
+
+impl EthSigner for EthImpersonationSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.state.impersonated_accounts()
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, Error> {
+		if !self.accounts().contains(address) {
+			return Err(internal_err("signer not available"));
+		}
+
+		let (r, s) = fp_ethereum::impersonation_signature(*address);
+		let transaction = match message {
+			TransactionMessage::Legacy(m) => {
+				let v = match m.chain_id {
+					None => 27,
+					Some(chain_id) => 2 * chain_id + 35,
+				};
+				EthereumTransaction::Legacy(ethereum::LegacyTransaction {
+					nonce: m.nonce,
+					gas_price: m.gas_price,
+					gas_limit: m.gas_limit,
+					action: m.action,
+					value: m.value,
+					input: m.input,
+					signature: ethereum::TransactionSignature::new(v, r, s)
+						.ok_or_else(|| internal_err("cannot impersonate the zero address"))?,
+				})
+			}
+			TransactionMessage::EIP2930(m) => {
+				EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
+					chain_id: m.chain_id,
+					nonce: m.nonce,
+					gas_price: m.gas_price,
+					gas_limit: m.gas_limit,
+					action: m.action,
+					value: m.value,
+					input: m.input,
+					access_list: m.access_list,
+					odd_y_parity: false,
+					r,
+					s,
+				})
+			}
+			TransactionMessage::EIP1559(m) => {
+				EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
+					chain_id: m.chain_id,
+					nonce: m.nonce,
+					max_priority_fee_per_gas: m.max_priority_fee_per_gas,
+					max_fee_per_gas: m.max_fee_per_gas,
+					gas_limit: m.gas_limit,
+					action: m.action,
+					value: m.value,
+					input: m.input,
+					access_list: m.access_list,
+					odd_y_parity: false,
+					r,
+					s,
+				})
+			}
+		};
+		Ok(transaction)
+	}
+}
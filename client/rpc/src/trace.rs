@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::{H160, H256};
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Substrate
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+// Frontier
+use fc_rpc_core::{
+	types::{
+		BlockNumber, Bytes, CallAction, CreateAction, LocalizedTrace, SuicideAction, TraceAction,
+		TraceFilter, TraceResult,
+	},
+	TraceApiServer,
+};
+use fp_evm::tracing::CallType;
+
+use crate::{debug::format::exit_error, frontier_backend_client, internal_err};
+
+/// Trace API implementation, serving the traces indexed by the SQL backend.
+pub struct Trace<B: BlockT, C> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+	/// Maximum number of traces returned by a query.
+	max_past_logs: u32,
+	/// Maximum number of blocks a `trace_filter` query can span.
+	max_block_range: u32,
+}
+
+impl<B: BlockT, C> Trace<B, C> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		max_past_logs: u32,
+		max_block_range: u32,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			max_past_logs,
+			max_block_range,
+		}
+	}
+}
+
+impl<B, C> Trace<B, C>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + 'static,
+{
+	async fn filter_traces(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		from_addresses: Vec<H160>,
+		to_addresses: Vec<H160>,
+		after: u64,
+		count: Option<u64>,
+	) -> RpcResult<Vec<LocalizedTrace>> {
+		if !self.backend.is_indexed() {
+			return Err(internal_err("traces are only indexed by the SQL backend"));
+		}
+
+		// Fetch one trace more than allowed, to tell whether the query exceeds the limit.
+		let max_past_logs = u64::from(self.max_past_logs);
+		let traces = self
+			.backend
+			.filter_traces(
+				from_block,
+				to_block,
+				from_addresses,
+				to_addresses,
+				after,
+				Some(count.map_or(max_past_logs + 1, |count| count.min(max_past_logs + 1))),
+			)
+			.await
+			.map_err(internal_err)?;
+		if traces.len() as u64 > max_past_logs {
+			return Err(internal_err(format!(
+				"query returned more than {} results",
+				max_past_logs
+			)));
+		}
+
+		Ok(traces.into_iter().map(localized_trace).collect())
+	}
+
+	async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>> {
+		let best_number: u64 = self.client.info().best_number.unique_saturated_into();
+		let to_block = filter
+			.to_block
+			.and_then(|v| v.to_min_block_num())
+			.unwrap_or(best_number)
+			.min(best_number);
+		let from_block = filter
+			.from_block
+			.and_then(|v| v.to_min_block_num())
+			.unwrap_or(best_number);
+		if to_block.saturating_sub(from_block) >= u64::from(self.max_block_range) {
+			return Err(internal_err(format!(
+				"block range is too wide (maximum {})",
+				self.max_block_range
+			)));
+		}
+
+		self.filter_traces(
+			from_block,
+			to_block,
+			filter.from_address.unwrap_or_default(),
+			filter.to_address.unwrap_or_default(),
+			filter.after.unwrap_or(0),
+			filter.count,
+		)
+		.await
+	}
+
+	async fn trace_block(&self, number: BlockNumber) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+		.await?
+		{
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let block_number: u64 = match self
+			.client
+			.block_number_from_id(&id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+		{
+			Some(block_number) => block_number.unique_saturated_into(),
+			None => return Ok(None),
+		};
+
+		self.filter_traces(block_number, block_number, vec![], vec![], 0, None)
+			.await
+			.map(Some)
+	}
+
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+	) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		let (ethereum_block_hash, _) = match frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			transaction_hash,
+			true,
+		)
+		.await?
+		{
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+		let substrate_hash = match frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			ethereum_block_hash,
+		)
+		.await?
+		{
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+		let block_number: u64 = match self
+			.client
+			.number(substrate_hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+		{
+			Some(block_number) => block_number.unique_saturated_into(),
+			None => return Ok(None),
+		};
+
+		let mut traces = self
+			.filter_traces(block_number, block_number, vec![], vec![], 0, None)
+			.await?;
+		traces.retain(|trace| trace.transaction_hash == transaction_hash);
+		Ok(Some(traces))
+	}
+}
+
+#[async_trait]
+impl<B, C> TraceApiServer for Trace<B, C>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + 'static,
+{
+	async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>> {
+		self.trace_filter(filter).await
+	}
+
+	async fn trace_block(&self, number: BlockNumber) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		self.trace_block(number).await
+	}
+
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+	) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		self.trace_transaction(transaction_hash).await
+	}
+}
+
+fn localized_trace<B: BlockT>(filtered: fc_db::FilteredTrace<B>) -> LocalizedTrace {
+	let trace = filtered.trace;
+	let succeeded = matches!(trace.exit_reason, Some(ExitReason::Succeed(_)) | None);
+
+	let (action, result, trace_type) = match trace.call_type {
+		CallType::Create | CallType::Create2 => (
+			TraceAction::Create(CreateAction {
+				from: trace.from,
+				value: trace.value,
+				gas: trace.gas.into(),
+				init: Bytes(trace.input),
+				creation_method: match trace.call_type {
+					CallType::Create2 => "create2".into(),
+					_ => "create".into(),
+				},
+			}),
+			Some(TraceResult::Create {
+				gas_used: trace.gas_used.into(),
+				code: Bytes(trace.output),
+				address: trace.to,
+			}),
+			"create",
+		),
+		CallType::SelfDestruct => (
+			TraceAction::Suicide(SuicideAction {
+				address: trace.from,
+				refund_address: trace.to,
+				balance: trace.value,
+			}),
+			// Self-destructs have no result.
+			None,
+			"suicide",
+		),
+		call_type => (
+			TraceAction::Call(CallAction {
+				from: trace.from,
+				to: trace.to,
+				value: trace.value,
+				gas: trace.gas.into(),
+				input: Bytes(trace.input),
+				call_type: match call_type {
+					CallType::CallCode => "callcode".into(),
+					CallType::DelegateCall => "delegatecall".into(),
+					CallType::StaticCall => "staticcall".into(),
+					_ => "call".into(),
+				},
+			}),
+			Some(TraceResult::Call {
+				gas_used: trace.gas_used.into(),
+				output: Bytes(trace.output),
+			}),
+			"call",
+		),
+	};
+
+	LocalizedTrace {
+		action,
+		result: result.filter(|_| succeeded),
+		error: trace.exit_reason.as_ref().and_then(trace_error),
+		block_hash: filtered.ethereum_block_hash,
+		block_number: filtered.block_number.into(),
+		transaction_hash: filtered.transaction_hash,
+		transaction_position: filtered.transaction_index,
+		subtraces: filtered.subtraces,
+		trace_address: filtered.trace_address,
+		trace_type: trace_type.into(),
+	}
+}
+
+/// Formats the exit reason the way Parity formats trace errors.
+fn trace_error(reason: &ExitReason) -> Option<String> {
+	match reason {
+		ExitReason::Succeed(_) => None,
+		ExitReason::Revert(_) => Some("Reverted".into()),
+		ExitReason::Error(ExitError::OutOfGas) => Some("Out of gas".into()),
+		ExitReason::Error(ExitError::InvalidJump) => Some("Bad jump destination".into()),
+		ExitReason::Error(ExitError::StackUnderflow) => Some("Stack underflow".into()),
+		ExitReason::Error(ExitError::StackOverflow) => Some("Out of stack".into()),
+		ExitReason::Error(ExitError::DesignatedInvalid) => Some("Bad instruction".into()),
+		reason => Some(exit_error(reason)),
+	}
+}
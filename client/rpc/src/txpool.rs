@@ -19,7 +19,7 @@
 use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
 use ethereum::TransactionV2;
-use ethereum_types::{H160, H256, U256};
+use ethereum_types::U256;
 use jsonrpsee::core::RpcResult;
 use serde::Serialize;
 // substrate
@@ -27,7 +27,6 @@ use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::InPoolTransaction;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_core::hashing::keccak_256;
 use sp_runtime::traits::Block as BlockT;
 // Frontier
 use fc_rpc_core::{
@@ -36,7 +35,7 @@ use fc_rpc_core::{
 };
 use fp_rpc::{EthereumRuntimeRPCApi, TxPoolResponse};
 
-use crate::{internal_err, public_key};
+use crate::{internal_err, transaction_sender};
 
 pub struct TxPool<B, C, A: ChainApi> {
 	client: Arc<C>,
@@ -80,10 +79,7 @@ where
 				TransactionV2::EIP2930(t) => t.nonce,
 				TransactionV2::EIP1559(t) => t.nonce,
 			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
-				Err(_e) => H160::default(),
-			};
+			let from_address = transaction_sender(txn).unwrap_or_default();
 			pending
 				.entry(from_address)
 				.or_insert_with(HashMap::new)
@@ -97,10 +93,7 @@ where
 				TransactionV2::EIP2930(t) => t.nonce,
 				TransactionV2::EIP1559(t) => t.nonce,
 			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
-				Err(_e) => H160::default(),
-			};
+			let from_address = transaction_sender(txn).unwrap_or_default();
 			queued
 				.entry(from_address)
 				.or_insert_with(HashMap::new)
@@ -37,6 +37,7 @@ use fp_ethereum::{
 	TransactionData, TransactionValidationError, ValidatedTransaction as ValidatedTransactionT,
 };
 use fp_evm::{
+	tracing::{TraceResult, Tracer, TracerConfig},
 	CallOrCreateInfo, CheckEvmTransaction, CheckEvmTransactionConfig, InvalidEvmTransactionError,
 };
 use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA};
@@ -55,7 +56,7 @@ use sp_runtime::{
 	transaction_validity::{
 		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransactionBuilder,
 	},
-	DispatchErrorWithPostInfo, RuntimeDebug, SaturatedConversion,
+	DispatchError, DispatchErrorWithPostInfo, RuntimeDebug, SaturatedConversion,
 };
 use sp_std::{marker::PhantomData, prelude::*};
 
@@ -111,6 +112,12 @@ where
 	pub fn check_self_contained(&self) -> Option<Result<H160, TransactionValidityError>> {
 		if let Call::transact { transaction } = self {
 			let check = || {
+				if T::AllowImpersonation::get() {
+					if let Some(source) = fp_ethereum::impersonated_source(transaction) {
+						return Ok(source);
+					}
+				}
+
 				let origin = Pallet::<T>::recover_signer(transaction).ok_or(
 					InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8),
 				)?;
@@ -197,6 +204,9 @@ pub mod pallet {
 		type PostLogContent: Get<PostLogContent>;
 		/// The maximum length of the extra data in the Executed event.
 		type ExtraDataLength: Get<u32>;
+		/// Whether transactions with a placeholder signature are applied on behalf of the
+		/// sender they name. Dev chains only, this lets anyone spend any account.
+		type AllowImpersonation: Get<bool>;
 	}
 
 	#[pallet::hooks]
@@ -853,6 +863,34 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Execute an Ethereum transaction with an EVM tracer attached, without adding it to the
+	/// pending block.
+	pub fn trace_transaction(
+		transaction: &Transaction,
+		tracer: TracerConfig,
+	) -> Result<TraceResult, DispatchError> {
+		let source = Self::recover_signer(transaction).ok_or(Error::<T>::InvalidSignature)?;
+
+		if let TracerConfig::PrestateTracer { diff_mode } = tracer {
+			let (info, trace) = pallet_evm::Pallet::<T>::trace_prestate(diff_mode, || {
+				Self::execute(source, transaction, None)
+			});
+			info.map_err(|e| e.error)?;
+			return Ok(TraceResult::Prestate(trace));
+		}
+
+		let mut tracer = Tracer::new(tracer);
+		let (_, _, info) = tracer
+			.trace(|| Self::execute(source, transaction, None))
+			.map_err(|e| e.error)?;
+		let used_gas = match info {
+			CallOrCreateInfo::Call(info) => info.used_gas.standard,
+			CallOrCreateInfo::Create(info) => info.used_gas.standard,
+		};
+
+		Ok(tracer.into_result(used_gas))
+	}
+
 	/// Validate an Ethereum transaction already in block
 	///
 	/// This function must be called during the pre-dispatch phase
@@ -180,10 +180,12 @@ impl pallet_evm::Config for Test {
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
+	type ForkSchedule = ();
 }
 
 parameter_types! {
 	pub const PostBlockAndTxnHashes: PostLogContent = PostLogContent::BlockAndTxnHashes;
+	pub static ImpersonationAllowed: bool = false;
 }
 
 impl Config for Test {
@@ -191,6 +193,7 @@ impl Config for Test {
 	type StateRoot = IntermediateStateRoot<Self>;
 	type PostLogContent = PostBlockAndTxnHashes;
 	type ExtraDataLength = ConstU32<30>;
+	type AllowImpersonation = ImpersonationAllowed;
 }
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
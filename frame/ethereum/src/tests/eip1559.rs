@@ -305,6 +305,65 @@ fn source_should_be_derived_from_signature() {
 	});
 }
 
+fn impersonate(transaction: Transaction, source: H160) -> Transaction {
+	match transaction {
+		Transaction::EIP1559(mut t) => {
+			(t.r, t.s) = fp_ethereum::impersonation_signature(source);
+			Transaction::EIP1559(t)
+		}
+		_ => unreachable!(),
+	}
+}
+
+#[test]
+fn source_should_be_impersonated_when_allowed() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let erc20_address = contract_address(alice.address, 0);
+	let alice_storage_address = storage_address(alice.address, H256::zero());
+
+	ext.execute_with(|| {
+		ImpersonationAllowed::set(true);
+		let transaction = impersonate(
+			eip1559_erc20_creation_unsigned_transaction().sign(&H256::repeat_byte(0x42), None),
+			alice.address,
+		);
+
+		let call = crate::Call::<Test>::transact {
+			transaction: transaction.clone(),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		assert_eq!(source, alice.address);
+
+		assert_ok!(Ethereum::transact(
+			RawOrigin::EthereumTransaction(source).into(),
+			transaction,
+		));
+		assert_eq!(
+			pallet_evm::AccountStorages::<Test>::get(erc20_address, alice_storage_address),
+			H256::from_str("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+				.unwrap()
+		)
+	});
+}
+
+#[test]
+fn impersonation_should_be_ignored_when_not_allowed() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = impersonate(eip1559_erc20_creation_transaction(alice), alice.address);
+
+		let call = crate::Call::<Test>::transact { transaction };
+		assert_ne!(
+			call.check_self_contained().unwrap().ok(),
+			Some(alice.address)
+		);
+	});
+}
+
 #[test]
 fn contract_should_be_created_at_given_address() {
 	let (pairs, mut ext) = new_test_ext(1);
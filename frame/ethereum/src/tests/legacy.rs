@@ -333,6 +333,97 @@ fn call_should_handle_errors() {
 	});
 }
 
+#[test]
+fn transaction_should_be_traced() {
+	use fp_evm::tracing::{CallType, StructLoggerConfig, TraceResult, TracerConfig};
+
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let t = legacy_erc20_creation_transaction(alice);
+
+		let trace = Ethereum::trace_transaction(
+			&t,
+			TracerConfig::CallTracer {
+				only_top_call: false,
+			},
+		)
+		.unwrap();
+		match trace {
+			TraceResult::CallTracer(call) => {
+				assert_eq!(call.call_type, CallType::Create);
+				assert_eq!(call.from, alice.address);
+				assert_eq!(call.gas_used, 894198);
+				assert_eq!(
+					call.exit_reason,
+					Some(ExitReason::Succeed(ExitSucceed::Returned))
+				);
+				assert!(call.calls.is_empty());
+			}
+			_ => panic!("expected call trace"),
+		}
+
+		let trace = Ethereum::trace_transaction(
+			&t,
+			TracerConfig::StructLogger(StructLoggerConfig::default()),
+		)
+		.unwrap();
+		match trace {
+			TraceResult::StructLogger(result) => {
+				assert!(!result.failed);
+				assert_eq!(result.gas, U256::from(894198));
+				let first = &result.struct_logs[0];
+				assert_eq!((first.pc, first.op, first.depth), (0, 0x60, 1));
+			}
+			_ => panic!("expected struct logs"),
+		}
+	});
+}
+
+#[test]
+fn transaction_prestate_should_be_traced() {
+	use fp_evm::tracing::{PrestateTrace, TraceResult, TracerConfig};
+
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let t = legacy_erc20_creation_transaction(alice);
+		let (alice_before, _) = pallet_evm::Pallet::<Test>::account_basic(&alice.address);
+
+		let trace = frame_support::storage::with_transaction(|| {
+			let trace =
+				Ethereum::trace_transaction(&t, TracerConfig::PrestateTracer { diff_mode: false });
+			sp_runtime::TransactionOutcome::Rollback(trace)
+		})
+		.unwrap();
+		match trace {
+			TraceResult::Prestate(PrestateTrace::Prestate(accounts)) => {
+				let alice_state = &accounts[&alice.address];
+				assert_eq!(alice_state.balance, Some(alice_before.balance));
+				assert_eq!(alice_state.nonce, Some(U256::zero()));
+				assert_eq!(alice_state.code, None);
+			}
+			_ => panic!("expected prestate"),
+		}
+
+		let trace =
+			Ethereum::trace_transaction(&t, TracerConfig::PrestateTracer { diff_mode: true })
+				.unwrap();
+		match trace {
+			TraceResult::Prestate(PrestateTrace::Diff { pre, post }) => {
+				assert_eq!(pre[&alice.address].nonce, Some(U256::zero()));
+				assert_eq!(post[&alice.address].nonce, Some(U256::one()));
+
+				let erc20_address = contract_address(alice.address, 0);
+				assert!(post[&erc20_address].code.is_some());
+			}
+			_ => panic!("expected prestate diff"),
+		}
+	});
+}
+
 #[test]
 fn event_extra_data_should_be_handle_properly() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dev-only EVM state manipulation.
+//!
+//! Backs the `anvil_set*` RPC methods of manual seal nodes. The node queues [`DevStateOp`]s,
+//! hands them to the runtime through the [`INHERENT_IDENTIFIER`] inherent, and the
+//! `set_state` call applies them at the start of the next block.
+//!
+//! The pallet is inert unless [`Config::Enabled`] returns `true`. It must not be enabled on
+//! production chains, since any block author could then rewrite arbitrary account state.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::{inherent::IsFatalError, traits::Currency};
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_inherents::{InherentData, InherentIdentifier};
+use sp_runtime::{
+	traits::{UniqueSaturatedFrom, UniqueSaturatedInto},
+	RuntimeDebug,
+};
+use sp_std::vec::Vec;
+// Frontier
+use pallet_evm::{AccountCodes, AccountCodesMetadata, AccountStorages, AddressMapping, BalanceOf};
+
+pub use self::pallet::*;
+
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"evmdev0_";
+
+/// A single EVM state change requested by the node.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum DevStateOp {
+	/// Set the free balance of an account.
+	SetBalance { address: H160, balance: U256 },
+	/// Set the nonce of an account.
+	SetNonce { address: H160, nonce: U256 },
+	/// Replace the code of an account. Empty code removes it.
+	SetCode { address: H160, code: Vec<u8> },
+	/// Set a storage slot of an account. A zero value clears it.
+	SetStorage {
+		address: H160,
+		index: H256,
+		value: H256,
+	},
+}
+
+pub type InherentType = Vec<DevStateOp>;
+
+#[cfg(feature = "std")]
+pub struct InherentDataProvider(pub InherentType);
+
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	async fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		if self.0.is_empty() {
+			return Ok(());
+		}
+		inherent_data.put_data(INHERENT_IDENTIFIER, &self.0)
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		None
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_evm::Config {
+		/// Whether dev state changes are accepted. Must be `false` outside of dev chains.
+		type Enabled: Get<bool>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Dev state changes are disabled on this chain.
+		Disabled,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight((
+			T::DbWeight::get().reads_writes(ops.len() as u64, 2 * ops.len() as u64),
+			DispatchClass::Mandatory
+		))]
+		pub fn set_state(origin: OriginFor<T>, ops: Vec<DevStateOp>) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(T::Enabled::get(), Error::<T>::Disabled);
+
+			for op in ops {
+				Self::apply(op);
+			}
+			Ok(())
+		}
+	}
+
+	#[derive(Encode, Decode, RuntimeDebug)]
+	pub enum InherentError {
+		/// Dev state changes are disabled on this chain.
+		Disabled,
+	}
+
+	impl IsFatalError for InherentError {
+		fn is_fatal_error(&self) -> bool {
+			true
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = InherentError;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			if !T::Enabled::get() {
+				return None;
+			}
+			let ops = data.get_data::<InherentType>(&INHERENT_IDENTIFIER).ok()??;
+			if ops.is_empty() {
+				return None;
+			}
+
+			Some(Call::set_state { ops })
+		}
+
+		fn check_inherent(_call: &Self::Call, _data: &InherentData) -> Result<(), Self::Error> {
+			if !T::Enabled::get() {
+				return Err(InherentError::Disabled);
+			}
+			Ok(())
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::set_state { .. })
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn apply(op: DevStateOp) {
+		match op {
+			DevStateOp::SetBalance { address, balance } => {
+				let account_id = T::AddressMapping::into_account_id(address);
+				let balance = UniqueSaturatedInto::<u128>::unique_saturated_into(balance);
+				let _ = T::Currency::make_free_balance_be(
+					&account_id,
+					BalanceOf::<T>::unique_saturated_from(balance),
+				);
+			}
+			DevStateOp::SetNonce { address, nonce } => {
+				let account_id = T::AddressMapping::into_account_id(address);
+				let nonce = UniqueSaturatedInto::<u128>::unique_saturated_into(nonce);
+				frame_system::Account::<T>::mutate(&account_id, |account| {
+					account.nonce = T::Index::unique_saturated_from(nonce);
+				});
+			}
+			DevStateOp::SetCode { address, code } => {
+				if code.is_empty() {
+					// Drop the code but keep the storage, like `anvil_setCode` does.
+					if <AccountCodes<T>>::contains_key(address) {
+						let account_id = T::AddressMapping::into_account_id(address);
+						let _ = frame_system::Pallet::<T>::dec_sufficients(&account_id);
+					}
+					<AccountCodes<T>>::remove(address);
+					<AccountCodesMetadata<T>>::remove(address);
+				} else {
+					pallet_evm::Pallet::<T>::create_account(address, code);
+				}
+			}
+			DevStateOp::SetStorage {
+				address,
+				index,
+				value,
+			} => {
+				if value == H256::default() {
+					<AccountStorages<T>>::remove(address, index);
+				} else {
+					<AccountStorages<T>>::insert(address, index, value);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	assert_noop, assert_ok,
+	inherent::{InherentData, ProvideInherent},
+};
+use sp_core::{H160, H256, U256};
+
+use super::*;
+use crate::mock::*;
+
+fn address() -> H160 {
+	H160::from_low_u64_be(0x1234)
+}
+
+fn set_state(ops: Vec<DevStateOp>) {
+	assert_ok!(EVMDev::set_state(RuntimeOrigin::none(), ops));
+}
+
+#[test]
+fn set_balance_and_nonce() {
+	new_test_ext().execute_with(|| {
+		set_state(vec![
+			DevStateOp::SetBalance {
+				address: address(),
+				balance: U256::from(1_000u64),
+			},
+			DevStateOp::SetNonce {
+				address: address(),
+				nonce: U256::from(7u64),
+			},
+		]);
+
+		let (account, _) = EVM::account_basic(&address());
+		assert_eq!(account.balance, U256::from(1_000u64));
+		assert_eq!(account.nonce, U256::from(7u64));
+	});
+}
+
+#[test]
+fn set_code_replaces_and_clears_code() {
+	new_test_ext().execute_with(|| {
+		set_state(vec![DevStateOp::SetCode {
+			address: address(),
+			code: vec![0x60, 0x00],
+		}]);
+		assert_eq!(AccountCodes::<Test>::get(address()), vec![0x60, 0x00]);
+		assert_eq!(System::sufficients(&address()), 1);
+
+		set_state(vec![DevStateOp::SetCode {
+			address: address(),
+			code: vec![0x00],
+		}]);
+		assert_eq!(AccountCodes::<Test>::get(address()), vec![0x00]);
+		assert_eq!(System::sufficients(&address()), 1);
+
+		set_state(vec![DevStateOp::SetCode {
+			address: address(),
+			code: vec![],
+		}]);
+		assert!(AccountCodes::<Test>::get(address()).is_empty());
+		assert_eq!(System::sufficients(&address()), 0);
+	});
+}
+
+#[test]
+fn set_storage_inserts_and_clears_slot() {
+	new_test_ext().execute_with(|| {
+		let index = H256::from_low_u64_be(1);
+		let value = H256::from_low_u64_be(42);
+
+		set_state(vec![DevStateOp::SetStorage {
+			address: address(),
+			index,
+			value,
+		}]);
+		assert_eq!(AccountStorages::<Test>::get(address(), index), value);
+
+		set_state(vec![DevStateOp::SetStorage {
+			address: address(),
+			index,
+			value: H256::default(),
+		}]);
+		assert!(!AccountStorages::<Test>::contains_key(address(), index));
+	});
+}
+
+#[test]
+fn disabled_pallet_rejects_state_changes() {
+	new_test_ext().execute_with(|| {
+		DevEnabled::set(false);
+		let ops = vec![DevStateOp::SetNonce {
+			address: address(),
+			nonce: U256::one(),
+		}];
+
+		let mut data = InherentData::new();
+		data.put_data(INHERENT_IDENTIFIER, &ops).unwrap();
+		assert!(EVMDev::create_inherent(&data).is_none());
+		assert_noop!(
+			EVMDev::set_state(RuntimeOrigin::none(), ops),
+			Error::<Test>::Disabled
+		);
+	});
+}
+
+#[test]
+fn empty_inherent_data_creates_no_call() {
+	new_test_ext().execute_with(|| {
+		let mut data = InherentData::new();
+		assert!(EVMDev::create_inherent(&data).is_none());
+
+		data.put_data(INHERENT_IDENTIFIER, &InherentType::new())
+			.unwrap();
+		assert!(EVMDev::create_inherent(&data).is_none());
+	});
+}
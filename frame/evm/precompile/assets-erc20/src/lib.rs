@@ -0,0 +1,541 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ERC-20 precompile set exposing `fungibles` assets (e.g. `pallet-assets`) to the EVM.
+//!
+//! Every asset is reachable at an address made of a reserved 4-byte prefix followed by the
+//! asset id encoded as a big-endian `u128`. On top of the ERC-20 interface, the precompile
+//! supports EIP-2612 `permit` and lets the asset's admin `mint` and `burn` tokens.
+//! Allowances and permit nonces are kept in storage owned by the precompile.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use fp_evm::{
+	IsPrecompileResult, LinearCostPrecompile, Precompile, PrecompileResult, PrecompileSet,
+};
+use frame_support::{
+	storage::types::{Key, StorageDoubleMap, StorageNMap, ValueQuery},
+	traits::{
+		fungibles,
+		tokens::{Fortitude, Precision, Preservation},
+		Get, StorageInstance, Time,
+	},
+	Blake2_128Concat,
+};
+use pallet_evm::AddressMapping;
+use pallet_evm_precompile_simple::ECRecover;
+use precompile_utils::{prelude::*, substrate::RuntimeHelper};
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::{UniqueSaturatedFrom, UniqueSaturatedInto};
+
+/// Topic of the `Transfer(address,address,uint256)` event.
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+/// Topic of the `Approval(address,address,uint256)` event.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+/// Type hash of the EIP-712 domain, with its name, version, chain id and verifying contract.
+pub const EIP712_DOMAIN_TYPEHASH: [u8; 32] = keccak256!(
+	"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+);
+/// Type hash of the EIP-2612 `Permit` struct signed by the owner of the tokens.
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+	"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+);
+
+/// Storage prefix of the allowances kept by the precompile set.
+pub struct ApprovesPrefix;
+
+impl StorageInstance for ApprovesPrefix {
+	const STORAGE_PREFIX: &'static str = "Approves";
+
+	fn pallet_prefix() -> &'static str {
+		"Erc20AssetsPrecompile"
+	}
+}
+
+/// Allowances keyed by token address, owner and spender.
+pub type Approves = StorageNMap<
+	ApprovesPrefix,
+	(
+		Key<Blake2_128Concat, H160>,
+		Key<Blake2_128Concat, H160>,
+		Key<Blake2_128Concat, H160>,
+	),
+	U256,
+	ValueQuery,
+>;
+
+/// Storage prefix of the EIP-2612 nonces kept by the precompile set.
+pub struct NoncesPrefix;
+
+impl StorageInstance for NoncesPrefix {
+	const STORAGE_PREFIX: &'static str = "Nonces";
+
+	fn pallet_prefix() -> &'static str {
+		"Erc20AssetsPrecompile"
+	}
+}
+
+/// Permit nonces keyed by token address and owner.
+pub type Nonces = StorageDoubleMap<
+	NoncesPrefix,
+	Blake2_128Concat,
+	H160,
+	Blake2_128Concat,
+	H160,
+	U256,
+	ValueQuery,
+>;
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+type AssetIdOf<Runtime, Assets> = <Assets as fungibles::Inspect<AccountIdOf<Runtime>>>::AssetId;
+type AssetBalanceOf<Runtime, Assets> =
+	<Assets as fungibles::Inspect<AccountIdOf<Runtime>>>::Balance;
+
+/// Precompile set exposing every asset of `Assets` as an ERC-20 token living at
+/// `Prefix ++ asset_id`.
+pub struct Erc20AssetsPrecompileSet<Runtime, Assets, Prefix>(
+	PhantomData<(Runtime, Assets, Prefix)>,
+);
+
+impl<Runtime, Assets, Prefix> Default for Erc20AssetsPrecompileSet<Runtime, Assets, Prefix> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<Runtime, Assets, Prefix> PrecompileSet for Erc20AssetsPrecompileSet<Runtime, Assets, Prefix>
+where
+	Runtime: pallet_evm::Config,
+	Assets: fungibles::Mutate<AccountIdOf<Runtime>>
+		+ fungibles::metadata::Inspect<AccountIdOf<Runtime>>
+		+ fungibles::roles::Inspect<AccountIdOf<Runtime>>,
+	AssetIdOf<Runtime, Assets>: TryFrom<u128> + Into<u128>,
+	Prefix: Get<[u8; 4]>,
+{
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		let asset_id = Self::address_to_asset_id(handle.code_address())?;
+		if !Assets::asset_exists(asset_id) {
+			return None;
+		}
+		Some(<Self as Precompile>::execute(handle))
+	}
+
+	fn is_precompile(&self, address: H160, _gas: u64) -> IsPrecompileResult {
+		// Only the addresses carrying the prefix need the asset to be read.
+		match Self::address_to_asset_id(address) {
+			Some(asset_id) => IsPrecompileResult::Answer {
+				is_precompile: Assets::asset_exists(asset_id),
+				extra_cost: RuntimeHelper::<Runtime>::db_read_gas_cost(),
+			},
+			None => IsPrecompileResult::Answer {
+				is_precompile: false,
+				extra_cost: 0,
+			},
+		}
+	}
+}
+
+/// ERC-20 interface of the asset living at the address of the precompile.
+#[precompile]
+impl<Runtime, Assets, Prefix> Erc20AssetsPrecompileSet<Runtime, Assets, Prefix>
+where
+	Runtime: pallet_evm::Config,
+	Assets: fungibles::Mutate<AccountIdOf<Runtime>>
+		+ fungibles::metadata::Inspect<AccountIdOf<Runtime>>
+		+ fungibles::roles::Inspect<AccountIdOf<Runtime>>,
+	AssetIdOf<Runtime, Assets>: TryFrom<u128> + Into<u128>,
+	Prefix: Get<[u8; 4]>,
+{
+	#[precompile::pre_check]
+	fn pre_check(handle: &mut impl PrecompileHandle) -> EvmResult {
+		// Transfers are made on behalf of the caller, which a contract delegating to the
+		// precompile would be able to impersonate.
+		if handle.code_address() != handle.context().address {
+			return Err(revert("cannot be called with DELEGATECALL or CALLCODE"));
+		}
+		Ok(())
+	}
+
+	// Names and symbols aren't required to be valid UTF-8, `bytes` share the ABI of `string`.
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name(handle: &mut impl PrecompileHandle) -> EvmResult<Bytes> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		Ok(<Assets as fungibles::metadata::Inspect<_>>::name(asset_id).into())
+	}
+
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol(handle: &mut impl PrecompileHandle) -> EvmResult<Bytes> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		Ok(<Assets as fungibles::metadata::Inspect<_>>::symbol(asset_id).into())
+	}
+
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals(handle: &mut impl PrecompileHandle) -> EvmResult<u8> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		Ok(<Assets as fungibles::metadata::Inspect<_>>::decimals(
+			asset_id,
+		))
+	}
+
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	fn total_supply(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		Ok(balance_to_u256::<Runtime, Assets>(Assets::total_issuance(
+			asset_id,
+		)))
+	}
+
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	fn balance_of(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		let owner = Runtime::AddressMapping::into_account_id(owner);
+		Ok(balance_to_u256::<Runtime, Assets>(Assets::balance(
+			asset_id, &owner,
+		)))
+	}
+
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	fn allowance(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+	) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(Approves::get((handle.code_address(), owner, spender)))
+	}
+
+	#[precompile::public("nonces(address)")]
+	#[precompile::view]
+	fn nonces(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(Nonces::get(handle.code_address(), owner))
+	}
+
+	#[precompile::public("DOMAIN_SEPARATOR()")]
+	#[precompile::view]
+	fn domain_separator_of(handle: &mut impl PrecompileHandle) -> EvmResult<H256> {
+		// Name and chain id reads.
+		handle.record_db_read::<Runtime>(2)?;
+		let asset_id = Self::asset_id(handle)?;
+		Ok(Self::domain_separator(asset_id, handle.code_address()))
+	}
+
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult<bool> {
+		let from = handle.context().caller;
+		Self::transfer_asset(handle, from, to, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("approve(address,uint256)")]
+	fn approve(handle: &mut impl PrecompileHandle, spender: H160, value: U256) -> EvmResult<bool> {
+		let owner = handle.context().caller;
+		Self::approve_spender(handle, owner, spender, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	fn transfer_from(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult<bool> {
+		let token = handle.code_address();
+		let caller = handle.context().caller;
+		if caller != from {
+			handle.record_db_read::<Runtime>(1)?;
+			handle.record_db_write::<Runtime>(1)?;
+			let allowance = Approves::get((token, from, caller));
+			if allowance < value {
+				return Err(revert("insufficient allowance"));
+			}
+			// An allowance of `U256::MAX` is treated as infinite.
+			if allowance != U256::MAX {
+				Approves::insert((token, from, caller), allowance - value);
+			}
+		}
+		Self::transfer_asset(handle, from, to, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("permit(address,address,uint256,uint256,uint8,bytes32,bytes32)")]
+	#[allow(clippy::too_many_arguments)]
+	fn permit(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+		value: U256,
+		deadline: U256,
+		v: u8,
+		r: H256,
+		s: H256,
+	) -> EvmResult {
+		// Timestamp, nonce and name reads, signature check and nonce write.
+		handle.record_db_read::<Runtime>(3)?;
+		handle.record_cost(ECRecover::BASE)?;
+		handle.record_db_write::<Runtime>(1)?;
+
+		let now: u64 = Runtime::Timestamp::now().unique_saturated_into();
+		if deadline < U256::from(now / 1000) {
+			return Err(revert("permit expired"));
+		}
+
+		let asset_id = Self::asset_id(handle)?;
+		let token = handle.code_address();
+		let nonce = Nonces::get(token, owner);
+		let digest = Self::permit_digest(asset_id, token, owner, spender, value, nonce, deadline);
+		if recover_signer(digest, v, r, s) != Some(owner) {
+			return Err(revert("invalid permit signature"));
+		}
+
+		Nonces::insert(token, owner, nonce.saturating_add(U256::one()));
+		Self::approve_spender(handle, owner, spender, value)
+	}
+
+	#[precompile::public("mint(address,uint256)")]
+	fn mint(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult<bool> {
+		let asset_id = Self::ensure_admin(handle)?;
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_TRANSFER,
+			H160::zero(),
+			to,
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_read::<Runtime>(2)?;
+		handle.record_db_write::<Runtime>(2)?;
+		handle.record_log_costs(&[&log])?;
+
+		let amount = u256_to_balance::<Runtime, Assets>(value)?;
+		let beneficiary = Runtime::AddressMapping::into_account_id(to);
+		Assets::mint_into(asset_id, &beneficiary, amount)
+			.map_err(|e| revert(<&'static str>::from(e)))?;
+
+		log.record(handle)?;
+		Ok(true)
+	}
+
+	#[precompile::public("burn(address,uint256)")]
+	fn burn(handle: &mut impl PrecompileHandle, from: H160, value: U256) -> EvmResult<bool> {
+		let asset_id = Self::ensure_admin(handle)?;
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_TRANSFER,
+			from,
+			H160::zero(),
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_read::<Runtime>(2)?;
+		handle.record_db_write::<Runtime>(2)?;
+		handle.record_log_costs(&[&log])?;
+
+		let amount = u256_to_balance::<Runtime, Assets>(value)?;
+		let who = Runtime::AddressMapping::into_account_id(from);
+		Assets::burn_from(asset_id, &who, amount, Precision::Exact, Fortitude::Polite)
+			.map_err(|e| revert(<&'static str>::from(e)))?;
+
+		log.record(handle)?;
+		Ok(true)
+	}
+}
+
+impl<Runtime, Assets, Prefix> Erc20AssetsPrecompileSet<Runtime, Assets, Prefix>
+where
+	Runtime: pallet_evm::Config,
+	Assets: fungibles::Mutate<AccountIdOf<Runtime>>
+		+ fungibles::metadata::Inspect<AccountIdOf<Runtime>>
+		+ fungibles::roles::Inspect<AccountIdOf<Runtime>>,
+	AssetIdOf<Runtime, Assets>: TryFrom<u128> + Into<u128>,
+	Prefix: Get<[u8; 4]>,
+{
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the address at which the given asset is exposed.
+	pub fn asset_id_to_address(asset_id: AssetIdOf<Runtime, Assets>) -> H160 {
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&Prefix::get());
+		address[4..].copy_from_slice(&Into::<u128>::into(asset_id).to_be_bytes());
+		H160(address)
+	}
+
+	/// Returns the asset id encoded in `address`, if it carries the reserved prefix.
+	pub fn address_to_asset_id(address: H160) -> Option<AssetIdOf<Runtime, Assets>> {
+		if address[..4] != Prefix::get() {
+			return None;
+		}
+		let mut id = [0u8; 16];
+		id.copy_from_slice(&address[4..]);
+		u128::from_be_bytes(id).try_into().ok()
+	}
+
+	/// Returns the EIP-712 digest signed by `owner` to grant `spender` an allowance.
+	pub fn permit_digest(
+		asset_id: AssetIdOf<Runtime, Assets>,
+		token: H160,
+		owner: H160,
+		spender: H160,
+		value: U256,
+		nonce: U256,
+		deadline: U256,
+	) -> [u8; 32] {
+		let message = EvmDataWriter::new()
+			.write(H256(PERMIT_TYPEHASH))
+			.write(owner)
+			.write(spender)
+			.write(value)
+			.write(nonce)
+			.write(deadline)
+			.build();
+
+		let mut payload = b"\x19\x01".to_vec();
+		payload.extend_from_slice(Self::domain_separator(asset_id, token).as_bytes());
+		payload.extend_from_slice(&keccak_256(&message));
+		keccak_256(&payload)
+	}
+
+	/// Returns the separator of the EIP-712 domain of the token at `token`, named after the asset.
+	pub fn domain_separator(asset_id: AssetIdOf<Runtime, Assets>, token: H160) -> H256 {
+		let name = <Assets as fungibles::metadata::Inspect<_>>::name(asset_id);
+		let domain = EvmDataWriter::new()
+			.write(H256(EIP712_DOMAIN_TYPEHASH))
+			.write(H256(keccak_256(&name)))
+			.write(H256(keccak_256(b"1")))
+			.write(U256::from(Runtime::ChainId::get()))
+			.write(token)
+			.build();
+		H256(keccak_256(&domain))
+	}
+
+	/// Returns the asset living at the address of the precompile.
+	fn asset_id(handle: &impl PrecompileHandle) -> EvmResult<AssetIdOf<Runtime, Assets>> {
+		Self::address_to_asset_id(handle.code_address()).ok_or_else(|| revert("unknown asset"))
+	}
+
+	/// Checks that the caller is the admin of the asset, which is returned.
+	fn ensure_admin(handle: &mut impl PrecompileHandle) -> EvmResult<AssetIdOf<Runtime, Assets>> {
+		handle.record_db_read::<Runtime>(1)?;
+		let asset_id = Self::asset_id(handle)?;
+		let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		if <Assets as fungibles::roles::Inspect<_>>::admin(asset_id) != Some(caller) {
+			return Err(revert("caller is not the asset admin"));
+		}
+		Ok(asset_id)
+	}
+
+	fn transfer_asset(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult {
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_TRANSFER,
+			from,
+			to,
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_read::<Runtime>(3)?;
+		handle.record_db_write::<Runtime>(2)?;
+		handle.record_log_costs(&[&log])?;
+
+		let asset_id = Self::asset_id(handle)?;
+		let amount = u256_to_balance::<Runtime, Assets>(value)?;
+		let source = Runtime::AddressMapping::into_account_id(from);
+		let target = Runtime::AddressMapping::into_account_id(to);
+		Assets::transfer(asset_id, &source, &target, amount, Preservation::Expendable)
+			.map_err(|e| revert(<&'static str>::from(e)))?;
+
+		log.record(handle)
+	}
+
+	fn approve_spender(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+		value: U256,
+	) -> EvmResult {
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_APPROVAL,
+			owner,
+			spender,
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_write::<Runtime>(1)?;
+		handle.record_log_costs(&[&log])?;
+
+		Approves::insert((handle.code_address(), owner, spender), value);
+		log.record(handle)
+	}
+}
+
+/// Recovers the signer of `digest` through the `ecrecover` precompile.
+fn recover_signer(digest: [u8; 32], v: u8, r: H256, s: H256) -> Option<H160> {
+	let input = EvmDataWriter::new()
+		.write(H256(digest))
+		.write(v)
+		.write(r)
+		.write(s)
+		.build();
+	let (_, output) = ECRecover::execute(&input, ECRecover::BASE).ok()?;
+	(output.len() == 32).then(|| H160::from_slice(&output[12..]))
+}
+
+fn balance_to_u256<Runtime, Assets>(balance: AssetBalanceOf<Runtime, Assets>) -> U256
+where
+	Runtime: frame_system::Config,
+	Assets: fungibles::Inspect<AccountIdOf<Runtime>>,
+{
+	U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
+
+fn u256_to_balance<Runtime, Assets>(value: U256) -> EvmResult<AssetBalanceOf<Runtime, Assets>>
+where
+	Runtime: frame_system::Config,
+	Assets: fungibles::Inspect<AccountIdOf<Runtime>>,
+{
+	let balance = AssetBalanceOf::<Runtime, Assets>::unique_saturated_from(value.low_u128());
+	if balance_to_u256::<Runtime, Assets>(balance) != value {
+		return Err(revert("value too large for balance type"));
+	}
+	Ok(balance)
+}
@@ -0,0 +1,266 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+
+use fp_evm::ExitSucceed;
+use frame_support::{assert_ok, traits::Currency};
+use pallet_evm::{FeeCalculator, Runner};
+use precompile_utils::{data::encode_with_selector, testing::PrecompileTesterExt};
+use sp_core::{ecdsa, Pair};
+
+type Erc20Assets = Erc20AssetsPrecompileSet<Test, Assets, AssetPrefix>;
+
+const ASSET: u32 = 7;
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+
+const NAME: u32 = 0x06fdde03;
+const DECIMALS: u32 = 0x313ce567;
+const BALANCE_OF: u32 = 0x70a08231;
+const TRANSFER: u32 = 0xa9059cbb;
+const PERMIT: u32 = 0xd505accf;
+const NONCES: u32 = 0x7ecebe00;
+const MINT: u32 = 0x40c10f19;
+const BURN: u32 = 0x9dc29fac;
+
+/// Cost of a `Transfer` or `Approval` log.
+const LOG_COST: u64 = 375 + 3 * 375 + 8 * 32;
+
+fn db_cost(reads: u64, writes: u64) -> u64 {
+	reads * RuntimeHelper::<Test>::db_read_gas_cost()
+		+ writes * RuntimeHelper::<Test>::db_write_gas_cost()
+}
+
+fn token() -> H160 {
+	Erc20Assets::asset_id_to_address(ASSET)
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		assert_ok!(Assets::force_create(
+			RuntimeOrigin::root(),
+			ASSET,
+			ALICE,
+			true,
+			1
+		));
+		assert_ok!(Assets::force_set_metadata(
+			RuntimeOrigin::root(),
+			ASSET,
+			b"Test Token".to_vec(),
+			b"TT".to_vec(),
+			12,
+			false
+		));
+		assert_ok!(Assets::mint(
+			RuntimeOrigin::signed(ALICE),
+			ASSET,
+			ALICE,
+			1_000
+		));
+	});
+	ext
+}
+
+#[test]
+fn asset_addresses_are_prefixed() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			token(),
+			H160::from_slice(&hex_literal::hex!(
+				"ffffffff00000000000000000000000000000007"
+			))
+		);
+		assert_eq!(Erc20Assets::address_to_asset_id(token()), Some(ASSET));
+		assert_eq!(Erc20Assets::address_to_asset_id(ALICE), None);
+
+		let is_precompile = |address| match Erc20Assets::new().is_precompile(address, 0) {
+			IsPrecompileResult::Answer { is_precompile, .. } => is_precompile,
+			_ => unreachable!(),
+		};
+		assert!(is_precompile(token()));
+		assert!(!is_precompile(Erc20Assets::asset_id_to_address(ASSET + 1)));
+		assert!(!is_precompile(ALICE));
+
+		Erc20Assets::new()
+			.prepare_test(
+				ALICE,
+				Erc20Assets::asset_id_to_address(ASSET + 1),
+				NAME.to_be_bytes(),
+			)
+			.execute_none();
+	});
+}
+
+#[test]
+fn transfer_uses_asset_balances() {
+	new_test_ext().execute_with(|| {
+		Erc20Assets::new()
+			.prepare_test(ALICE, token(), NAME.to_be_bytes())
+			.with_static_call(true)
+			.execute_returns(Bytes(b"Test Token".to_vec()));
+		Erc20Assets::new()
+			.prepare_test(ALICE, token(), DECIMALS.to_be_bytes())
+			.with_static_call(true)
+			.execute_returns(12u8);
+
+		Erc20Assets::new()
+			.prepare_test(
+				ALICE,
+				token(),
+				encode_with_selector(TRANSFER, (BOB, U256::from(250))),
+			)
+			.expect_cost(db_cost(3, 2) + LOG_COST)
+			.expect_log(log3(
+				token(),
+				SELECTOR_LOG_TRANSFER,
+				ALICE,
+				BOB,
+				EvmDataWriter::new().write(U256::from(250)).build(),
+			))
+			.execute_returns(true);
+		assert_eq!(Assets::balance(ASSET, BOB), 250);
+
+		Erc20Assets::new()
+			.prepare_test(BOB, token(), encode_with_selector(BALANCE_OF, (BOB,)))
+			.with_static_call(true)
+			.execute_returns(U256::from(250));
+	});
+}
+
+#[test]
+fn permit_grants_allowance_once() {
+	new_test_ext().execute_with(|| {
+		let pair = ecdsa::Pair::from_seed(&[1u8; 32]);
+		// Derive the owner address by recovering the key from a probe signature.
+		let probe = pair.sign_prehashed(&[0u8; 32]);
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&probe.0, &[0u8; 32]).unwrap();
+		let owner = H160::from(H256(keccak_256(&pubkey)));
+
+		let (value, deadline) = (U256::from(100), U256::from(1_000));
+		let digest =
+			Erc20Assets::permit_digest(ASSET, token(), owner, BOB, value, U256::zero(), deadline);
+		let signature = pair.sign_prehashed(&digest).0;
+		let input = EvmDataWriter::new_with_selector(PERMIT)
+			.write(owner)
+			.write(BOB)
+			.write(value)
+			.write(deadline)
+			.write(signature[64] + 27)
+			.write(H256::from_slice(&signature[..32]))
+			.write(H256::from_slice(&signature[32..64]))
+			.build();
+
+		Erc20Assets::new()
+			.prepare_test(BOB, token(), input.clone())
+			.expect_cost(db_cost(3, 2) + ECRecover::BASE + LOG_COST)
+			.expect_log(log3(
+				token(),
+				SELECTOR_LOG_APPROVAL,
+				owner,
+				BOB,
+				EvmDataWriter::new().write(value).build(),
+			))
+			.execute_returns(());
+		assert_eq!(Approves::get((token(), owner, BOB)), value);
+		Erc20Assets::new()
+			.prepare_test(BOB, token(), encode_with_selector(NONCES, (owner,)))
+			.with_static_call(true)
+			.execute_returns(U256::one());
+
+		// The nonce moved on, so the same signature can't be replayed.
+		Erc20Assets::new()
+			.prepare_test(BOB, token(), input)
+			.execute_reverts(|message| message == b"invalid permit signature");
+	});
+}
+
+#[test]
+fn mint_and_burn_are_restricted_to_admin() {
+	new_test_ext().execute_with(|| {
+		Erc20Assets::new()
+			.prepare_test(
+				BOB,
+				token(),
+				encode_with_selector(MINT, (BOB, U256::from(10))),
+			)
+			.execute_reverts(|message| message == b"caller is not the asset admin");
+
+		Erc20Assets::new()
+			.prepare_test(
+				ALICE,
+				token(),
+				encode_with_selector(MINT, (BOB, U256::from(10))),
+			)
+			.expect_log(log3(
+				token(),
+				SELECTOR_LOG_TRANSFER,
+				H160::zero(),
+				BOB,
+				EvmDataWriter::new().write(U256::from(10)).build(),
+			))
+			.execute_returns(true);
+		assert_eq!(Assets::balance(ASSET, BOB), 10);
+
+		Erc20Assets::new()
+			.prepare_test(
+				ALICE,
+				token(),
+				encode_with_selector(BURN, (BOB, U256::from(4))),
+			)
+			.execute_returns(true);
+		assert_eq!(Assets::balance(ASSET, BOB), 6);
+		assert_eq!(Assets::total_supply(ASSET), 1_006);
+	});
+}
+
+#[test]
+fn plain_transfers_do_not_pay_for_the_asset_lookup() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000_000_000_000);
+
+		// Neither the sender nor the recipient carries the prefix, so no asset is read.
+		let info = <Test as pallet_evm::Config>::Runner::call(
+			ALICE,
+			BOB,
+			Vec::new(),
+			U256::one(),
+			21_000,
+			Some(FixedGasPrice::min_gas_price().0),
+			None,
+			None,
+			Vec::new(),
+			true,
+			true,
+			None,
+			None,
+			<Test as pallet_evm::Config>::config(),
+		)
+		.expect("call succeeds");
+		assert_eq!(info.exit_reason, ExitSucceed::Stopped.into());
+		assert_eq!(info.used_gas.standard, U256::from(21_000));
+		assert_eq!(Balances::free_balance(BOB), 1);
+	});
+}
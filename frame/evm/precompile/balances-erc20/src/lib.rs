@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ERC-20 precompile exposing the native currency of the EVM pallet.
+//!
+//! Balances and total supply are read from `pallet_evm::Config::Currency`, while allowances
+//! are kept in storage owned by the precompile. `Transfer` and `Approval` logs are emitted
+//! through the precompile handle, so indexers and wallets see the token as any other ERC-20.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::string::String;
+use core::marker::PhantomData;
+use frame_support::{
+	storage::types::{StorageDoubleMap, ValueQuery},
+	traits::{Currency, ExistenceRequirement, StorageInstance},
+	Blake2_128Concat,
+};
+use pallet_evm::{AddressMapping, BalanceOf};
+use precompile_utils::prelude::*;
+use sp_runtime::traits::{UniqueSaturatedFrom, UniqueSaturatedInto};
+
+/// Topic of the `Transfer(address,address,uint256)` event.
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+/// Topic of the `Approval(address,address,uint256)` event.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// Metadata of the token exposed by [`Erc20BalancesPrecompile`].
+pub trait Erc20Metadata {
+	/// Name of the token, e.g. "Unit".
+	fn name() -> &'static str;
+	/// Symbol of the token, e.g. "UNIT".
+	fn symbol() -> &'static str;
+	/// Number of decimals used by the native currency.
+	fn decimals() -> u8;
+}
+
+/// Storage prefix of the allowances kept by the precompile.
+pub struct ApprovesPrefix;
+
+impl StorageInstance for ApprovesPrefix {
+	const STORAGE_PREFIX: &'static str = "Approves";
+
+	fn pallet_prefix() -> &'static str {
+		"Erc20BalancesPrecompile"
+	}
+}
+
+/// Allowances granted by an owner (first key) to a spender (second key).
+pub type Approves = StorageDoubleMap<
+	ApprovesPrefix,
+	Blake2_128Concat,
+	H160,
+	Blake2_128Concat,
+	H160,
+	U256,
+	ValueQuery,
+>;
+
+pub struct Erc20BalancesPrecompile<Runtime, Metadata>(PhantomData<(Runtime, Metadata)>);
+
+#[precompile]
+impl<Runtime, Metadata> Erc20BalancesPrecompile<Runtime, Metadata>
+where
+	Runtime: pallet_evm::Config,
+	Metadata: Erc20Metadata,
+{
+	#[precompile::pre_check]
+	fn pre_check(handle: &mut impl PrecompileHandle) -> EvmResult {
+		// Transfers are made on behalf of the caller, which a contract delegating to the
+		// precompile would be able to impersonate.
+		if handle.code_address() != handle.context().address {
+			return Err(revert("cannot be called with DELEGATECALL or CALLCODE"));
+		}
+		Ok(())
+	}
+
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name(_handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		Ok(Metadata::name().into())
+	}
+
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol(_handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		Ok(Metadata::symbol().into())
+	}
+
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals(_handle: &mut impl PrecompileHandle) -> EvmResult<u8> {
+		Ok(Metadata::decimals())
+	}
+
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	fn total_supply(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(balance_to_u256::<Runtime>(
+			Runtime::Currency::total_issuance(),
+		))
+	}
+
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	fn balance_of(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		let owner = Runtime::AddressMapping::into_account_id(owner);
+		Ok(balance_to_u256::<Runtime>(Runtime::Currency::free_balance(
+			&owner,
+		)))
+	}
+
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	fn allowance(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+	) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(Approves::get(owner, spender))
+	}
+
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult<bool> {
+		let from = handle.context().caller;
+		Self::transfer_balance(handle, from, to, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("approve(address,uint256)")]
+	fn approve(handle: &mut impl PrecompileHandle, spender: H160, value: U256) -> EvmResult<bool> {
+		let owner = handle.context().caller;
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_APPROVAL,
+			owner,
+			spender,
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_write::<Runtime>(1)?;
+		handle.record_log_costs(&[&log])?;
+
+		Approves::insert(owner, spender, value);
+		log.record(handle)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	fn transfer_from(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult<bool> {
+		let caller = handle.context().caller;
+		if caller != from {
+			handle.record_db_read::<Runtime>(1)?;
+			handle.record_db_write::<Runtime>(1)?;
+			let allowance = Approves::get(from, caller);
+			if allowance < value {
+				return Err(revert("insufficient allowance"));
+			}
+			// An allowance of `U256::MAX` is treated as infinite.
+			if allowance != U256::MAX {
+				Approves::insert(from, caller, allowance - value);
+			}
+		}
+		Self::transfer_balance(handle, from, to, value)?;
+		Ok(true)
+	}
+}
+
+impl<Runtime, Metadata> Erc20BalancesPrecompile<Runtime, Metadata>
+where
+	Runtime: pallet_evm::Config,
+	Metadata: Erc20Metadata,
+{
+	fn transfer_balance(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult {
+		let log = log3(
+			handle.code_address(),
+			SELECTOR_LOG_TRANSFER,
+			from,
+			to,
+			EvmDataWriter::new().write(value).build(),
+		);
+		handle.record_db_read::<Runtime>(2)?;
+		handle.record_db_write::<Runtime>(2)?;
+		handle.record_log_costs(&[&log])?;
+
+		let amount = u256_to_balance::<Runtime>(value)?;
+		let source = Runtime::AddressMapping::into_account_id(from);
+		let target = Runtime::AddressMapping::into_account_id(to);
+		Runtime::Currency::transfer(&source, &target, amount, ExistenceRequirement::AllowDeath)
+			.map_err(|e| revert(<&'static str>::from(e)))?;
+
+		log.record(handle)
+	}
+}
+
+fn balance_to_u256<Runtime: pallet_evm::Config>(balance: BalanceOf<Runtime>) -> U256 {
+	U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+}
+
+fn u256_to_balance<Runtime: pallet_evm::Config>(value: U256) -> EvmResult<BalanceOf<Runtime>> {
+	let balance = BalanceOf::<Runtime>::unique_saturated_from(value.low_u128());
+	if balance_to_u256::<Runtime>(balance) != value {
+		return Err(revert("value too large for balance type"));
+	}
+	Ok(balance)
+}
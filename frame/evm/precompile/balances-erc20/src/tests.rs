@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+
+use precompile_utils::{
+	data::encode_with_selector,
+	testing::{PrecompileTesterExt, SinglePrecompile},
+};
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+const CHARLIE: H160 = H160::repeat_byte(0xcc);
+const PRECOMPILE: H160 = H160::repeat_byte(0x01);
+
+const NAME: u32 = 0x06fdde03;
+const SYMBOL: u32 = 0x95d89b41;
+const DECIMALS: u32 = 0x313ce567;
+const TOTAL_SUPPLY: u32 = 0x18160ddd;
+const BALANCE_OF: u32 = 0x70a08231;
+const ALLOWANCE: u32 = 0xdd62ed3e;
+const TRANSFER: u32 = 0xa9059cbb;
+const APPROVE: u32 = 0x095ea7b3;
+const TRANSFER_FROM: u32 = 0x23b872dd;
+
+/// Cost of a `Transfer` or `Approval` log, the mock runtime having free storage accesses.
+const LOG_COST: u64 = 375 + 3 * 375 + 8 * 32;
+
+fn precompiles() -> SinglePrecompile<Erc20BalancesPrecompile<Test, TestMetadata>> {
+	SinglePrecompile::default()
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000), (BOB, 500)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+fn selector_only(selector: u32) -> Vec<u8> {
+	selector.to_be_bytes().to_vec()
+}
+
+#[test]
+fn metadata_and_balances_are_exposed() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(ALICE, PRECOMPILE, selector_only(NAME))
+			.with_static_call(true)
+			.execute_returns(String::from("Unit"));
+		precompiles()
+			.prepare_test(ALICE, PRECOMPILE, selector_only(SYMBOL))
+			.with_static_call(true)
+			.execute_returns(String::from("UNIT"));
+		precompiles()
+			.prepare_test(ALICE, PRECOMPILE, selector_only(DECIMALS))
+			.with_static_call(true)
+			.execute_returns(18u8);
+		precompiles()
+			.prepare_test(ALICE, PRECOMPILE, selector_only(TOTAL_SUPPLY))
+			.with_static_call(true)
+			.execute_returns(U256::from(1_500));
+		precompiles()
+			.prepare_test(ALICE, PRECOMPILE, encode_with_selector(BALANCE_OF, (BOB,)))
+			.with_static_call(true)
+			.execute_returns(U256::from(500));
+	});
+}
+
+#[test]
+fn transfer_moves_native_balance_and_logs() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER, (BOB, U256::from(400))),
+			)
+			.expect_cost(LOG_COST)
+			.expect_log(log3(
+				PRECOMPILE,
+				SELECTOR_LOG_TRANSFER,
+				ALICE,
+				BOB,
+				EvmDataWriter::new().write(U256::from(400)).build(),
+			))
+			.execute_returns(true);
+		assert_eq!(Balances::free_balance(ALICE), 600);
+		assert_eq!(Balances::free_balance(BOB), 900);
+
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER, (BOB, U256::from(10_000))),
+			)
+			.execute_reverts(|_| true);
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER, (BOB, U256::MAX)),
+			)
+			.execute_reverts(|message| message == b"value too large for balance type");
+	});
+}
+
+#[test]
+fn transfer_from_spends_allowance() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(APPROVE, (CHARLIE, U256::from(300))),
+			)
+			.expect_cost(LOG_COST)
+			.expect_log(log3(
+				PRECOMPILE,
+				SELECTOR_LOG_APPROVAL,
+				ALICE,
+				CHARLIE,
+				EvmDataWriter::new().write(U256::from(300)).build(),
+			))
+			.execute_returns(true);
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(ALLOWANCE, (ALICE, CHARLIE)),
+			)
+			.with_static_call(true)
+			.execute_returns(U256::from(300));
+
+		precompiles()
+			.prepare_test(
+				CHARLIE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER_FROM, (ALICE, BOB, U256::from(200))),
+			)
+			.execute_returns(true);
+		assert_eq!(Approves::get(ALICE, CHARLIE), U256::from(100));
+		assert_eq!(Balances::free_balance(BOB), 700);
+
+		precompiles()
+			.prepare_test(
+				CHARLIE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER_FROM, (ALICE, BOB, U256::from(200))),
+			)
+			.execute_reverts(|message| message == b"insufficient allowance");
+	});
+}
+
+#[test]
+fn state_changes_are_rejected_in_static_context() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				ALICE,
+				PRECOMPILE,
+				encode_with_selector(TRANSFER, (BOB, U256::one())),
+			)
+			.with_static_call(true)
+			.execute_reverts(|message| {
+				message == b"can't call non-static function in static context"
+			});
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+	});
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Batch precompile, performing several calls on behalf of its caller in a single transaction.
+//!
+//! All entry points take the same arrays: the targets of the subcalls, and their values, call
+//! data and gas limits. The last three arrays can be shorter than the targets, missing entries
+//! being zero or empty. A gas limit of zero forwards all the remaining gas to the subcall.
+//! Subcalls are performed with the caller of the batch as `msg.sender`, and each of them emits
+//! either `SubcallSucceeded(uint256)` or `SubcallFailed(uint256)` with its index.
+//!
+//! - `batchSome` performs every subcall, skipping the ones that fail.
+//! - `batchSomeUntilFailure` stops at the first failing subcall.
+//! - `batchAll` reverts the whole batch if any subcall fails.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+use fp_evm::{Context, ExitError, ExitReason, Transfer};
+use precompile_utils::{logs::log_costs, prelude::*};
+
+/// Topic of the `SubcallSucceeded(uint256)` event.
+pub const LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+/// Topic of the `SubcallFailed(uint256)` event.
+pub const LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// Behaviour of a batch when one of its subcalls fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+	/// Skips the failing subcall and performs the next ones.
+	BatchSome,
+	/// Stops at the failing subcall, keeping the effects of the previous ones.
+	BatchSomeUntilFailure,
+	/// Reverts the whole batch.
+	BatchAll,
+}
+
+pub struct BatchPrecompile;
+
+#[precompile]
+impl BatchPrecompile {
+	#[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some(
+		handle: &mut impl PrecompileHandle,
+		to: Vec<H160>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(handle, Mode::BatchSome, to, value, call_data, gas_limit)
+	}
+
+	#[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some_until_failure(
+		handle: &mut impl PrecompileHandle,
+		to: Vec<H160>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchSomeUntilFailure,
+			to,
+			value,
+			call_data,
+			gas_limit,
+		)
+	}
+
+	#[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+	fn batch_all(
+		handle: &mut impl PrecompileHandle,
+		to: Vec<H160>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(handle, Mode::BatchAll, to, value, call_data, gas_limit)
+	}
+}
+
+impl BatchPrecompile {
+	fn batch(
+		handle: &mut impl PrecompileHandle,
+		mode: Mode,
+		to: Vec<H160>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		// Subcalls are made on behalf of the caller, which a contract delegating to the
+		// precompile would be able to impersonate.
+		if handle.code_address() != handle.context().address {
+			return Err(revert("cannot be called with DELEGATECALL or CALLCODE"));
+		}
+		if value.len() > to.len() {
+			return Err(revert("value: more values than targets"));
+		}
+		if call_data.len() > to.len() {
+			return Err(revert("callData: more call data than targets"));
+		}
+		if gas_limit.len() > to.len() {
+			return Err(revert("gasLimit: more gas limits than targets"));
+		}
+
+		let caller = handle.context().caller;
+		let log_cost = log_costs(1, 32)?;
+		let mut call_data = call_data.into_iter();
+
+		for (index, address) in to.into_iter().enumerate() {
+			let value = value.get(index).copied().unwrap_or_default();
+			let input = call_data.next().map(|data| data.0).unwrap_or_default();
+			let gas_limit = gas_limit.get(index).copied().unwrap_or_default();
+
+			// The subcall emits an event whatever its outcome.
+			handle.record_cost(log_cost)?;
+
+			let (reason, output) = if gas_limit > handle.remaining_gas() {
+				(ExitReason::Error(ExitError::OutOfGas), Vec::new())
+			} else {
+				let transfer = (!value.is_zero()).then_some(Transfer {
+					source: caller,
+					target: address,
+					value,
+				});
+				let context = Context {
+					address,
+					caller,
+					apparent_value: value,
+				};
+				handle.call(
+					address,
+					transfer,
+					input,
+					(gas_limit != 0).then_some(gas_limit),
+					false,
+					&context,
+				)
+			};
+
+			let index = EvmDataWriter::new().write(U256::from(index)).build();
+			match reason {
+				ExitReason::Succeed(_) => {
+					log1(handle.code_address(), LOG_SUBCALL_SUCCEEDED, index).record(handle)?;
+				}
+				ExitReason::Fatal(exit_status) => {
+					return Err(PrecompileFailure::Fatal { exit_status });
+				}
+				ExitReason::Revert(exit_status) if mode == Mode::BatchAll => {
+					return Err(PrecompileFailure::Revert {
+						exit_status,
+						output,
+					});
+				}
+				ExitReason::Error(exit_status) if mode == Mode::BatchAll => {
+					return Err(PrecompileFailure::Error { exit_status });
+				}
+				ExitReason::Revert(_) | ExitReason::Error(_) => {
+					log1(handle.code_address(), LOG_SUBCALL_FAILED, index).record(handle)?;
+					if mode == Mode::BatchSomeUntilFailure {
+						break;
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
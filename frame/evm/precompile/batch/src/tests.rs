@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use super::*;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use precompile_utils::{
+	data::encode_with_selector,
+	revert::Revert,
+	testing::{PrecompileTesterExt, SinglePrecompile, Subcall, SubcallOutput},
+};
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+const CHARLIE: H160 = H160::repeat_byte(0xcc);
+const PRECOMPILE: H160 = H160::repeat_byte(0x01);
+
+const BATCH_SOME: u32 = 0x79df4b9c;
+const BATCH_SOME_UNTIL_FAILURE: u32 = 0xcf0491c7;
+const BATCH_ALL: u32 = 0x96e292b8;
+
+fn precompiles() -> SinglePrecompile<BatchPrecompile> {
+	SinglePrecompile::default()
+}
+
+fn batch_input(
+	selector: u32,
+	to: Vec<H160>,
+	value: Vec<U256>,
+	call_data: Vec<Vec<u8>>,
+	gas_limit: Vec<u64>,
+) -> Vec<u8> {
+	let call_data: Vec<Bytes> = call_data.into_iter().map(Bytes).collect();
+	encode_with_selector(selector, (to, value, call_data, gas_limit))
+}
+
+fn subcall_log(topic: [u8; 32], index: u64) -> fp_evm::Log {
+	log1(
+		PRECOMPILE,
+		topic,
+		EvmDataWriter::new().write(U256::from(index)).build(),
+	)
+}
+
+/// Answers subcalls with `outcomes`, in order, and records them in the returned list.
+fn recording_handle(
+	outcomes: Vec<SubcallOutput>,
+) -> (
+	Rc<RefCell<Vec<Subcall>>>,
+	impl FnMut(Subcall) -> SubcallOutput,
+) {
+	let subcalls = Rc::new(RefCell::new(Vec::new()));
+	let recorded = subcalls.clone();
+	let mut outcomes = outcomes.into_iter();
+	let handle = move |subcall: Subcall| {
+		recorded.borrow_mut().push(subcall);
+		outcomes.next().expect("unexpected subcall")
+	};
+	(subcalls, handle)
+}
+
+#[test]
+fn subcalls_are_made_on_behalf_of_the_caller() {
+	let (subcalls, handle) =
+		recording_handle(vec![SubcallOutput::succeed(), SubcallOutput::succeed()]);
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(
+				BATCH_ALL,
+				vec![BOB, CHARLIE],
+				vec![U256::from(5)],
+				vec![vec![0x12, 0x34]],
+				vec![0, 50_000],
+			),
+		)
+		.with_subcall_handle(handle)
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 0))
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 1))
+		.execute_returns(());
+
+	let subcalls = subcalls.borrow();
+	assert_eq!(subcalls.len(), 2);
+
+	assert_eq!(subcalls[0].address, BOB);
+	assert_eq!(
+		subcalls[0].transfer,
+		Some(Transfer {
+			source: ALICE,
+			target: BOB,
+			value: U256::from(5),
+		})
+	);
+	assert_eq!(subcalls[0].input, vec![0x12, 0x34]);
+	assert_eq!(subcalls[0].target_gas, None);
+	assert_eq!(subcalls[0].context.caller, ALICE);
+	assert_eq!(subcalls[0].context.apparent_value, U256::from(5));
+
+	assert_eq!(subcalls[1].address, CHARLIE);
+	assert_eq!(subcalls[1].transfer, None);
+	assert!(subcalls[1].input.is_empty());
+	assert_eq!(subcalls[1].target_gas, Some(50_000));
+	assert_eq!(subcalls[1].context.caller, ALICE);
+}
+
+#[test]
+fn batch_all_reverts_with_the_failing_subcall() {
+	let revert = SubcallOutput {
+		output: Revert::new("insufficient allowance").to_encoded_bytes(),
+		..SubcallOutput::revert()
+	};
+	let (_, handle) = recording_handle(vec![SubcallOutput::succeed(), revert]);
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_ALL, vec![BOB, CHARLIE, BOB], vec![], vec![], vec![]),
+		)
+		.with_subcall_handle(handle)
+		.execute_reverts(|output| output == b"insufficient allowance");
+
+	let error = SubcallOutput {
+		reason: ExitReason::Error(ExitError::Other("invalid opcode".into())),
+		..SubcallOutput::succeed()
+	};
+	let (_, handle) = recording_handle(vec![error]);
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_ALL, vec![BOB, CHARLIE], vec![], vec![], vec![]),
+		)
+		.with_subcall_handle(handle)
+		.execute_error(ExitError::Other("invalid opcode".into()));
+}
+
+#[test]
+fn batch_some_skips_failing_subcalls() {
+	let (subcalls, handle) = recording_handle(vec![
+		SubcallOutput::succeed(),
+		SubcallOutput::revert(),
+		SubcallOutput::succeed(),
+	]);
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_SOME, vec![BOB, CHARLIE, BOB], vec![], vec![], vec![]),
+		)
+		.with_subcall_handle(handle)
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 0))
+		.expect_log(subcall_log(LOG_SUBCALL_FAILED, 1))
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 2))
+		.execute_returns(());
+
+	assert_eq!(subcalls.borrow().len(), 3);
+}
+
+#[test]
+fn batch_some_until_failure_stops_at_the_failing_subcall() {
+	let (subcalls, handle) = recording_handle(vec![
+		SubcallOutput::succeed(),
+		SubcallOutput::revert(),
+		SubcallOutput::succeed(),
+	]);
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(
+				BATCH_SOME_UNTIL_FAILURE,
+				vec![BOB, CHARLIE, BOB],
+				vec![],
+				vec![],
+				vec![],
+			),
+		)
+		.with_subcall_handle(handle)
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 0))
+		.expect_log(subcall_log(LOG_SUBCALL_FAILED, 1))
+		.execute_returns(());
+
+	assert_eq!(subcalls.borrow().len(), 2);
+}
+
+#[test]
+fn gas_limits_above_the_remaining_gas_fail() {
+	let log_cost = log_costs(1, 32).unwrap();
+
+	let (subcalls, handle) = recording_handle(vec![SubcallOutput::succeed()]);
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(
+				BATCH_SOME,
+				vec![BOB, CHARLIE],
+				vec![],
+				vec![],
+				vec![100_000, 1_000],
+			),
+		)
+		.with_target_gas(Some(2 * log_cost + 10_000))
+		.with_subcall_handle(handle)
+		.expect_log(subcall_log(LOG_SUBCALL_FAILED, 0))
+		.expect_log(subcall_log(LOG_SUBCALL_SUCCEEDED, 1))
+		.execute_returns(());
+	assert_eq!(subcalls.borrow()[0].address, CHARLIE);
+
+	let (_, handle) = recording_handle(vec![]);
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_ALL, vec![BOB], vec![], vec![], vec![100_000]),
+		)
+		.with_target_gas(Some(log_cost + 10_000))
+		.with_subcall_handle(handle)
+		.execute_error(ExitError::OutOfGas);
+}
+
+#[test]
+fn arrays_longer_than_targets_revert() {
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(
+				BATCH_ALL,
+				vec![BOB],
+				vec![U256::one(), U256::one()],
+				vec![],
+				vec![],
+			),
+		)
+		.execute_reverts(|output| output == b"value: more values than targets");
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_SOME, vec![], vec![], vec![], vec![0]),
+		)
+		.execute_reverts(|output| output == b"gasLimit: more gas limits than targets");
+}
+
+#[test]
+fn value_is_rejected() {
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			batch_input(BATCH_ALL, vec![BOB], vec![], vec![], vec![]),
+		)
+		.with_value(1)
+		.execute_reverts(|output| output == b"function is not payable");
+}
@@ -0,0 +1,453 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! BLS12-381 precompiles, as specified by EIP-2537.
+//!
+//! Base field elements are encoded as 64-byte big-endian integers whose top 16 bytes are zero,
+//! and must be lower than the field modulus. G1 points are encoded as their affine `x` and `y`
+//! coordinates, G2 points as the `c0` and `c1` components of each of their coordinates, and the
+//! point at infinity as zeroes. Scalars are 32-byte big-endian integers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+// Arkworks
+use ark_bls12_381::{
+	g1, g2, Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective,
+};
+use ark_ec::{
+	hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+	pairing::Pairing,
+	AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInteger384, PrimeField, Zero};
+
+// Frontier
+use fp_evm::{
+	ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+	PrecompileResult,
+};
+
+/// Length of an encoded base field element.
+const FP_LENGTH: usize = 64;
+/// Length of an encoded G1 point.
+const G1_LENGTH: usize = 2 * FP_LENGTH;
+/// Length of an encoded G2 point.
+const G2_LENGTH: usize = 4 * FP_LENGTH;
+/// Length of an encoded scalar.
+const SCALAR_LENGTH: usize = 32;
+
+/// Gas discount table for G1 multi-scalar multiplications, indexed by number of pairs.
+const G1_MSM_DISCOUNT_TABLE: [u16; 128] = [
+	1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+	661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+	613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+	584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+	562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+	544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+	528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// Gas discount table for G2 multi-scalar multiplications, indexed by number of pairs.
+const G2_MSM_DISCOUNT_TABLE: [u16; 128] = [
+	1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+	699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+	629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+	592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+	567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+	548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+	533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+fn error(message: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error {
+		exit_status: ExitError::Other(message.into()),
+	}
+}
+
+/// Encode Fq as `64` bytes by performing Big-Endian encoding of the corresponding (unsigned) integer (top 16 bytes are always zeroes).
+fn encode_fq(field: Fq) -> [u8; FP_LENGTH] {
+	let mut result = [0u8; FP_LENGTH];
+	let rep = field.into_bigint().0;
+	for (i, limb) in rep.iter().enumerate() {
+		let end = FP_LENGTH - 8 * i;
+		result[end - 8..end].copy_from_slice(&limb.to_be_bytes());
+	}
+	result
+}
+
+/// Encode point G1 as byte concatenation of encodings of the `x` and `y` affine coordinates.
+fn encode_g1(g1: G1Affine) -> [u8; G1_LENGTH] {
+	let mut result = [0u8; G1_LENGTH];
+	if !g1.is_zero() {
+		result[0..64].copy_from_slice(&encode_fq(g1.x));
+		result[64..128].copy_from_slice(&encode_fq(g1.y));
+	}
+	result
+}
+
+/// Encode point G2 as byte concatenation of encodings of the `x` and `y` affine coordinates.
+fn encode_g2(g2: G2Affine) -> [u8; G2_LENGTH] {
+	let mut result = [0u8; G2_LENGTH];
+	if !g2.is_zero() {
+		result[0..64].copy_from_slice(&encode_fq(g2.x.c0));
+		result[64..128].copy_from_slice(&encode_fq(g2.x.c1));
+		result[128..192].copy_from_slice(&encode_fq(g2.y.c0));
+		result[192..256].copy_from_slice(&encode_fq(g2.y.c1));
+	}
+	result
+}
+
+/// Decode Fq expects 64 byte input with zero top 16 bytes, returns Fq in base field.
+fn decode_fq(bytes: &[u8]) -> Result<Fq, PrecompileFailure> {
+	if bytes[..16].iter().any(|b| *b != 0) {
+		return Err(error("invalid field element top bytes"));
+	}
+
+	let mut rep = BigInteger384::new([0u64; 6]);
+	for (i, limb) in rep.0.iter_mut().enumerate() {
+		let end = FP_LENGTH - 8 * i;
+		let mut buf = [0u8; 8];
+		buf.copy_from_slice(&bytes[end - 8..end]);
+		*limb = u64::from_be_bytes(buf);
+	}
+
+	Fq::from_bigint(rep).ok_or_else(|| error("invalid fp.Element encoding"))
+}
+
+/// Decode Fq2 expects 128 byte input, the encodings of its `c0` and `c1` components.
+fn decode_fq2(bytes: &[u8]) -> Result<Fq2, PrecompileFailure> {
+	let c0 = decode_fq(&bytes[..FP_LENGTH])?;
+	let c1 = decode_fq(&bytes[FP_LENGTH..2 * FP_LENGTH])?;
+	Ok(Fq2::new(c0, c1))
+}
+
+/// Decode Fr expects 32 byte input, returns fr in scalar field.
+fn decode_fr(bytes: &[u8]) -> Fr {
+	Fr::from_be_bytes_mod_order(&bytes[..SCALAR_LENGTH])
+}
+
+/// Decode G1 given encoded (x, y) coordinates in 128 bytes, checking that the point is on the
+/// curve and, if requested, in the prime order subgroup.
+fn decode_g1(bytes: &[u8], subgroup_check: bool) -> Result<G1Affine, PrecompileFailure> {
+	let px = decode_fq(&bytes[..FP_LENGTH])?;
+	let py = decode_fq(&bytes[FP_LENGTH..G1_LENGTH])?;
+
+	// Check if given input points to infinity
+	if px.is_zero() && py.is_zero() {
+		return Ok(G1Affine::zero());
+	}
+
+	let g1 = G1Affine::new_unchecked(px, py);
+	if !g1.is_on_curve() {
+		return Err(error("invalid point: not on curve"));
+	}
+	if subgroup_check && !g1.is_in_correct_subgroup_assuming_on_curve() {
+		return Err(error("g1 point is not on correct subgroup"));
+	}
+	Ok(g1)
+}
+
+/// Decode G2 given encoded (x, y) coordinates in 256 bytes, checking that the point is on the
+/// curve and, if requested, in the prime order subgroup.
+fn decode_g2(bytes: &[u8], subgroup_check: bool) -> Result<G2Affine, PrecompileFailure> {
+	let px = decode_fq2(&bytes[..2 * FP_LENGTH])?;
+	let py = decode_fq2(&bytes[2 * FP_LENGTH..G2_LENGTH])?;
+
+	// Check if given input points to infinity
+	if px.is_zero() && py.is_zero() {
+		return Ok(G2Affine::zero());
+	}
+
+	let g2 = G2Affine::new_unchecked(px, py);
+	if !g2.is_on_curve() {
+		return Err(error("invalid point: not on curve"));
+	}
+	if subgroup_check && !g2.is_in_correct_subgroup_assuming_on_curve() {
+		return Err(error("g2 point is not on correct subgroup"));
+	}
+	Ok(g2)
+}
+
+/// Returns the gas cost of a multi-scalar multiplication of `k` pairs.
+fn msm_gas_cost(k: usize, multiplication_cost: u64, discount_table: &[u16; 128]) -> u64 {
+	if k == 0 {
+		return 0;
+	}
+	let discount = discount_table[k.min(discount_table.len()) - 1];
+	(k as u64)
+		.saturating_mul(multiplication_cost)
+		.saturating_mul(discount as u64)
+		/ 1_000
+}
+
+fn output(bytes: &[u8]) -> PrecompileResult {
+	Ok(PrecompileOutput {
+		exit_status: ExitSucceed::Returned,
+		output: bytes.to_vec(),
+	})
+}
+
+/// Bls12381G1Add implements EIP-2537 G1ADD precompile.
+pub struct Bls12381G1Add;
+
+impl Bls12381G1Add {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-g1-addition
+	const GAS_COST: u64 = 375;
+}
+
+impl Precompile for Bls12381G1Add {
+	/// Implements EIP-2537 G1ADD precompile.
+	/// > G1 addition call expects `256` bytes as an input that is interpreted as byte concatenation of two G1 points (`128` bytes each).
+	/// > Output is an encoding of addition operation result - single G1 point (`128` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381G1Add::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 2 * G1_LENGTH {
+			return Err(error("invalid input length"));
+		}
+
+		// Points aren't required to be in the subgroup for additions.
+		let p0 = decode_g1(&input[..G1_LENGTH], false)?;
+		let p1 = decode_g1(&input[G1_LENGTH..], false)?;
+		let r = p0.into_group() + p1;
+
+		output(&encode_g1(r.into_affine()))
+	}
+}
+
+/// Bls12381G1Msm implements EIP-2537 G1MSM precompile.
+pub struct Bls12381G1Msm;
+
+impl Bls12381G1Msm {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-g1-multiexponentiation
+	const MULTIPLICATION_COST: u64 = 12_000;
+}
+
+impl Precompile for Bls12381G1Msm {
+	/// Implements EIP-2537 G1MSM precompile.
+	/// > G1 MSM call expects `160*k` bytes as an input that is interpreted as byte concatenation of `k` slices each of them being a byte concatenation of encoding of G1 point (`128` bytes) and encoding of a scalar value (`32` bytes).
+	/// > Output is an encoding of MSM operation result - single G1 point (`128` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		const PAIR_LENGTH: usize = G1_LENGTH + SCALAR_LENGTH;
+
+		let k = handle.input().len() / PAIR_LENGTH;
+		handle.record_cost(msm_gas_cost(
+			k,
+			Bls12381G1Msm::MULTIPLICATION_COST,
+			&G1_MSM_DISCOUNT_TABLE,
+		))?;
+
+		let input = handle.input();
+		if input.is_empty() || input.len() % PAIR_LENGTH != 0 {
+			return Err(error("invalid input length"));
+		}
+
+		let mut points = Vec::with_capacity(k);
+		let mut scalars = Vec::with_capacity(k);
+		for pair in input.chunks_exact(PAIR_LENGTH) {
+			points.push(decode_g1(&pair[..G1_LENGTH], true)?);
+			scalars.push(decode_fr(&pair[G1_LENGTH..]));
+		}
+
+		let r = G1Projective::msm(&points, &scalars).map_err(|_| error("MSM failed"))?;
+
+		output(&encode_g1(r.into_affine()))
+	}
+}
+
+/// Bls12381G2Add implements EIP-2537 G2ADD precompile.
+pub struct Bls12381G2Add;
+
+impl Bls12381G2Add {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-g2-addition
+	const GAS_COST: u64 = 600;
+}
+
+impl Precompile for Bls12381G2Add {
+	/// Implements EIP-2537 G2ADD precompile.
+	/// > G2 addition call expects `512` bytes as an input that is interpreted as byte concatenation of two G2 points (`256` bytes each).
+	/// > Output is an encoding of addition operation result - single G2 point (`256` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381G2Add::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 2 * G2_LENGTH {
+			return Err(error("invalid input length"));
+		}
+
+		// Points aren't required to be in the subgroup for additions.
+		let p0 = decode_g2(&input[..G2_LENGTH], false)?;
+		let p1 = decode_g2(&input[G2_LENGTH..], false)?;
+		let r = p0.into_group() + p1;
+
+		output(&encode_g2(r.into_affine()))
+	}
+}
+
+/// Bls12381G2Msm implements EIP-2537 G2MSM precompile.
+pub struct Bls12381G2Msm;
+
+impl Bls12381G2Msm {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-g2-multiexponentiation
+	const MULTIPLICATION_COST: u64 = 22_500;
+}
+
+impl Precompile for Bls12381G2Msm {
+	/// Implements EIP-2537 G2MSM precompile.
+	/// > G2 MSM call expects `288*k` bytes as an input that is interpreted as byte concatenation of `k` slices each of them being a byte concatenation of encoding of G2 point (`256` bytes) and encoding of a scalar value (`32` bytes).
+	/// > Output is an encoding of MSM operation result - single G2 point (`256` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		const PAIR_LENGTH: usize = G2_LENGTH + SCALAR_LENGTH;
+
+		let k = handle.input().len() / PAIR_LENGTH;
+		handle.record_cost(msm_gas_cost(
+			k,
+			Bls12381G2Msm::MULTIPLICATION_COST,
+			&G2_MSM_DISCOUNT_TABLE,
+		))?;
+
+		let input = handle.input();
+		if input.is_empty() || input.len() % PAIR_LENGTH != 0 {
+			return Err(error("invalid input length"));
+		}
+
+		let mut points = Vec::with_capacity(k);
+		let mut scalars = Vec::with_capacity(k);
+		for pair in input.chunks_exact(PAIR_LENGTH) {
+			points.push(decode_g2(&pair[..G2_LENGTH], true)?);
+			scalars.push(decode_fr(&pair[G2_LENGTH..]));
+		}
+
+		let r = G2Projective::msm(&points, &scalars).map_err(|_| error("MSM failed"))?;
+
+		output(&encode_g2(r.into_affine()))
+	}
+}
+
+/// Bls12381PairingCheck implements EIP-2537 PAIRING_CHECK precompile.
+pub struct Bls12381PairingCheck;
+
+impl Bls12381PairingCheck {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-pairing-check
+	const BASE_GAS: u64 = 37_700;
+	const PER_PAIR_GAS: u64 = 32_600;
+}
+
+impl Precompile for Bls12381PairingCheck {
+	/// Implements EIP-2537 PAIRING_CHECK precompile.
+	/// > Pairing check call expects `384*k` bytes as an inputs that is interpreted as byte concatenation of `k` slices. Each slice has the following structure:
+	/// > - `128` bytes of G1 point encoding
+	/// > - `256` bytes of G2 point encoding
+	/// > Output is a `32` bytes where last single byte is `0x01` if pairing result is equal to multiplicative identity in a pairing target field and `0x00` otherwise.
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		const PAIR_LENGTH: usize = G1_LENGTH + G2_LENGTH;
+
+		let k = handle.input().len() / PAIR_LENGTH;
+		handle.record_cost(
+			Bls12381PairingCheck::BASE_GAS
+				.saturating_add((k as u64).saturating_mul(Bls12381PairingCheck::PER_PAIR_GAS)),
+		)?;
+
+		let input = handle.input();
+		if input.is_empty() || input.len() % PAIR_LENGTH != 0 {
+			return Err(error("invalid input length"));
+		}
+
+		let mut a = Vec::with_capacity(k);
+		let mut b = Vec::with_capacity(k);
+		for pair in input.chunks_exact(PAIR_LENGTH) {
+			a.push(decode_g1(&pair[..G1_LENGTH], true)?);
+			b.push(decode_g2(&pair[G1_LENGTH..], true)?);
+		}
+
+		let mut result = [0u8; 32];
+		if Bls12_381::multi_pairing(a, b).is_zero() {
+			result[31] = 1;
+		}
+
+		output(&result)
+	}
+}
+
+/// Bls12381MapFpToG1 implements EIP-2537 MAP_FP_TO_G1 precompile.
+pub struct Bls12381MapFpToG1;
+
+impl Bls12381MapFpToG1 {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-mapping-fp-element-to-g1-point
+	const GAS_COST: u64 = 5_500;
+}
+
+impl Precompile for Bls12381MapFpToG1 {
+	/// Implements EIP-2537 MAP_FP_TO_G1 precompile.
+	/// > Field-to-curve call expects `64` bytes as an input that is interpreted as an element of the base field.
+	/// > Output of this call is `128` bytes and is an encoded G1 point.
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381MapFpToG1::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != FP_LENGTH {
+			return Err(error("invalid input length"));
+		}
+
+		let fp = decode_fq(input)?;
+		// Simplified SWU map followed by the clearing of the cofactor, as in RFC 9380.
+		let point = WBMap::<g1::Config>::new()
+			.and_then(|map| map.map_to_curve(fp))
+			.map_err(|_| error("map to curve failed"))?;
+
+		output(&encode_g1(point.clear_cofactor()))
+	}
+}
+
+/// Bls12381MapFp2ToG2 implements EIP-2537 MAP_FP2_TO_G2 precompile.
+pub struct Bls12381MapFp2ToG2;
+
+impl Bls12381MapFp2ToG2 {
+	/// https://eips.ethereum.org/EIPS/eip-2537#abi-for-mapping-fp2-element-to-g2-point
+	const GAS_COST: u64 = 23_800;
+}
+
+impl Precompile for Bls12381MapFp2ToG2 {
+	/// Implements EIP-2537 MAP_FP2_TO_G2 precompile.
+	/// > Field-to-curve call expects `128` bytes as an input that is interpreted as an element of the quadratic extension field.
+	/// > Output of this call is `256` bytes and is an encoded G2 point.
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381MapFp2ToG2::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 2 * FP_LENGTH {
+			return Err(error("invalid input length"));
+		}
+
+		let fp2 = decode_fq2(input)?;
+		// Simplified SWU map followed by the clearing of the cofactor, as in RFC 9380.
+		let point = WBMap::<g2::Config>::new()
+			.and_then(|map| map.map_to_curve(fp2))
+			.map_err(|_| error("map to curve failed"))?;
+
+		output(&encode_g2(point.clear_cofactor()))
+	}
+}
+
+#[cfg(test)]
+mod tests;
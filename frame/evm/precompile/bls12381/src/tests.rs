@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use pallet_evm_test_vector_support::{
+	test_precompile_failure_test_vectors, test_precompile_test_vectors,
+};
+
+#[test]
+fn process_consensus_tests() -> Result<(), String> {
+	test_precompile_test_vectors::<Bls12381G1Add>("../testdata/bls12381G1Add.json")?;
+	test_precompile_test_vectors::<Bls12381G1Msm>("../testdata/bls12381G1Msm.json")?;
+	test_precompile_test_vectors::<Bls12381G2Add>("../testdata/bls12381G2Add.json")?;
+	test_precompile_test_vectors::<Bls12381G2Msm>("../testdata/bls12381G2Msm.json")?;
+	test_precompile_test_vectors::<Bls12381PairingCheck>("../testdata/bls12381Pairing.json")?;
+	test_precompile_test_vectors::<Bls12381MapFpToG1>("../testdata/bls12381MapG1.json")?;
+	test_precompile_test_vectors::<Bls12381MapFp2ToG2>("../testdata/bls12381MapG2.json")?;
+	Ok(())
+}
+
+#[test]
+fn process_consensus_failure_tests() -> Result<(), String> {
+	test_precompile_failure_test_vectors::<Bls12381G1Add>("../testdata/fail-bls12381G1Add.json")?;
+	test_precompile_failure_test_vectors::<Bls12381G1Msm>("../testdata/fail-bls12381G1Msm.json")?;
+	test_precompile_failure_test_vectors::<Bls12381G2Add>("../testdata/fail-bls12381G2Add.json")?;
+	test_precompile_failure_test_vectors::<Bls12381G2Msm>("../testdata/fail-bls12381G2Msm.json")?;
+	test_precompile_failure_test_vectors::<Bls12381PairingCheck>(
+		"../testdata/fail-bls12381Pairing.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381MapFpToG1>(
+		"../testdata/fail-bls12381MapG1.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381MapFp2ToG2>(
+		"../testdata/fail-bls12381MapG2.json",
+	)?;
+	Ok(())
+}
+
+#[test]
+fn msm_gas_cost_is_discounted() {
+	assert_eq!(msm_gas_cost(0, 12_000, &G1_MSM_DISCOUNT_TABLE), 0);
+	assert_eq!(msm_gas_cost(1, 12_000, &G1_MSM_DISCOUNT_TABLE), 12_000);
+	assert_eq!(msm_gas_cost(2, 22_500, &G2_MSM_DISCOUNT_TABLE), 45_000);
+	assert_eq!(msm_gas_cost(128, 12_000, &G1_MSM_DISCOUNT_TABLE), 797_184);
+	assert_eq!(msm_gas_cost(200, 12_000, &G1_MSM_DISCOUNT_TABLE), 1_245_600);
+	assert_eq!(msm_gas_cost(200, 22_500, &G2_MSM_DISCOUNT_TABLE), 2_358_000);
+}
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! EIP-712 call permit precompile, letting a relayer dispatch calls signed by their sender.
+//!
+//! The sender signs a `CallPermit(from, to, value, data, gasLimit, nonce, deadline)` message
+//! within the domain of the precompile, and anyone can then submit it to `dispatch`, which
+//! performs the call with `from` as `msg.sender` and pays for it. Nonces are kept in storage
+//! owned by the precompile, so every permit can be dispatched only once.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitReason, LinearCostPrecompile, Transfer};
+use frame_support::{
+	storage::types::{StorageMap, ValueQuery},
+	traits::{Get, StorageInstance, Time},
+	Blake2_128Concat,
+};
+use pallet_evm_precompile_simple::ECRecover;
+use precompile_utils::prelude::*;
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::UniqueSaturatedInto;
+
+/// Type hash of the EIP-712 domain, with its name, version, chain id and verifying contract.
+pub const EIP712_DOMAIN_TYPEHASH: [u8; 32] = keccak256!(
+	"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+);
+/// Type hash of the `CallPermit` struct signed by the sender of a call.
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+	"CallPermit(address from,address to,uint256 value,bytes data,uint64 gasLimit,uint256 nonce,uint256 deadline)"
+);
+/// Name of the EIP-712 domain of the precompile.
+pub const PERMIT_DOMAIN_NAME: &[u8] = b"Call Permit Precompile";
+
+/// Upper bound of the cost of a `CALL` transferring value to a cold and empty account, recorded
+/// by the EVM on top of the gas limit of the call.
+const CALL_COST: u64 = 2_600 + 9_000 + 25_000;
+
+/// Storage prefix of the nonces kept by the precompile.
+pub struct NoncesPrefix;
+
+impl StorageInstance for NoncesPrefix {
+	const STORAGE_PREFIX: &'static str = "Nonces";
+
+	fn pallet_prefix() -> &'static str {
+		"CallPermitPrecompile"
+	}
+}
+
+/// Permit nonces keyed by the account dispatching the calls.
+pub type Nonces = StorageMap<NoncesPrefix, Blake2_128Concat, H160, U256, ValueQuery>;
+
+pub struct CallPermitPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile]
+impl<Runtime: pallet_evm::Config> CallPermitPrecompile<Runtime> {
+	#[precompile::public(
+		"dispatch(address,address,uint256,bytes,uint64,uint256,uint8,bytes32,bytes32)"
+	)]
+	#[allow(clippy::too_many_arguments)]
+	fn dispatch(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+		data: Bytes,
+		gas_limit: u64,
+		deadline: U256,
+		v: u8,
+		r: H256,
+		s: H256,
+	) -> EvmResult<Bytes> {
+		// Timestamp and nonce reads, hash of the call data, signature check and nonce write.
+		handle.record_db_read::<Runtime>(2)?;
+		handle.record_cost(keccak_cost(data.0.len()))?;
+		handle.record_cost(ECRecover::BASE)?;
+		handle.record_db_write::<Runtime>(1)?;
+
+		let now: u64 = Runtime::Timestamp::now().unique_saturated_into();
+		if deadline < U256::from(now / 1000) {
+			return Err(revert("permit expired"));
+		}
+
+		let nonce = Nonces::get(from);
+		let digest = Self::permit_digest(
+			handle.code_address(),
+			from,
+			to,
+			value,
+			&data.0,
+			gas_limit,
+			nonce,
+			deadline,
+		);
+		if recover_signer(digest, v, r, s) != Some(from) {
+			return Err(revert("invalid permit"));
+		}
+
+		// The signer expects the call to be able to use its whole gas limit, which the EVM
+		// caps to 63/64 of the gas left once the cost of the call is paid.
+		let available = handle.remaining_gas().saturating_sub(CALL_COST);
+		if gas_limit > available - available / 64 {
+			return Err(revert("gas limit is too low to dispatch the call"));
+		}
+
+		// The nonce moves on before the call so that it can't replay the permit.
+		Nonces::insert(from, nonce.saturating_add(U256::one()));
+
+		let transfer = (!value.is_zero()).then_some(Transfer {
+			source: from,
+			target: to,
+			value,
+		});
+		let context = Context {
+			address: to,
+			caller: from,
+			apparent_value: value,
+		};
+		let (reason, output) = handle.call(to, transfer, data.0, Some(gas_limit), false, &context);
+
+		match reason {
+			ExitReason::Succeed(_) => Ok(Bytes(output)),
+			ExitReason::Revert(exit_status) => Err(PrecompileFailure::Revert {
+				exit_status,
+				output,
+			}),
+			ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+			ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+		}
+	}
+
+	#[precompile::public("nonces(address)")]
+	#[precompile::view]
+	fn nonces(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(Nonces::get(owner))
+	}
+
+	#[precompile::public("DOMAIN_SEPARATOR()")]
+	#[precompile::view]
+	fn domain_separator_of(handle: &mut impl PrecompileHandle) -> EvmResult<H256> {
+		// Chain id read.
+		handle.record_db_read::<Runtime>(1)?;
+		Ok(Self::domain_separator(handle.code_address()))
+	}
+}
+
+impl<Runtime: pallet_evm::Config> CallPermitPrecompile<Runtime> {
+	/// Returns the EIP-712 digest signed by `from` to allow dispatching a call.
+	#[allow(clippy::too_many_arguments)]
+	pub fn permit_digest(
+		address: H160,
+		from: H160,
+		to: H160,
+		value: U256,
+		data: &[u8],
+		gas_limit: u64,
+		nonce: U256,
+		deadline: U256,
+	) -> [u8; 32] {
+		let message = EvmDataWriter::new()
+			.write(H256(PERMIT_TYPEHASH))
+			.write(from)
+			.write(to)
+			.write(value)
+			.write(H256(keccak_256(data)))
+			.write(gas_limit)
+			.write(nonce)
+			.write(deadline)
+			.build();
+
+		let mut payload = b"\x19\x01".to_vec();
+		payload.extend_from_slice(Self::domain_separator(address).as_bytes());
+		payload.extend_from_slice(&keccak_256(&message));
+		keccak_256(&payload)
+	}
+
+	/// Returns the separator of the EIP-712 domain of the precompile at `address`.
+	pub fn domain_separator(address: H160) -> H256 {
+		let domain = EvmDataWriter::new()
+			.write(H256(EIP712_DOMAIN_TYPEHASH))
+			.write(H256(keccak_256(PERMIT_DOMAIN_NAME)))
+			.write(H256(keccak_256(b"1")))
+			.write(U256::from(Runtime::ChainId::get()))
+			.write(address)
+			.build();
+		H256(keccak_256(&domain))
+	}
+}
+
+/// Recovers the signer of `digest` through the `ecrecover` precompile.
+fn recover_signer(digest: [u8; 32], v: u8, r: H256, s: H256) -> Option<H160> {
+	let input = EvmDataWriter::new()
+		.write(H256(digest))
+		.write(v)
+		.write(r)
+		.write(s)
+		.build();
+	let (_, output) = ECRecover::execute(&input, ECRecover::BASE).ok()?;
+	(output.len() == 32).then(|| H160::from_slice(&output[12..]))
+}
+
+/// Gas cost of hashing `len` bytes with the `SHA3` opcode.
+fn keccak_cost(len: usize) -> u64 {
+	let words = (len as u64).saturating_add(31) / 32;
+	30u64.saturating_add(words.saturating_mul(6))
+}
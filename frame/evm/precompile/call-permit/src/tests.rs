@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use super::*;
+use crate::mock::*;
+
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use precompile_utils::{
+	data::encode_with_selector,
+	testing::{PrecompileTesterExt, SinglePrecompile, Subcall, SubcallOutput},
+};
+use sp_core::{ecdsa, Pair};
+
+const BOB: H160 = H160::repeat_byte(0xbb);
+const RELAYER: H160 = H160::repeat_byte(0xee);
+const PRECOMPILE: H160 = H160::repeat_byte(0x01);
+
+const DISPATCH: u32 = 0xb5ea0966;
+const NONCES: u32 = 0x7ecebe00;
+const DOMAIN_SEPARATOR: u32 = 0x3644e515;
+
+fn precompiles() -> SinglePrecompile<CallPermitPrecompile<Test>> {
+	SinglePrecompile::default()
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	sp_io::TestExternalities::new(t)
+}
+
+fn signer() -> (ecdsa::Pair, H160) {
+	let pair = ecdsa::Pair::from_seed(&[1u8; 32]);
+	// Derive the address by recovering the key from a probe signature.
+	let probe = pair.sign_prehashed(&[0u8; 32]);
+	let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&probe.0, &[0u8; 32]).unwrap();
+	(pair, H160::from(H256(keccak_256(&pubkey))))
+}
+
+struct Permit {
+	from: H160,
+	to: H160,
+	value: U256,
+	data: Vec<u8>,
+	gas_limit: u64,
+	deadline: U256,
+}
+
+impl Permit {
+	fn to_bob(from: H160) -> Self {
+		Self {
+			from,
+			to: BOB,
+			value: U256::from(5),
+			data: vec![0x12, 0x34],
+			gas_limit: 100_000,
+			deadline: U256::from(10),
+		}
+	}
+
+	fn signed_by(&self, pair: &ecdsa::Pair, nonce: U256) -> Vec<u8> {
+		let digest = CallPermitPrecompile::<Test>::permit_digest(
+			PRECOMPILE,
+			self.from,
+			self.to,
+			self.value,
+			&self.data,
+			self.gas_limit,
+			nonce,
+			self.deadline,
+		);
+		let signature = pair.sign_prehashed(&digest).0;
+		EvmDataWriter::new_with_selector(DISPATCH)
+			.write(self.from)
+			.write(self.to)
+			.write(self.value)
+			.write(Bytes(self.data.clone()))
+			.write(self.gas_limit)
+			.write(self.deadline)
+			.write(signature[64] + 27)
+			.write(H256::from_slice(&signature[..32]))
+			.write(H256::from_slice(&signature[32..64]))
+			.build()
+	}
+}
+
+fn recording_handle(
+	output: SubcallOutput,
+) -> (
+	Rc<RefCell<Vec<Subcall>>>,
+	impl FnMut(Subcall) -> SubcallOutput,
+) {
+	let subcalls = Rc::new(RefCell::new(Vec::new()));
+	let recorded = subcalls.clone();
+	let handle = move |subcall: Subcall| {
+		recorded.borrow_mut().push(subcall);
+		output.clone()
+	};
+	(subcalls, handle)
+}
+
+#[test]
+fn dispatch_calls_on_behalf_of_the_signer() {
+	new_test_ext().execute_with(|| {
+		let (pair, alice) = signer();
+		let permit = Permit::to_bob(alice);
+		let (subcalls, handle) = recording_handle(SubcallOutput {
+			output: vec![0x42],
+			..SubcallOutput::succeed()
+		});
+
+		precompiles()
+			.prepare_test(RELAYER, PRECOMPILE, permit.signed_by(&pair, U256::zero()))
+			.with_subcall_handle(handle)
+			.expect_cost(keccak_cost(2) + ECRecover::BASE)
+			.expect_no_logs()
+			.execute_returns(Bytes(vec![0x42]));
+
+		let subcalls = subcalls.borrow();
+		assert_eq!(subcalls.len(), 1);
+		assert_eq!(subcalls[0].address, BOB);
+		assert_eq!(
+			subcalls[0].transfer,
+			Some(Transfer {
+				source: alice,
+				target: BOB,
+				value: U256::from(5),
+			})
+		);
+		assert_eq!(subcalls[0].input, vec![0x12, 0x34]);
+		assert_eq!(subcalls[0].target_gas, Some(100_000));
+		assert_eq!(subcalls[0].context.caller, alice);
+		assert_eq!(Nonces::get(alice), U256::one());
+	});
+}
+
+#[test]
+fn permits_cannot_be_replayed() {
+	new_test_ext().execute_with(|| {
+		let (pair, alice) = signer();
+		let input = Permit::to_bob(alice).signed_by(&pair, U256::zero());
+
+		let (_, handle) = recording_handle(SubcallOutput::succeed());
+		precompiles()
+			.prepare_test(RELAYER, PRECOMPILE, input.clone())
+			.with_subcall_handle(handle)
+			.execute_some();
+
+		precompiles()
+			.prepare_test(RELAYER, PRECOMPILE, input)
+			.execute_reverts(|output| output == b"invalid permit");
+
+		precompiles()
+			.prepare_test(RELAYER, PRECOMPILE, encode_with_selector(NONCES, (alice,)))
+			.with_static_call(true)
+			.execute_returns(U256::one());
+	});
+}
+
+#[test]
+fn invalid_permits_revert() {
+	new_test_ext().execute_with(|| {
+		let (pair, alice) = signer();
+
+		// Signed for another sender.
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				Permit::to_bob(BOB).signed_by(&pair, U256::zero()),
+			)
+			.execute_reverts(|output| output == b"invalid permit");
+
+		// Signed with a nonce that isn't the current one.
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				Permit::to_bob(alice).signed_by(&pair, U256::one()),
+			)
+			.execute_reverts(|output| output == b"invalid permit");
+
+		// Past its deadline.
+		pallet_timestamp::Pallet::<Test>::set_timestamp(11_000);
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				Permit::to_bob(alice).signed_by(&pair, U256::zero()),
+			)
+			.execute_reverts(|output| output == b"permit expired");
+	});
+}
+
+#[test]
+fn dispatch_forwards_the_failure_of_the_call() {
+	new_test_ext().execute_with(|| {
+		let (pair, alice) = signer();
+		let (_, handle) = recording_handle(SubcallOutput {
+			output: b"nope".to_vec(),
+			..SubcallOutput::revert()
+		});
+
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				Permit::to_bob(alice).signed_by(&pair, U256::zero()),
+			)
+			.with_subcall_handle(handle)
+			.execute_reverts(|output| output == b"nope");
+	});
+}
+
+#[test]
+fn dispatch_requires_the_signed_gas_limit() {
+	new_test_ext().execute_with(|| {
+		let (pair, alice) = signer();
+
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				Permit::to_bob(alice).signed_by(&pair, U256::zero()),
+			)
+			.with_target_gas(Some(120_000))
+			.execute_reverts(|output| output == b"gas limit is too low to dispatch the call");
+	});
+}
+
+#[test]
+fn domain_separator_follows_eip712() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				RELAYER,
+				PRECOMPILE,
+				encode_with_selector(DOMAIN_SEPARATOR, ()),
+			)
+			.with_static_call(true)
+			.execute_returns(H256(hex_literal::hex!(
+				"645054bbabfaa6720da6e300ea1172bff4b2f9ae88a9852f8f35ea0874c396d1"
+			)));
+	});
+}
@@ -166,6 +166,7 @@ impl pallet_evm::Config for Test {
 	type GasLimitPovSizeRatio = ();
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
+	type ForkSchedule = ();
 }
 
 pub(crate) struct MockHandle {
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! secp256r1 (P-256) signature verification precompile, as specified by RIP-7212.
+//!
+//! The input is made of the 32-byte message hash, the `r` and `s` scalars of the signature and
+//! the `x` and `y` coordinates of the public key, each 32 bytes long. The precompile returns
+//! `1` as a 32-byte word if the signature is valid, and nothing otherwise. Like `ecrecover`,
+//! it never fails, so that callers can't tell malformed inputs from invalid signatures.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fp_evm::{ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+use p256::{
+	ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey},
+	EncodedPoint, FieldBytes,
+};
+
+/// The P256VERIFY precompile.
+pub struct P256Verify;
+
+impl LinearCostPrecompile for P256Verify {
+	const BASE: u64 = 3450;
+	const WORD: u64 = 0;
+
+	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		let result = if verify(input) {
+			let mut valid = [0u8; 32];
+			valid[31] = 1;
+			valid.to_vec()
+		} else {
+			Vec::new()
+		};
+
+		Ok((ExitSucceed::Returned, result))
+	}
+}
+
+/// Checks the signature of a RIP-7212 input, which must be exactly 160 bytes long.
+fn verify(input: &[u8]) -> bool {
+	if input.len() != 160 {
+		return false;
+	}
+
+	let hash = &input[0..32];
+	// Rejects `r` and `s` outside of `[1, n - 1]`, but not high `s` values.
+	let signature = match Signature::from_slice(&input[32..96]) {
+		Ok(signature) => signature,
+		Err(_) => return false,
+	};
+	// Rejects non-canonical coordinates and points outside of the curve.
+	let point = EncodedPoint::from_affine_coordinates(
+		FieldBytes::from_slice(&input[96..128]),
+		FieldBytes::from_slice(&input[128..160]),
+		false,
+	);
+	let key = match VerifyingKey::from_encoded_point(&point) {
+		Ok(key) => key,
+		Err(_) => return false,
+	};
+
+	key.verify_prehash(hash, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_evm_test_vector_support::test_precompile_test_vectors;
+
+	#[test]
+	fn process_consensus_tests_for_p256verify() -> Result<(), String> {
+		test_precompile_test_vectors::<P256Verify>("../testdata/p256Verify.json")?;
+		Ok(())
+	}
+}
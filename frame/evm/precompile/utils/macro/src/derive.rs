@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Fields};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+	let fields = match &input.data {
+		Data::Struct(DataStruct {
+			fields: Fields::Named(fields),
+			..
+		}) => &fields.named,
+		_ => {
+			return Err(syn::Error::new_spanned(
+				&input.ident,
+				"`EvmData` can only be derived for structs with named fields",
+			))
+		}
+	};
+
+	let ident = &input.ident;
+	let field_idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+	let field_names: Vec<_> = fields
+		.iter()
+		.map(|f| {
+			f.ident
+				.as_ref()
+				.map(ToString::to_string)
+				.unwrap_or_default()
+		})
+		.collect();
+	let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+	let mut generics = input.generics.clone();
+	let where_clause = generics.make_where_clause();
+	for ty in &field_types {
+		where_clause
+			.predicates
+			.push(parse_quote!(#ty: ::precompile_utils::data::EvmData));
+	}
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::precompile_utils::data::EvmData for #ident #ty_generics #where_clause {
+			fn read(
+				reader: &mut ::precompile_utils::data::EvmDataReader,
+			) -> ::precompile_utils::revert::MayRevert<Self> {
+				let is_static = <Self as ::precompile_utils::data::EvmData>::has_static_size();
+				reader.read_tuple(is_static, |reader| {
+					Ok(Self {
+						#(
+							#field_idents: ::precompile_utils::revert::InjectBacktrace::in_field(
+								<#field_types as ::precompile_utils::data::EvmData>::read(reader),
+								#field_names,
+							)?,
+						)*
+					})
+				})
+			}
+
+			fn write(writer: &mut ::precompile_utils::data::EvmDataWriter, value: Self) {
+				let is_static = <Self as ::precompile_utils::data::EvmData>::has_static_size();
+				writer.write_tuple(is_static, |writer| {
+					#(
+						<#field_types as ::precompile_utils::data::EvmData>::write(
+							writer,
+							value.#field_idents,
+						);
+					)*
+				});
+			}
+
+			fn has_static_size() -> bool {
+				true #(&& <#field_types as ::precompile_utils::data::EvmData>::has_static_size())*
+			}
+
+			fn signature() -> ::precompile_utils::data::String {
+				::precompile_utils::data::tuple_signature(&[
+					#(<#field_types as ::precompile_utils::data::EvmData>::signature()),*
+				])
+			}
+		}
+	})
+}
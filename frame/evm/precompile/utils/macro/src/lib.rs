@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Procedural macros of `precompile-utils`. Use them through the re-exports of that crate.
+
+#![deny(unused_crate_dependencies)]
+
+mod derive;
+mod precompile;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use sha3::{Digest, Keccak256};
+use syn::{parse_macro_input, DeriveInput, ItemImpl, LitStr};
+
+/// Computes the Keccak-256 hash of a string literal at compile time, as a `[u8; 32]`.
+///
+/// ```ignore
+/// const TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+/// ```
+#[proc_macro]
+pub fn keccak256(input: TokenStream) -> TokenStream {
+	let literal = parse_macro_input!(input as LitStr);
+	let hash = Keccak256::digest(literal.value().as_bytes());
+	let bytes = hash.iter();
+	quote!([#(#bytes),*]).into()
+}
+
+/// Generates a `fp_evm::Precompile` implementation dispatching on Solidity selectors.
+///
+/// Every function of the annotated `impl` block marked with `#[precompile::public("sig")]`
+/// becomes callable with the selector of `sig`. Functions take the precompile handle followed
+/// by their Solidity arguments, all implementing `EvmData`, and return an `EvmResult` of an
+/// `EvmData` value. Functions are non-payable by default; `#[precompile::view]` allows them
+/// in static calls and `#[precompile::payable]` lets them receive value.
+///
+/// A single function taking only the handle and returning an `EvmResult` can be marked with
+/// `#[precompile::pre_check]`, it then runs before any other function, e.g. to reject calls
+/// made through `DELEGATECALL`.
+///
+/// ```ignore
+/// #[precompile_utils::precompile]
+/// impl<Runtime: pallet_evm::Config> Erc20<Runtime> {
+///     #[precompile::public("balanceOf(address)")]
+///     #[precompile::view]
+///     fn balance_of(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+///         ...
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn precompile(attr: TokenStream, item: TokenStream) -> TokenStream {
+	if !attr.is_empty() {
+		return syn::Error::new(
+			proc_macro2::Span::call_site(),
+			"`precompile` doesn't take any argument",
+		)
+		.to_compile_error()
+		.into();
+	}
+	let item = parse_macro_input!(item as ItemImpl);
+	precompile::expand(item)
+		.unwrap_or_else(|e| e.to_compile_error())
+		.into()
+}
+
+/// Derives `EvmData` for a struct with named fields, encoded as a Solidity tuple of its fields.
+#[proc_macro_derive(EvmData)]
+pub fn derive_evm_data(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	derive::expand(input)
+		.unwrap_or_else(|e| e.to_compile_error())
+		.into()
+}
@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
+use syn::{
+	spanned::Spanned, Attribute, FnArg, Ident, ImplItem, ItemImpl, LitInt, LitStr, Pat, Type,
+};
+
+enum Modifier {
+	NonPayable,
+	Payable,
+	View,
+}
+
+struct Function {
+	ident: Ident,
+	selectors: Vec<u32>,
+	modifier: Modifier,
+	args: Vec<(Ident, Type)>,
+}
+
+/// Returns the name of a `#[precompile::name]` attribute.
+fn precompile_attribute(attr: &Attribute) -> Option<String> {
+	let segments = &attr.path().segments;
+	if segments.len() == 2 && segments[0].ident == "precompile" {
+		Some(segments[1].ident.to_string())
+	} else {
+		None
+	}
+}
+
+/// Checks that `signature` looks like `name(type,...)` with `args` arguments.
+fn check_signature(signature: &LitStr, args: usize) -> syn::Result<()> {
+	let value = signature.value();
+	let (name, rest) = match value.split_once('(') {
+		Some(split) => split,
+		None => {
+			return Err(syn::Error::new(
+				signature.span(),
+				"expected `name(type,...)`",
+			))
+		}
+	};
+	if name.is_empty() || !rest.ends_with(')') || value.contains(char::is_whitespace) {
+		return Err(syn::Error::new(
+			signature.span(),
+			"expected `name(type,...)` without whitespace",
+		));
+	}
+
+	let types = &rest[..rest.len() - 1];
+	let mut depth = 0i32;
+	let mut count = usize::from(!types.is_empty());
+	for c in types.chars() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => count += 1,
+			_ => {}
+		}
+	}
+	if count != args {
+		return Err(syn::Error::new(
+			signature.span(),
+			format!("signature has {count} argument(s) but the function takes {args}"),
+		));
+	}
+	Ok(())
+}
+
+fn selector(signature: &str) -> u32 {
+	let hash = Keccak256::digest(signature.as_bytes());
+	u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+fn parse_function(method: &mut syn::ImplItemFn) -> syn::Result<Option<Function>> {
+	let mut signatures = Vec::new();
+	let mut modifier = None;
+	for attr in core::mem::take(&mut method.attrs) {
+		let name = match precompile_attribute(&attr) {
+			Some(name) => name,
+			None => {
+				method.attrs.push(attr);
+				continue;
+			}
+		};
+		let new_modifier = match name.as_str() {
+			"public" => {
+				signatures.push(attr.parse_args::<LitStr>()?);
+				continue;
+			}
+			"view" => Modifier::View,
+			"payable" => Modifier::Payable,
+			_ => {
+				return Err(syn::Error::new(
+					attr.span(),
+					format!("unknown attribute `precompile::{name}`"),
+				))
+			}
+		};
+		if modifier.replace(new_modifier).is_some() {
+			return Err(syn::Error::new(
+				attr.span(),
+				"a function can't be both `view` and `payable`",
+			));
+		}
+	}
+
+	if signatures.is_empty() {
+		if modifier.is_some() {
+			return Err(syn::Error::new(
+				method.sig.ident.span(),
+				"modifiers require a `#[precompile::public(\"...\")]` signature",
+			));
+		}
+		return Ok(None);
+	}
+
+	let mut inputs = method.sig.inputs.iter();
+	match inputs.next() {
+		Some(FnArg::Typed(_)) => {}
+		Some(FnArg::Receiver(receiver)) => {
+			return Err(syn::Error::new(
+				receiver.span(),
+				"precompile functions can't take `self`",
+			))
+		}
+		None => {
+			return Err(syn::Error::new(
+				method.sig.span(),
+				"precompile functions must take the precompile handle first",
+			))
+		}
+	}
+	let mut args = Vec::new();
+	for input in inputs {
+		match input {
+			FnArg::Typed(arg) => match &*arg.pat {
+				Pat::Ident(pat) => args.push((pat.ident.clone(), (*arg.ty).clone())),
+				pat => {
+					return Err(syn::Error::new(
+						pat.span(),
+						"expected a plain argument name",
+					))
+				}
+			},
+			FnArg::Receiver(receiver) => {
+				return Err(syn::Error::new(
+					receiver.span(),
+					"precompile functions can't take `self`",
+				))
+			}
+		}
+	}
+
+	let mut selectors = Vec::new();
+	for signature in &signatures {
+		check_signature(signature, args.len())?;
+		selectors.push(selector(&signature.value()));
+	}
+
+	Ok(Some(Function {
+		ident: method.sig.ident.clone(),
+		selectors,
+		modifier: modifier.unwrap_or(Modifier::NonPayable),
+		args,
+	}))
+}
+
+fn expand_function(function: &Function) -> TokenStream {
+	let ident = &function.ident;
+	let selectors = function
+		.selectors
+		.iter()
+		.map(|selector| LitInt::new(&format!("{selector:#010x}u32"), Span::call_site()));
+	let modifier = match function.modifier {
+		Modifier::NonPayable => quote!(NonPayable),
+		Modifier::Payable => quote!(Payable),
+		Modifier::View => quote!(View),
+	};
+	let arg_idents: Vec<_> = function.args.iter().map(|(ident, _)| ident).collect();
+	let arg_types = function.args.iter().map(|(_, ty)| ty);
+	let arg_names = function.args.iter().map(|(ident, _)| ident.to_string());
+
+	let read_args = if function.args.is_empty() {
+		quote!()
+	} else {
+		quote! {
+			let mut reader = handle.read_after_selector()?;
+			#(
+				let #arg_idents: #arg_types = ::precompile_utils::revert::InjectBacktrace::in_field(
+					reader.read(),
+					#arg_names,
+				)?;
+			)*
+		}
+	};
+
+	quote! {
+		#(#selectors)|* => {
+			handle.check_function_modifier(
+				::precompile_utils::handle::FunctionModifier::#modifier,
+			)?;
+			#read_args
+			let output = Self::#ident(handle, #(#arg_idents),*)?;
+			Ok(::precompile_utils::fp_evm::PrecompileOutput {
+				exit_status: ::precompile_utils::fp_evm::ExitSucceed::Returned,
+				output: ::precompile_utils::data::EvmDataWriter::new().write(output).build(),
+			})
+		}
+	}
+}
+
+/// Removes the `#[precompile::pre_check]` attribute of `method`, returning whether it had one.
+fn take_pre_check(method: &mut syn::ImplItemFn) -> bool {
+	let len = method.attrs.len();
+	method
+		.attrs
+		.retain(|attr| precompile_attribute(attr).as_deref() != Some("pre_check"));
+	method.attrs.len() != len
+}
+
+pub fn expand(mut item: ItemImpl) -> syn::Result<TokenStream> {
+	let mut functions = Vec::new();
+	let mut pre_check: Option<Ident> = None;
+	for impl_item in &mut item.items {
+		if let ImplItem::Fn(method) = impl_item {
+			if take_pre_check(method) {
+				if let Some(other) = pre_check.replace(method.sig.ident.clone()) {
+					return Err(syn::Error::new(
+						method.sig.ident.span(),
+						format!("`{other}` is already the pre-check"),
+					));
+				}
+			}
+			if let Some(function) = parse_function(method)? {
+				if pre_check.as_ref() == Some(&function.ident) {
+					return Err(syn::Error::new(
+						function.ident.span(),
+						"a pre-check can't be a public function",
+					));
+				}
+				functions.push(function);
+			}
+		}
+	}
+
+	let mut seen = BTreeMap::new();
+	for function in &functions {
+		for selector in &function.selectors {
+			if let Some(other) = seen.insert(*selector, &function.ident) {
+				return Err(syn::Error::new(
+					function.ident.span(),
+					format!("selector {selector:#010x} is already used by `{other}`"),
+				));
+			}
+		}
+	}
+
+	let arms = functions.iter().map(expand_function);
+	let pre_check = pre_check.map(|ident| quote!(Self::#ident(handle)?;));
+	let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+	let self_ty = &item.self_ty;
+
+	Ok(quote! {
+		#item
+
+		impl #impl_generics ::precompile_utils::fp_evm::Precompile for #self_ty #where_clause {
+			fn execute(
+				handle: &mut impl ::precompile_utils::fp_evm::PrecompileHandle,
+			) -> ::precompile_utils::fp_evm::PrecompileResult {
+				use ::precompile_utils::handle::PrecompileHandleExt as _;
+
+				#pre_check
+				match handle.read_selector()? {
+					#(#arms)*
+					_ => Err(::precompile_utils::revert::revert("unknown selector")),
+				}
+			}
+		}
+	})
+}
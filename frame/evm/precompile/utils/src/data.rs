@@ -0,0 +1,539 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI encoding and decoding.
+//!
+//! Values are read and written through [`EvmData`]. Static values are stored inline, while
+//! dynamic ones (`bytes`, `string`, dynamic arrays and tuples containing them) are stored
+//! behind a pointer to the tail of the enclosing tuple, as specified by the Solidity ABI.
+
+pub use alloc::string::String;
+
+use crate::revert::{InjectBacktrace, MayRevert, Revert};
+use alloc::{format, vec::Vec};
+use sp_core::{H160, H256, U256};
+
+/// A value that can be read from and written to Solidity ABI data.
+pub trait EvmData: Sized {
+	/// Reads the value at the current position of the reader.
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self>;
+
+	/// Writes the value at the current position of the writer.
+	fn write(writer: &mut EvmDataWriter, value: Self);
+
+	/// Whether the value is stored inline rather than behind a pointer.
+	fn has_static_size() -> bool;
+
+	/// Solidity type of the value, as used in function signatures.
+	fn signature() -> String;
+}
+
+/// Returns the signature of a tuple made of elements with the given signatures.
+pub fn tuple_signature(elements: &[String]) -> String {
+	format!("({})", elements.join(","))
+}
+
+/// Reader of ABI-encoded data.
+#[derive(Clone, Copy, Debug)]
+pub struct EvmDataReader<'a> {
+	input: &'a [u8],
+	cursor: usize,
+}
+
+impl<'a> EvmDataReader<'a> {
+	/// Reader of `input`, which must not start with a function selector.
+	pub fn new(input: &'a [u8]) -> Self {
+		Self { input, cursor: 0 }
+	}
+
+	/// Reader of the arguments following the function selector of `input`.
+	pub fn new_skip_selector(input: &'a [u8]) -> MayRevert<Self> {
+		match input.get(4..) {
+			Some(arguments) => Ok(Self::new(arguments)),
+			None => Err(Revert::new("tried to parse selector out of bounds")),
+		}
+	}
+
+	/// Reads the function selector of `input`.
+	pub fn read_selector(input: &[u8]) -> MayRevert<u32> {
+		match input.get(..4) {
+			Some(selector) => Ok(u32::from_be_bytes([
+				selector[0],
+				selector[1],
+				selector[2],
+				selector[3],
+			])),
+			None => Err(Revert::new("tried to parse selector out of bounds")),
+		}
+	}
+
+	/// Reads a value of type `T`.
+	pub fn read<T: EvmData>(&mut self) -> MayRevert<T> {
+		T::read(self)
+	}
+
+	/// Reads `len` raw bytes and moves the cursor past them.
+	pub fn read_raw_bytes(&mut self, len: usize) -> MayRevert<&'a [u8]> {
+		let range = self
+			.cursor
+			.checked_add(len)
+			.and_then(|end| self.input.get(self.cursor..end))
+			.ok_or_else(|| Revert::new("tried to parse out of bounds"))?;
+		self.cursor += len;
+		Ok(range)
+	}
+
+	/// Reads a 32-byte word.
+	fn read_word(&mut self) -> MayRevert<&'a [u8]> {
+		self.read_raw_bytes(32)
+	}
+
+	/// Reads an offset and returns a reader of the data it points to.
+	pub fn read_pointer(&mut self) -> MayRevert<Self> {
+		let offset = self.read_length()?;
+		match self.input.get(offset..) {
+			Some(input) if offset < self.input.len() => Ok(Self::new(input)),
+			_ => Err(Revert::new("pointer points out of bounds")),
+		}
+	}
+
+	/// Reads a word holding a length or an offset.
+	fn read_length(&mut self) -> MayRevert<usize> {
+		let value = self.read::<U256>()?;
+		if value > U256::from(u32::MAX) {
+			return Err(Revert::new("length or offset is too large"));
+		}
+		Ok(value.low_u64() as usize)
+	}
+
+	/// Reads a tuple with `f`, inline if it is static and behind a pointer otherwise.
+	pub fn read_tuple<T>(
+		&mut self,
+		is_static: bool,
+		f: impl FnOnce(&mut EvmDataReader<'a>) -> MayRevert<T>,
+	) -> MayRevert<T> {
+		if is_static {
+			f(self)
+		} else {
+			let mut inner = self.read_pointer()?;
+			f(&mut inner)
+		}
+	}
+}
+
+/// Writer of ABI-encoded data.
+#[derive(Clone, Debug, Default)]
+pub struct EvmDataWriter {
+	data: Vec<u8>,
+	/// Positions of the pointers in `data`, along with the data they point to.
+	offset_data: Vec<(usize, Vec<u8>)>,
+	selector: Option<u32>,
+}
+
+impl EvmDataWriter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Writer of a call to the function with the given selector.
+	pub fn new_with_selector(selector: u32) -> Self {
+		Self {
+			selector: Some(selector),
+			..Default::default()
+		}
+	}
+
+	/// Writes a value of type `T`.
+	pub fn write<T: EvmData>(mut self, value: T) -> Self {
+		T::write(&mut self, value);
+		self
+	}
+
+	/// Appends raw bytes to the inline data.
+	pub fn write_raw_bytes(&mut self, bytes: &[u8]) {
+		self.data.extend_from_slice(bytes);
+	}
+
+	/// Writes a pointer to `data`, which is appended after the inline data.
+	pub fn write_pointer(&mut self, data: Vec<u8>) {
+		self.offset_data.push((self.data.len(), data));
+		self.data.extend_from_slice(&[0u8; 32]);
+	}
+
+	/// Writes a tuple with `f`, inline if it is static and behind a pointer otherwise.
+	pub fn write_tuple(&mut self, is_static: bool, f: impl FnOnce(&mut EvmDataWriter)) {
+		if is_static {
+			f(self)
+		} else {
+			let mut inner = EvmDataWriter::new();
+			f(&mut inner);
+			self.write_pointer(inner.build());
+		}
+	}
+
+	/// Returns the encoded data, prefixed with the selector if any.
+	pub fn build(self) -> Vec<u8> {
+		let mut output = self.data;
+		for (position, data) in self.offset_data {
+			U256::from(output.len()).to_big_endian(&mut output[position..position + 32]);
+			output.extend(data);
+		}
+		match self.selector {
+			Some(selector) => {
+				let mut with_selector = selector.to_be_bytes().to_vec();
+				with_selector.extend(output);
+				with_selector
+			}
+			None => output,
+		}
+	}
+}
+
+/// Returns `value` as a 32-byte word.
+fn word(value: U256) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	value.to_big_endian(&mut word);
+	word
+}
+
+/// Returns `bytes` prefixed with their length and padded to a multiple of 32 bytes.
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+	let mut data = word(bytes.len().into()).to_vec();
+	data.extend_from_slice(bytes);
+	data.resize(32 + (bytes.len() + 31) / 32 * 32, 0);
+	data
+}
+
+/// Reads bytes stored behind a pointer as a length followed by the bytes themselves.
+fn read_bytes<'a>(reader: &mut EvmDataReader<'a>) -> MayRevert<&'a [u8]> {
+	let mut inner = reader.read_pointer()?;
+	let len = inner.read_length().in_field("length")?;
+	inner.read_raw_bytes(len)
+}
+
+impl EvmData for () {
+	fn read(_reader: &mut EvmDataReader) -> MayRevert<Self> {
+		Ok(())
+	}
+
+	fn write(_writer: &mut EvmDataWriter, _value: Self) {}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		"()".into()
+	}
+}
+
+impl EvmData for U256 {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		Ok(U256::from_big_endian(reader.read_word()?))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_raw_bytes(&word(value));
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		"uint256".into()
+	}
+}
+
+macro_rules! impl_evmdata_for_uints {
+	($($uint:ty),*) => {
+		$(
+			impl EvmData for $uint {
+				fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+					let value = reader.read::<U256>()?;
+					if value > U256::from(<$uint>::MAX) {
+						return Err(Revert::new("value too large for type"));
+					}
+					Ok(value.low_u128() as $uint)
+				}
+
+				fn write(writer: &mut EvmDataWriter, value: Self) {
+					U256::write(writer, value.into());
+				}
+
+				fn has_static_size() -> bool {
+					true
+				}
+
+				fn signature() -> String {
+					format!("uint{}", <$uint>::BITS)
+				}
+			}
+		)*
+	};
+}
+
+impl_evmdata_for_uints!(u8, u16, u32, u64, u128);
+
+impl EvmData for bool {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		match reader.read::<U256>()? {
+			value if value.is_zero() => Ok(false),
+			value if value == U256::one() => Ok(true),
+			_ => Err(Revert::new("value too large for type")),
+		}
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		U256::write(writer, u8::from(value).into());
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		"bool".into()
+	}
+}
+
+impl EvmData for H256 {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		Ok(H256::from_slice(reader.read_word()?))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_raw_bytes(value.as_bytes());
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		"bytes32".into()
+	}
+}
+
+impl EvmData for H160 {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		let word = reader.read_word()?;
+		if word[..12].iter().any(|b| *b != 0) {
+			return Err(Revert::new("value too large for type"));
+		}
+		Ok(H160::from_slice(&word[12..]))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		H256::write(writer, value.into());
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		"address".into()
+	}
+}
+
+/// Solidity `bytes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self(bytes)
+	}
+}
+
+impl From<&[u8]> for Bytes {
+	fn from(bytes: &[u8]) -> Self {
+		Self(bytes.to_vec())
+	}
+}
+
+impl EvmData for Bytes {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		Ok(Self(read_bytes(reader)?.to_vec()))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_pointer(encode_bytes(&value.0));
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		"bytes".into()
+	}
+}
+
+impl EvmData for String {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		let bytes = read_bytes(reader)?;
+		String::from_utf8(bytes.to_vec()).map_err(|_| Revert::new("invalid utf-8 string"))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_pointer(encode_bytes(value.as_bytes()));
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		"string".into()
+	}
+}
+
+impl<T: EvmData> EvmData for Vec<T> {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		let mut inner = reader.read_pointer()?;
+		let len = inner.read_length().in_field("length")?;
+
+		// Elements are stored after the length, and pointers are relative to them.
+		let mut elements = EvmDataReader::new(&inner.input[inner.cursor..]);
+		// Every element takes at least one word, which bounds the allocation below.
+		if len.saturating_mul(32) > elements.input.len() {
+			return Err(Revert::new("array length exceeds input"));
+		}
+		let mut array = Vec::with_capacity(len);
+		for index in 0..len {
+			array.push(elements.read::<T>().in_field(&format!("[{}]", index))?);
+		}
+		Ok(array)
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		let mut data = word(value.len().into()).to_vec();
+		let mut elements = EvmDataWriter::new();
+		for element in value {
+			T::write(&mut elements, element);
+		}
+		data.extend(elements.build());
+		writer.write_pointer(data);
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		format!("{}[]", T::signature())
+	}
+}
+
+impl<T: EvmData, const N: usize> EvmData for [T; N] {
+	fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+		reader
+			.read_tuple(Self::has_static_size(), |reader| {
+				let mut array = Vec::with_capacity(N);
+				for index in 0..N {
+					array.push(reader.read::<T>().in_field(&format!("[{}]", index))?);
+				}
+				Ok(array)
+			})?
+			.try_into()
+			.map_err(|_| Revert::new("unexpected array length"))
+	}
+
+	fn write(writer: &mut EvmDataWriter, value: Self) {
+		writer.write_tuple(Self::has_static_size(), |writer| {
+			for element in value {
+				T::write(writer, element);
+			}
+		});
+	}
+
+	fn has_static_size() -> bool {
+		T::has_static_size()
+	}
+
+	fn signature() -> String {
+		format!("{}[{}]", T::signature(), N)
+	}
+}
+
+macro_rules! impl_evmdata_for_tuples {
+	($(($($ty:ident),+)),+) => {
+		$(
+			impl<$($ty: EvmData),+> EvmData for ($($ty,)+) {
+				fn read(reader: &mut EvmDataReader) -> MayRevert<Self> {
+					reader.read_tuple(Self::has_static_size(), |reader| {
+						Ok(($(reader.read::<$ty>()?,)+))
+					})
+				}
+
+				#[allow(non_snake_case)]
+				fn write(writer: &mut EvmDataWriter, value: Self) {
+					let ($($ty,)+) = value;
+					writer.write_tuple(Self::has_static_size(), |writer| {
+						$($ty::write(writer, $ty);)+
+					});
+				}
+
+				fn has_static_size() -> bool {
+					true $(&& $ty::has_static_size())+
+				}
+
+				fn signature() -> String {
+					tuple_signature(&[$($ty::signature()),+])
+				}
+			}
+		)+
+	};
+}
+
+impl_evmdata_for_tuples!(
+	(A),
+	(A, B),
+	(A, B, C),
+	(A, B, C, D),
+	(A, B, C, D, E),
+	(A, B, C, D, E, F),
+	(A, B, C, D, E, F, G),
+	(A, B, C, D, E, F, G, H)
+);
+
+/// Encodes a single value, e.g. the return data of a function.
+pub fn encode<T: EvmData>(value: T) -> Vec<u8> {
+	EvmDataWriter::new().write(value).build()
+}
+
+/// Encodes a call to the function with the given selector.
+///
+/// The arguments are laid out as the content of a tuple, without a leading pointer.
+pub fn encode_with_selector<T: EvmData>(selector: u32, arguments: T) -> Vec<u8> {
+	let encoded = encode(arguments);
+	let arguments = if T::has_static_size() {
+		&encoded[..]
+	} else {
+		&encoded[32..]
+	};
+	let mut output = selector.to_be_bytes().to_vec();
+	output.extend_from_slice(arguments);
+	output
+}
+
+/// Decodes a single value, e.g. the return data of a function.
+pub fn decode<T: EvmData>(input: &[u8]) -> MayRevert<T> {
+	EvmDataReader::new(input).read()
+}
@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extension methods of the precompile handle.
+
+use crate::{
+	data::EvmDataReader,
+	logs::LogExt,
+	revert::{MayRevert, Revert},
+	substrate::RuntimeHelper,
+};
+use fp_evm::{ExitError, Log, PrecompileHandle};
+
+/// How a function deals with value transfers and static calls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionModifier {
+	/// Doesn't modify the state: callable in static calls, but rejects value.
+	View,
+	/// Modifies the state and rejects value.
+	NonPayable,
+	/// Modifies the state and accepts value.
+	Payable,
+}
+
+pub trait PrecompileHandleExt: PrecompileHandle {
+	/// Reads the function selector of the input.
+	fn read_selector(&self) -> MayRevert<u32>;
+
+	/// Returns a reader of the arguments following the function selector.
+	fn read_after_selector(&self) -> MayRevert<EvmDataReader<'_>>;
+
+	/// Checks that the call context is compatible with the function modifier.
+	fn check_function_modifier(&self, modifier: FunctionModifier) -> MayRevert;
+
+	/// Records the cost of `reads` database reads.
+	fn record_db_read<Runtime: pallet_evm::Config>(&mut self, reads: u64) -> Result<(), ExitError>;
+
+	/// Records the cost of `writes` database writes.
+	fn record_db_write<Runtime: pallet_evm::Config>(
+		&mut self,
+		writes: u64,
+	) -> Result<(), ExitError>;
+
+	/// Records the cost of emitting the given logs.
+	fn record_log_costs(&mut self, logs: &[&Log]) -> Result<(), ExitError>;
+}
+
+impl<T: PrecompileHandle> PrecompileHandleExt for T {
+	fn read_selector(&self) -> MayRevert<u32> {
+		EvmDataReader::read_selector(self.input())
+	}
+
+	fn read_after_selector(&self) -> MayRevert<EvmDataReader<'_>> {
+		EvmDataReader::new_skip_selector(self.input())
+	}
+
+	fn check_function_modifier(&self, modifier: FunctionModifier) -> MayRevert {
+		if modifier != FunctionModifier::Payable && !self.context().apparent_value.is_zero() {
+			return Err(Revert::new("function is not payable"));
+		}
+		if modifier != FunctionModifier::View && self.is_static() {
+			return Err(Revert::new(
+				"can't call non-static function in static context",
+			));
+		}
+		Ok(())
+	}
+
+	fn record_db_read<Runtime: pallet_evm::Config>(&mut self, reads: u64) -> Result<(), ExitError> {
+		self.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(reads))
+	}
+
+	fn record_db_write<Runtime: pallet_evm::Config>(
+		&mut self,
+		writes: u64,
+	) -> Result<(), ExitError> {
+		self.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost().saturating_mul(writes))
+	}
+
+	fn record_log_costs(&mut self, logs: &[&Log]) -> Result<(), ExitError> {
+		let mut cost = 0u64;
+		for log in logs {
+			cost = cost
+				.checked_add(log.compute_cost()?)
+				.ok_or(ExitError::OutOfGas)?;
+		}
+		self.record_cost(cost)
+	}
+}
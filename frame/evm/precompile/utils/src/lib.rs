@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to write Solidity-facing precompiles on top of `fp_evm::PrecompileHandle`.
+//!
+//! - [`data`] encodes and decodes the Solidity ABI, including dynamic arrays, `bytes`,
+//!   `string` and structs (through `#[derive(EvmData)]`).
+//! - [`precompile`] generates the selector dispatch of a precompile from an `impl` block.
+//! - [`revert`] builds `Error(string)` reverts, with the argument that failed to decode.
+//! - [`handle`], [`logs`] and [`substrate`] record gas costs against the handle.
+//! - `testing` (behind the `testing` feature) runs precompiles against a mock handle.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+// Allows the macros to refer to `::precompile_utils` from within this crate.
+extern crate self as precompile_utils;
+
+pub mod data;
+pub mod handle;
+pub mod logs;
+pub mod revert;
+pub mod substrate;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(test)]
+mod tests;
+
+#[doc(hidden)]
+pub use fp_evm;
+pub use precompile_utils_macro::{keccak256, precompile, EvmData};
+
+use fp_evm::PrecompileFailure;
+
+/// Result of a precompile function.
+pub type EvmResult<T = ()> = Result<T, PrecompileFailure>;
+
+pub mod prelude {
+	pub use crate::{
+		data::{Bytes, EvmData, EvmDataReader, EvmDataWriter},
+		handle::{FunctionModifier, PrecompileHandleExt},
+		keccak256,
+		logs::{log0, log1, log2, log3, log4, LogExt},
+		precompile,
+		revert::{revert, InjectBacktrace, MayRevert, Revert},
+		substrate::RuntimeHelper,
+		EvmResult,
+	};
+	pub use fp_evm::{PrecompileFailure, PrecompileHandle};
+	pub use sp_core::{H160, H256, U256};
+}
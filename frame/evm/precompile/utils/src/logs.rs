@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Logs emitted by precompiles.
+
+use crate::EvmResult;
+use alloc::{vec, vec::Vec};
+use fp_evm::{ExitError, Log, PrecompileHandle};
+use sp_core::{H160, H256};
+
+/// Base gas cost of a `LOG` opcode.
+const G_LOG: u64 = 375;
+/// Gas cost of each topic of a `LOG` opcode.
+const G_LOG_TOPIC: u64 = 375;
+/// Gas cost of each byte of data of a `LOG` opcode.
+const G_LOG_DATA: u64 = 8;
+
+/// Returns the gas cost of a log with `topics` topics and `data_len` bytes of data.
+pub fn log_costs(topics: usize, data_len: usize) -> Result<u64, ExitError> {
+	let topics_cost = G_LOG_TOPIC
+		.checked_mul(topics as u64)
+		.ok_or(ExitError::OutOfGas)?;
+	let data_cost = G_LOG_DATA
+		.checked_mul(data_len as u64)
+		.ok_or(ExitError::OutOfGas)?;
+	G_LOG
+		.checked_add(topics_cost)
+		.and_then(|cost| cost.checked_add(data_cost))
+		.ok_or(ExitError::OutOfGas)
+}
+
+fn log(address: impl Into<H160>, topics: Vec<H256>, data: impl Into<Vec<u8>>) -> Log {
+	Log {
+		address: address.into(),
+		topics,
+		data: data.into(),
+	}
+}
+
+pub fn log0(address: impl Into<H160>, data: impl Into<Vec<u8>>) -> Log {
+	log(address, vec![], data)
+}
+
+pub fn log1(address: impl Into<H160>, topic0: impl Into<H256>, data: impl Into<Vec<u8>>) -> Log {
+	log(address, vec![topic0.into()], data)
+}
+
+pub fn log2(
+	address: impl Into<H160>,
+	topic0: impl Into<H256>,
+	topic1: impl Into<H256>,
+	data: impl Into<Vec<u8>>,
+) -> Log {
+	log(address, vec![topic0.into(), topic1.into()], data)
+}
+
+pub fn log3(
+	address: impl Into<H160>,
+	topic0: impl Into<H256>,
+	topic1: impl Into<H256>,
+	topic2: impl Into<H256>,
+	data: impl Into<Vec<u8>>,
+) -> Log {
+	log(
+		address,
+		vec![topic0.into(), topic1.into(), topic2.into()],
+		data,
+	)
+}
+
+pub fn log4(
+	address: impl Into<H160>,
+	topic0: impl Into<H256>,
+	topic1: impl Into<H256>,
+	topic2: impl Into<H256>,
+	topic3: impl Into<H256>,
+	data: impl Into<Vec<u8>>,
+) -> Log {
+	log(
+		address,
+		vec![topic0.into(), topic1.into(), topic2.into(), topic3.into()],
+		data,
+	)
+}
+
+pub trait LogExt {
+	/// Emits the log through the handle. Its cost must be recorded beforehand.
+	fn record(self, handle: &mut impl PrecompileHandle) -> EvmResult;
+
+	/// Returns the gas cost of emitting the log.
+	fn compute_cost(&self) -> Result<u64, ExitError>;
+}
+
+impl LogExt for Log {
+	fn record(self, handle: &mut impl PrecompileHandle) -> EvmResult {
+		handle.log(self.address, self.topics, self.data)?;
+		Ok(())
+	}
+
+	fn compute_cost(&self) -> Result<u64, ExitError> {
+		log_costs(self.topics.len(), self.data.len())
+	}
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity reverts.
+
+use crate::data::EvmDataWriter;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use fp_evm::{ExitRevert, PrecompileFailure};
+
+/// Selector of the Solidity `Error(string)` revert payload.
+pub const ERROR_SELECTOR: u32 = 0x08c379a0;
+
+/// Result of an operation that can only fail with a revert, such as ABI decoding.
+pub type MayRevert<T = ()> = Result<T, Revert>;
+
+/// Revert reason, along with the path of the value that caused it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Revert {
+	reason: String,
+	/// Fields leading to the failing value, innermost first.
+	backtrace: Vec<String>,
+}
+
+impl Revert {
+	pub fn new(reason: impl Into<String>) -> Self {
+		Self {
+			reason: reason.into(),
+			backtrace: Vec::new(),
+		}
+	}
+
+	/// Encodes the revert as a Solidity `Error(string)`.
+	pub fn to_encoded_bytes(&self) -> Vec<u8> {
+		EvmDataWriter::new_with_selector(ERROR_SELECTOR)
+			.write(alloc::format!("{}", self))
+			.build()
+	}
+}
+
+impl fmt::Display for Revert {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, field) in self.backtrace.iter().rev().enumerate() {
+			if i > 0 {
+				f.write_str(".")?;
+			}
+			f.write_str(field)?;
+		}
+		if !self.backtrace.is_empty() {
+			f.write_str(": ")?;
+		}
+		f.write_str(&self.reason)
+	}
+}
+
+impl From<Revert> for PrecompileFailure {
+	fn from(revert: Revert) -> Self {
+		PrecompileFailure::Revert {
+			exit_status: ExitRevert::Reverted,
+			output: revert.to_encoded_bytes(),
+		}
+	}
+}
+
+/// Records the field in which a revert happened, e.g. `owner: value too large for type`.
+pub trait InjectBacktrace {
+	fn in_field(self, field: &str) -> Self;
+}
+
+impl InjectBacktrace for Revert {
+	fn in_field(mut self, field: &str) -> Self {
+		self.backtrace.push(field.into());
+		self
+	}
+}
+
+impl<T> InjectBacktrace for MayRevert<T> {
+	fn in_field(self, field: &str) -> Self {
+		self.map_err(|e| e.in_field(field))
+	}
+}
+
+/// Builds a revert carrying a Solidity `Error(string)` payload.
+pub fn revert(reason: impl Into<String>) -> PrecompileFailure {
+	Revert::new(reason).into()
+}
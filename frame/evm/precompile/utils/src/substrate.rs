@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Costs of Substrate storage accesses performed by precompiles.
+
+use core::marker::PhantomData;
+use frame_support::traits::Get;
+use pallet_evm::GasWeightMapping;
+
+pub struct RuntimeHelper<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime: pallet_evm::Config> RuntimeHelper<Runtime> {
+	/// Gas cost of a single database read.
+	pub fn db_read_gas_cost() -> u64 {
+		Runtime::GasWeightMapping::weight_to_gas(
+			<Runtime as frame_system::Config>::DbWeight::get().reads(1),
+		)
+	}
+
+	/// Gas cost of a single database write.
+	pub fn db_write_gas_cost() -> u64 {
+		Runtime::GasWeightMapping::weight_to_gas(
+			<Runtime as frame_system::Config>::DbWeight::get().writes(1),
+		)
+	}
+}
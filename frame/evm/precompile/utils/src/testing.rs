@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Harness running precompiles against a mock handle.
+//!
+//! ```ignore
+//! precompiles
+//!     .prepare_test(alice, precompile_address, input)
+//!     .expect_cost(1_000)
+//!     .expect_no_logs()
+//!     .execute_returns(true);
+//! ```
+
+use crate::{
+	data::{encode, EvmData, EvmDataReader},
+	revert::ERROR_SELECTOR,
+};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::marker::PhantomData;
+use fp_evm::{
+	Context, ExitError, ExitReason, ExitSucceed, IsPrecompileResult, Log, Precompile,
+	PrecompileFailure, PrecompileHandle, PrecompileResult, PrecompileSet, Transfer,
+};
+use sp_core::{H160, H256, U256};
+
+/// Subcall performed by a precompile through [`PrecompileHandle::call`].
+#[derive(Clone, Debug)]
+pub struct Subcall {
+	pub address: H160,
+	pub transfer: Option<Transfer>,
+	pub input: Vec<u8>,
+	pub target_gas: Option<u64>,
+	pub is_static: bool,
+	pub context: Context,
+}
+
+/// Result of a mocked subcall.
+#[derive(Clone, Debug)]
+pub struct SubcallOutput {
+	pub reason: ExitReason,
+	pub output: Vec<u8>,
+	pub cost: u64,
+	pub logs: Vec<Log>,
+}
+
+impl SubcallOutput {
+	pub fn succeed() -> Self {
+		Self {
+			reason: ExitReason::Succeed(ExitSucceed::Returned),
+			output: Vec::new(),
+			cost: 0,
+			logs: Vec::new(),
+		}
+	}
+
+	pub fn revert() -> Self {
+		Self {
+			reason: ExitReason::Revert(fp_evm::ExitRevert::Reverted),
+			..Self::succeed()
+		}
+	}
+}
+
+pub trait SubcallTrait: FnMut(Subcall) -> SubcallOutput + 'static {}
+
+impl<T: FnMut(Subcall) -> SubcallOutput + 'static> SubcallTrait for T {}
+
+pub type SubcallHandle = Box<dyn SubcallTrait>;
+
+/// Mock handle recording costs and logs, with subcalls answered by a closure.
+pub struct MockHandle {
+	pub gas_limit: u64,
+	pub gas_used: u64,
+	pub logs: Vec<Log>,
+	pub subcall_handle: Option<SubcallHandle>,
+	pub code_address: H160,
+	pub input: Vec<u8>,
+	pub context: Context,
+	pub is_static: bool,
+}
+
+impl MockHandle {
+	pub fn new(code_address: H160, context: Context) -> Self {
+		Self {
+			gas_limit: u64::MAX,
+			gas_used: 0,
+			logs: Vec::new(),
+			subcall_handle: None,
+			code_address,
+			input: Vec::new(),
+			context,
+			is_static: false,
+		}
+	}
+}
+
+impl PrecompileHandle for MockHandle {
+	fn call(
+		&mut self,
+		address: H160,
+		transfer: Option<Transfer>,
+		input: Vec<u8>,
+		target_gas: Option<u64>,
+		is_static: bool,
+		context: &Context,
+	) -> (ExitReason, Vec<u8>) {
+		let subcall_handle = self
+			.subcall_handle
+			.as_mut()
+			.expect("no subcall handle registered");
+		let SubcallOutput {
+			reason,
+			output,
+			cost,
+			logs,
+		} = subcall_handle(Subcall {
+			address,
+			transfer,
+			input,
+			target_gas,
+			is_static,
+			context: context.clone(),
+		});
+
+		if self.record_cost(cost).is_err() {
+			return (ExitReason::Error(ExitError::OutOfGas), Vec::new());
+		}
+		if matches!(reason, ExitReason::Succeed(_)) {
+			self.logs.extend(logs);
+		}
+		(reason, output)
+	}
+
+	fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+		self.gas_used = self.gas_used.saturating_add(cost);
+		if self.gas_used > self.gas_limit {
+			return Err(ExitError::OutOfGas);
+		}
+		Ok(())
+	}
+
+	fn record_external_cost(
+		&mut self,
+		_ref_time: Option<u64>,
+		_proof_size: Option<u64>,
+	) -> Result<(), ExitError> {
+		Ok(())
+	}
+
+	fn refund_external_cost(&mut self, _ref_time: Option<u64>, _proof_size: Option<u64>) {}
+
+	fn remaining_gas(&self) -> u64 {
+		self.gas_limit.saturating_sub(self.gas_used)
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+		self.logs.push(Log {
+			address,
+			topics,
+			data,
+		});
+		Ok(())
+	}
+
+	fn code_address(&self) -> H160 {
+		self.code_address
+	}
+
+	fn input(&self) -> &[u8] {
+		&self.input
+	}
+
+	fn context(&self) -> &Context {
+		&self.context
+	}
+
+	fn is_static(&self) -> bool {
+		self.is_static
+	}
+
+	fn gas_limit(&self) -> Option<u64> {
+		Some(self.gas_limit)
+	}
+}
+
+/// Precompile set running the precompile `P` at every address.
+pub struct SinglePrecompile<P>(PhantomData<P>);
+
+impl<P> Default for SinglePrecompile<P> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<P: Precompile> PrecompileSet for SinglePrecompile<P> {
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		Some(P::execute(handle))
+	}
+
+	fn is_precompile(&self, _address: H160, _gas: u64) -> IsPrecompileResult {
+		IsPrecompileResult::Answer {
+			is_precompile: true,
+			extra_cost: 0,
+		}
+	}
+}
+
+/// Returns the message of a Solidity `Error(string)` revert, or the raw output otherwise.
+pub fn decode_revert_message(output: &[u8]) -> Vec<u8> {
+	if EvmDataReader::read_selector(output) == Ok(ERROR_SELECTOR) {
+		if let Ok(message) = EvmDataReader::new(&output[4..]).read::<String>() {
+			return message.into_bytes();
+		}
+	}
+	output.to_vec()
+}
+
+/// Builder of a precompile call along with the expectations on its execution.
+#[must_use]
+pub struct PrecompileTester<'p, P> {
+	precompiles: &'p P,
+	handle: MockHandle,
+	expected_cost: Option<u64>,
+	expected_logs: Option<Vec<Log>>,
+}
+
+pub trait PrecompileTesterExt: PrecompileSet + Sized {
+	fn prepare_test(
+		&self,
+		from: impl Into<H160>,
+		to: impl Into<H160>,
+		data: impl Into<Vec<u8>>,
+	) -> PrecompileTester<'_, Self>;
+}
+
+impl<T: PrecompileSet> PrecompileTesterExt for T {
+	fn prepare_test(
+		&self,
+		from: impl Into<H160>,
+		to: impl Into<H160>,
+		data: impl Into<Vec<u8>>,
+	) -> PrecompileTester<'_, Self> {
+		PrecompileTester::new(self, from, to, data)
+	}
+}
+
+impl<'p, P: PrecompileSet> PrecompileTester<'p, P> {
+	pub fn new(
+		precompiles: &'p P,
+		from: impl Into<H160>,
+		to: impl Into<H160>,
+		data: impl Into<Vec<u8>>,
+	) -> Self {
+		let to = to.into();
+		let mut handle = MockHandle::new(
+			to,
+			Context {
+				address: to,
+				caller: from.into(),
+				apparent_value: U256::zero(),
+			},
+		);
+		handle.input = data.into();
+
+		Self {
+			precompiles,
+			handle,
+			expected_cost: None,
+			expected_logs: None,
+		}
+	}
+
+	pub fn with_value(mut self, value: impl Into<U256>) -> Self {
+		self.handle.context.apparent_value = value.into();
+		self
+	}
+
+	pub fn with_static_call(mut self, is_static: bool) -> Self {
+		self.handle.is_static = is_static;
+		self
+	}
+
+	pub fn with_target_gas(mut self, target_gas: Option<u64>) -> Self {
+		self.handle.gas_limit = target_gas.unwrap_or(u64::MAX);
+		self
+	}
+
+	pub fn with_subcall_handle(mut self, subcall_handle: impl SubcallTrait) -> Self {
+		self.handle.subcall_handle = Some(Box::new(subcall_handle));
+		self
+	}
+
+	pub fn expect_cost(mut self, cost: u64) -> Self {
+		self.expected_cost = Some(cost);
+		self
+	}
+
+	pub fn expect_no_logs(mut self) -> Self {
+		self.expected_logs = Some(Vec::new());
+		self
+	}
+
+	pub fn expect_log(mut self, log: Log) -> Self {
+		self.expected_logs.get_or_insert_with(Vec::new).push(log);
+		self
+	}
+
+	fn execute(&mut self) -> Option<PrecompileResult> {
+		let result = self.precompiles.execute(&mut self.handle);
+		if let Some(cost) = self.expected_cost {
+			assert_eq!(self.handle.gas_used, cost, "unexpected gas cost");
+		}
+		if let Some(logs) = &self.expected_logs {
+			assert_eq!(&self.handle.logs, logs, "unexpected logs");
+		}
+		result
+	}
+
+	/// Executes the call and checks that the address isn't a precompile.
+	pub fn execute_none(mut self) {
+		assert!(self.execute().is_none(), "address is a precompile");
+	}
+
+	/// Executes the call and checks that it succeeds, whatever its output.
+	pub fn execute_some(mut self) {
+		match self.execute() {
+			Some(Ok(_)) => {}
+			other => panic!("call didn't succeed: {:?}", other),
+		}
+	}
+
+	/// Executes the call and checks that it returns `output`.
+	pub fn execute_returns_raw(mut self, output: Vec<u8>) {
+		match self.execute() {
+			Some(Ok(result)) => assert_eq!(result.output, output, "unexpected output"),
+			other => panic!("call didn't succeed: {:?}", other),
+		}
+	}
+
+	/// Executes the call and checks that it returns the ABI encoding of `output`.
+	pub fn execute_returns(self, output: impl EvmData) {
+		self.execute_returns_raw(encode(output))
+	}
+
+	/// Executes the call and checks that it reverts with a message accepted by `check`.
+	pub fn execute_reverts(mut self, check: impl Fn(&[u8]) -> bool) {
+		match self.execute() {
+			Some(Err(PrecompileFailure::Revert { output, .. })) => {
+				let message = decode_revert_message(&output);
+				assert!(
+					check(&message),
+					"unexpected revert message: {}",
+					String::from_utf8_lossy(&message)
+				);
+			}
+			other => panic!("call didn't revert: {:?}", other),
+		}
+	}
+
+	/// Executes the call and checks that it fails with `error`.
+	pub fn execute_error(mut self, error: ExitError) {
+		match self.execute() {
+			Some(Err(PrecompileFailure::Error { exit_status })) => {
+				assert_eq!(exit_status, error, "unexpected error")
+			}
+			other => panic!("call didn't fail: {:?}", other),
+		}
+	}
+}
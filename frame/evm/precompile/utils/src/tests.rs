@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2021-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(test)]
+
+use crate::{
+	data::{decode, encode, encode_with_selector},
+	prelude::*,
+	testing::{PrecompileTesterExt, SinglePrecompile},
+};
+
+fn selector(signature: &str) -> u32 {
+	let hash = sp_core::hashing::keccak_256(signature.as_bytes());
+	u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, crate::EvmData)]
+struct Call {
+	target: H160,
+	value: U256,
+	data: Bytes,
+}
+
+#[test]
+fn static_values_roundtrip() {
+	let value = (H160::repeat_byte(0x11), U256::from(42), true, 7u32);
+	let encoded = encode(value);
+	assert_eq!(encoded.len(), 4 * 32);
+	assert_eq!(decode::<(H160, U256, bool, u32)>(&encoded), Ok(value));
+	assert_eq!(
+		<(H160, U256, bool, u32)>::signature(),
+		"(address,uint256,bool,uint32)"
+	);
+}
+
+#[test]
+fn dynamic_arguments_follow_the_abi() {
+	let input = encode_with_selector(
+		0x8be65246,
+		(
+			U256::from(0x123),
+			vec![0x456u32, 0x789],
+			Bytes(b"1234567890".to_vec()),
+		),
+	);
+	let expected = hex_literal::hex!(
+		"8be65246"
+		"0000000000000000000000000000000000000000000000000000000000000123"
+		"0000000000000000000000000000000000000000000000000000000000000060"
+		"00000000000000000000000000000000000000000000000000000000000000c0"
+		"0000000000000000000000000000000000000000000000000000000000000002"
+		"0000000000000000000000000000000000000000000000000000000000000456"
+		"0000000000000000000000000000000000000000000000000000000000000789"
+		"000000000000000000000000000000000000000000000000000000000000000a"
+		"3132333435363738393000000000000000000000000000000000000000000000"
+	);
+	assert_eq!(input, expected.to_vec());
+
+	let mut reader = EvmDataReader::new_skip_selector(&input).unwrap();
+	assert_eq!(reader.read::<U256>(), Ok(U256::from(0x123)));
+	assert_eq!(reader.read::<Vec<u32>>(), Ok(vec![0x456, 0x789]));
+	assert_eq!(reader.read::<Bytes>(), Ok(Bytes(b"1234567890".to_vec())));
+}
+
+#[test]
+fn structs_roundtrip() {
+	let calls = vec![
+		Call {
+			target: H160::repeat_byte(0x01),
+			value: U256::zero(),
+			data: Bytes(vec![0xaa; 40]),
+		},
+		Call {
+			target: H160::repeat_byte(0x02),
+			value: U256::from(10),
+			data: Bytes(Vec::new()),
+		},
+	];
+	assert_eq!(<Vec<Call>>::signature(), "(address,uint256,bytes)[]");
+	assert!(!Call::has_static_size());
+	assert_eq!(decode::<Vec<Call>>(&encode(calls.clone())), Ok(calls));
+}
+
+#[test]
+fn invalid_values_revert() {
+	assert_eq!(
+		decode::<bool>(&encode(U256::from(2))),
+		Err(Revert::new("value too large for type"))
+	);
+	assert_eq!(
+		decode::<u8>(&encode(U256::from(256))),
+		Err(Revert::new("value too large for type"))
+	);
+	assert_eq!(
+		decode::<H160>(&encode(U256::MAX)),
+		Err(Revert::new("value too large for type"))
+	);
+	assert_eq!(
+		decode::<U256>(&[0u8; 31]),
+		Err(Revert::new("tried to parse out of bounds"))
+	);
+
+	// An array claiming more elements than the input can hold.
+	let mut encoded = encode(vec![U256::one()]);
+	encoded[63] = 0xff;
+	assert_eq!(
+		decode::<Vec<U256>>(&encoded),
+		Err(Revert::new("array length exceeds input"))
+	);
+}
+
+pub struct Example;
+
+#[precompile]
+impl Example {
+	#[precompile::public("add(uint256,uint256)")]
+	#[precompile::view]
+	fn add(handle: &mut impl PrecompileHandle, a: U256, b: U256) -> EvmResult<U256> {
+		handle.record_cost(10)?;
+		Ok(a.saturating_add(b))
+	}
+
+	#[precompile::public("concat(string[])")]
+	#[precompile::view]
+	fn concat(_handle: &mut impl PrecompileHandle, parts: Vec<String>) -> EvmResult<String> {
+		Ok(parts.concat())
+	}
+
+	#[precompile::public("emit(bytes32)")]
+	fn emit(handle: &mut impl PrecompileHandle, topic: H256) -> EvmResult {
+		let log = log1(handle.code_address(), topic, Vec::new());
+		handle.record_log_costs(&[&log])?;
+		log.record(handle)
+	}
+
+	#[precompile::public("deposit()")]
+	#[precompile::public("fund()")]
+	#[precompile::payable]
+	fn deposit(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		Ok(handle.context().apparent_value)
+	}
+}
+
+fn precompiles() -> SinglePrecompile<Example> {
+	SinglePrecompile::default()
+}
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const PRECOMPILE: H160 = H160::repeat_byte(0x01);
+
+#[test]
+fn selectors_are_dispatched() {
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			encode_with_selector(
+				selector("add(uint256,uint256)"),
+				(U256::from(2), U256::from(3)),
+			),
+		)
+		.expect_cost(10)
+		.expect_no_logs()
+		.execute_returns(U256::from(5));
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			encode_with_selector(
+				selector("concat(string[])"),
+				(vec![String::from("ab"), String::from("c")],),
+			),
+		)
+		.execute_returns(String::from("abc"));
+
+	precompiles()
+		.prepare_test(ALICE, PRECOMPILE, vec![0x12, 0x34, 0x56, 0x78])
+		.execute_reverts(|message| message == b"unknown selector");
+}
+
+#[test]
+fn arguments_errors_name_the_argument() {
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			encode_with_selector(selector("add(uint256,uint256)"), (U256::from(2),)),
+		)
+		.execute_reverts(|message| message == b"b: tried to parse out of bounds");
+}
+
+#[test]
+fn modifiers_are_enforced() {
+	let topic = H256::repeat_byte(0x42);
+	let emit = encode_with_selector(selector("emit(bytes32)"), (topic,));
+
+	precompiles()
+		.prepare_test(ALICE, PRECOMPILE, emit.clone())
+		.expect_cost(750)
+		.expect_log(log1(PRECOMPILE, topic, Vec::new()))
+		.execute_returns(());
+
+	precompiles()
+		.prepare_test(ALICE, PRECOMPILE, emit)
+		.with_static_call(true)
+		.execute_reverts(|message| message == b"can't call non-static function in static context");
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			encode_with_selector(selector("add(uint256,uint256)"), (U256::one(), U256::one())),
+		)
+		.with_value(1)
+		.execute_reverts(|message| message == b"function is not payable");
+
+	precompiles()
+		.prepare_test(
+			ALICE,
+			PRECOMPILE,
+			selector("deposit()").to_be_bytes().to_vec(),
+		)
+		.with_value(100)
+		.execute_returns(U256::from(100));
+}
+
+pub struct Guarded;
+
+#[precompile]
+impl Guarded {
+	#[precompile::pre_check]
+	fn pre_check(handle: &mut impl PrecompileHandle) -> EvmResult {
+		if handle.context().caller == ALICE {
+			return Err(revert("caller is not allowed"));
+		}
+		Ok(())
+	}
+
+	#[precompile::public("ping()")]
+	#[precompile::view]
+	fn ping(_handle: &mut impl PrecompileHandle) -> EvmResult<bool> {
+		Ok(true)
+	}
+}
+
+#[test]
+fn pre_check_runs_before_dispatch() {
+	let ping = selector("ping()").to_be_bytes().to_vec();
+
+	SinglePrecompile::<Guarded>::default()
+		.prepare_test(H160::repeat_byte(0xbb), PRECOMPILE, ping.clone())
+		.execute_returns(true);
+
+	SinglePrecompile::<Guarded>::default()
+		.prepare_test(ALICE, PRECOMPILE, ping)
+		.execute_reverts(|message| message == b"caller is not allowed");
+}
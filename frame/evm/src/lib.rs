@@ -93,7 +93,10 @@ use sp_runtime::{
 use sp_std::{cmp::min, collections::btree_map::BTreeMap, vec::Vec};
 // Frontier
 use fp_account::AccountId20;
-use fp_evm::GenesisAccount;
+use fp_evm::{
+	tracing::{AccountState, PrestateTrace, StateAccessListener},
+	GenesisAccount,
+};
 pub use fp_evm::{
 	Account, CallInfo, CreateInfo, ExecutionInfoV2 as ExecutionInfo, FeeCalculator,
 	InvalidEvmTransactionError, IsPrecompileResult, LinearCostPrecompile, Log, Precompile,
@@ -173,9 +176,12 @@ pub mod pallet {
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 
-		/// EVM config used in the module.
+		/// Schedule of the EVM configs, by block number.
+		type ForkSchedule: ForkSchedule<Self::BlockNumber>;
+
+		/// EVM config used in the module, which is the one scheduled for the current block.
 		fn config() -> &'static EvmConfig {
-			&SHANGHAI_CONFIG
+			Self::ForkSchedule::config_at(frame_system::Pallet::<Self>::block_number())
 		}
 	}
 
@@ -769,6 +775,61 @@ impl<T: Config> GasWeightMapping for FixedGasWeightMapping<T> {
 	}
 }
 
+/// A trait for selecting the EVM config in force at a block, so that the EVM rules can change
+/// at a given block number while past blocks keep being executed with their own rules.
+///
+/// Schedules are provided up to Shanghai, the last hard fork supported by the `evm` version in
+/// use. Cancun needs its opcodes (transient storage, `MCOPY`, blob hashes) in the EVM
+/// interpreter first, so it is left out until `evm` is upgraded.
+pub trait ForkSchedule<BlockNumber> {
+	fn config_at(number: BlockNumber) -> &'static EvmConfig;
+}
+
+/// Applies the Shanghai rules at every block.
+impl<BlockNumber> ForkSchedule<BlockNumber> for () {
+	fn config_at(_number: BlockNumber) -> &'static EvmConfig {
+		&SHANGHAI_CONFIG
+	}
+}
+
+/// Applies the London rules at every block.
+pub struct London;
+impl<BlockNumber> ForkSchedule<BlockNumber> for London {
+	fn config_at(_number: BlockNumber) -> &'static EvmConfig {
+		&LONDON_CONFIG
+	}
+}
+
+/// Applies the Shanghai rules at every block.
+pub struct Shanghai;
+impl<BlockNumber> ForkSchedule<BlockNumber> for Shanghai {
+	fn config_at(_number: BlockNumber) -> &'static EvmConfig {
+		&SHANGHAI_CONFIG
+	}
+}
+
+/// Follows the `Before` schedule until the block `Activation`, and the `After` one from it.
+pub struct ForkAt<Activation, Before, After>(
+	sp_std::marker::PhantomData<(Activation, Before, After)>,
+);
+impl<BlockNumber, Activation, Before, After> ForkSchedule<BlockNumber>
+	for ForkAt<Activation, Before, After>
+where
+	BlockNumber: PartialOrd,
+	Activation: Get<BlockNumber>,
+	Before: ForkSchedule<BlockNumber>,
+	After: ForkSchedule<BlockNumber>,
+{
+	fn config_at(number: BlockNumber) -> &'static EvmConfig {
+		if number >= Activation::get() {
+			After::config_at(number)
+		} else {
+			Before::config_at(number)
+		}
+	}
+}
+
+static LONDON_CONFIG: EvmConfig = EvmConfig::london();
 static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
 
 impl<T: Config> Pallet<T> {
@@ -863,6 +924,75 @@ impl<T: Config> Pallet<T> {
 		)
 	}
 
+	/// Basic state and code of `address` as seen by the EVM, for the prestate tracer.
+	fn account_state(address: &H160) -> AccountState {
+		let (account, _) = Self::account_basic(address);
+		let code = <AccountCodes<T>>::get(address);
+
+		AccountState {
+			balance: Some(account.balance),
+			nonce: Some(account.nonce),
+			code: (!code.is_empty()).then_some(code),
+			storage: BTreeMap::new(),
+		}
+	}
+
+	/// Run `f` and return the state it touches, as reported by the prestate tracer.
+	///
+	/// The state of an account or storage slot is read the first time `f` touches it, so `f` only
+	/// runs once.
+	pub fn trace_prestate<R>(diff_mode: bool, f: impl FnOnce() -> R) -> (R, PrestateTrace) {
+		let mut recorder = PrestateRecorder::<T> {
+			accounts: BTreeMap::new(),
+			_marker: sp_std::marker::PhantomData,
+		};
+		let result = fp_evm::tracing::listen_state_accesses(&mut recorder, f);
+
+		let pre = recorder.accounts;
+		let trace = if diff_mode {
+			let post = pre
+				.iter()
+				.map(|(address, before)| {
+					let mut after = Self::account_state(address);
+					after.storage = before
+						.storage
+						.keys()
+						.map(|index| (*index, <AccountStorages<T>>::get(address, index)))
+						.collect();
+					(*address, after)
+				})
+				.collect();
+			PrestateTrace::diff(pre, post)
+		} else {
+			PrestateTrace::Prestate(pre)
+		};
+
+		(result, trace)
+	}
+
+	/// Run `f` and return the accounts and storage slots it accesses, as an EIP-2930 access list.
+	///
+	/// Precompiles are left out, as they are always warm.
+	pub fn access_list<R>(f: impl FnOnce() -> R) -> (R, Vec<(H160, Vec<H256>)>) {
+		let (result, accessed) = runner::stack::record_accessed_storage(f);
+		let precompiles = T::PrecompilesValue::get();
+		let access_list = accessed
+			.into_iter()
+			.filter(|(address, _)| {
+				!matches!(
+					precompiles.is_precompile(*address, u64::MAX),
+					IsPrecompileResult::Answer {
+						is_precompile: true,
+						..
+					}
+				)
+			})
+			.map(|(address, indexes)| (address, indexes.into_iter().collect()))
+			.collect();
+
+		(result, access_list)
+	}
+
 	/// Get the author using the FindAuthor trait.
 	pub fn find_author() -> H160 {
 		let digest = <frame_system::Pallet<T>>::digest();
@@ -872,6 +1002,30 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+/// Records the state of the accounts and storage slots the first time the EVM touches them.
+struct PrestateRecorder<T> {
+	accounts: BTreeMap<H160, AccountState>,
+	_marker: sp_std::marker::PhantomData<T>,
+}
+
+impl<T: Config> StateAccessListener for PrestateRecorder<T> {
+	fn on_account_access(&mut self, address: H160) {
+		self.accounts
+			.entry(address)
+			.or_insert_with(|| Pallet::<T>::account_state(&address));
+	}
+
+	fn on_storage_access(&mut self, address: H160, index: H256) {
+		self.on_account_access(address);
+		if let Some(account) = self.accounts.get_mut(&address) {
+			account
+				.storage
+				.entry(index)
+				.or_insert_with(|| <AccountStorages<T>>::get(address, index));
+		}
+	}
+}
+
 /// Handle withdrawing, refunding and depositing of transaction fees.
 /// Similar to `OnChargeTransaction` of `pallet_transaction_payment`
 pub trait OnChargeEVMTransaction<T: Config> {
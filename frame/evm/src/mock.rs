@@ -31,8 +31,9 @@ use sp_runtime::{
 use sp_std::{boxed::Box, prelude::*, str::FromStr};
 
 use crate::{
-	EnsureAddressNever, EnsureAddressRoot, FeeCalculator, IdentityAddressMapping,
-	IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+	EnsureAddressNever, EnsureAddressRoot, FeeCalculator, ForkAt, IdentityAddressMapping,
+	IsPrecompileResult, London, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+	Shanghai,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -137,6 +138,8 @@ parameter_types! {
 	pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
 	pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
 	pub MockPrecompiles: MockPrecompileSet = MockPrecompileSet;
+	/// Block from which the Shanghai rules apply, the London rules applying before it.
+	pub storage ShanghaiActivation: u64 = 0;
 }
 impl crate::Config for Test {
 	type FeeCalculator = FixedGasPrice;
@@ -162,6 +165,7 @@ impl crate::Config for Test {
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
+	type ForkSchedule = ForkAt<ShanghaiActivation, London, Shanghai>;
 }
 
 /// Example PrecompileSet with only Identity precompile.
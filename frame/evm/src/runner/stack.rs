@@ -42,9 +42,10 @@ use sp_std::{
 };
 // Frontier
 use fp_evm::{
-	AccessedStorage, CallInfo, CreateInfo, ExecutionInfoV2, IsPrecompileResult, Log, PrecompileSet,
-	Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_CODES_METADATA_PROOF_SIZE,
-	ACCOUNT_STORAGE_PROOF_SIZE, IS_EMPTY_CHECK_PROOF_SIZE, WRITE_PROOF_SIZE,
+	AccessedStorage, BlockOverrides, CallInfo, CreateInfo, ExecutionInfoV2, IsPrecompileResult,
+	Log, PrecompileSet, Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE,
+	ACCOUNT_CODES_METADATA_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE, IS_EMPTY_CHECK_PROOF_SIZE,
+	WRITE_PROOF_SIZE,
 };
 
 use crate::{
@@ -56,6 +57,28 @@ use crate::{
 #[cfg(feature = "forbid-evm-reentrancy")]
 environmental::thread_local_impl!(static IN_EVM: environmental::RefCell<bool> = environmental::RefCell::new(false));
 
+environmental::environmental!(block_environment: BlockOverrides);
+
+/// Run `f` with the block environment seen by the EVM overridden by `overrides`.
+pub fn with_block_overrides<R>(mut overrides: BlockOverrides, f: impl FnOnce() -> R) -> R {
+	block_environment::using(&mut overrides, f)
+}
+
+/// Block environment overrides set by [`with_block_overrides`], if any.
+fn block_overrides() -> BlockOverrides {
+	block_environment::with(|overrides| overrides.clone()).unwrap_or_default()
+}
+
+environmental::environmental!(accessed_storage: BTreeMap<H160, BTreeSet<H256>>);
+
+/// Run `f`, collecting the accounts and storage slots accessed by the EVM executions it runs, as
+/// tracked by the [`Recorded`] storage of their [`SubstrateStackState`].
+pub fn record_accessed_storage<R>(f: impl FnOnce() -> R) -> (R, BTreeMap<H160, BTreeSet<H256>>) {
+	let mut accessed = BTreeMap::new();
+	let result = accessed_storage::using(&mut accessed, f);
+	(result, accessed)
+}
+
 #[derive(Default)]
 pub struct Runner<T: Config> {
 	_marker: PhantomData<T>,
@@ -92,6 +115,7 @@ where
 		R: Default,
 	{
 		let (base_fee, weight) = T::FeeCalculator::min_gas_price();
+		let base_fee = block_overrides().base_fee_per_gas.unwrap_or(base_fee);
 
 		#[cfg(feature = "forbid-evm-reentrancy")]
 		if IN_EVM.with(|in_evm| in_evm.replace(true)) {
@@ -232,6 +256,8 @@ where
 					weight,
 				})?;
 
+		// Report the `source` account before its fee is withdrawn, for the prestate tracer.
+		fp_evm::tracing::record_account_access(source);
 		// Deduct fee from the `source` account. Returns `None` if `total_fee` is Zero.
 		let fee = T::OnChargeTransaction::withdraw_fee(&source, total_fee)
 			.map_err(|e| RunnerError { error: e, weight })?;
@@ -240,6 +266,7 @@ where
 		let vicinity = Vicinity {
 			gas_price: base_fee,
 			origin: source,
+			block_overrides: block_overrides(),
 		};
 
 		let metadata = StackSubstateMetadata::new(gas_limit, config);
@@ -311,6 +338,14 @@ where
 
 		let state = executor.into_state();
 
+		if let Some(accessed) = &state.recorded().accessed {
+			accessed_storage::with(|recorded| {
+				for (address, indexes) in accessed {
+					recorded.entry(*address).or_default().extend(indexes);
+				}
+			});
+		}
+
 		for address in &state.substate.deletes {
 			log::debug!(
 				target: "evm",
@@ -376,12 +411,15 @@ where
 		let (base_fee, mut weight) = T::FeeCalculator::min_gas_price();
 		let (source_account, inner_weight) = Pallet::<T>::account_basic(&source);
 		weight = weight.saturating_add(inner_weight);
+		let block_overrides = block_overrides();
 
 		let _ = fp_evm::CheckEvmTransaction::<Self::Error>::new(
 			fp_evm::CheckEvmTransactionConfig {
 				evm_config,
-				block_gas_limit: T::BlockGasLimit::get(),
-				base_fee,
+				block_gas_limit: block_overrides
+					.gas_limit
+					.unwrap_or_else(T::BlockGasLimit::get),
+				base_fee: block_overrides.base_fee_per_gas.unwrap_or(base_fee),
 				chain_id: T::ChainId::get(),
 				is_transactional,
 			},
@@ -672,6 +710,9 @@ impl<'config> SubstrateStackSubstate<'config> {
 pub struct Recorded {
 	account_codes: Vec<H160>,
 	account_storages: BTreeMap<(H160, H256), bool>,
+	/// All the accounts and storage slots accessed, only tracked within
+	/// [`record_accessed_storage`].
+	accessed: Option<BTreeMap<H160, BTreeSet<H256>>>,
 }
 
 /// Substrate backend for EVM.
@@ -701,7 +742,10 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 			},
 			_marker: PhantomData,
 			original_storage: BTreeMap::new(),
-			recorded: Default::default(),
+			recorded: Recorded {
+				accessed: accessed_storage::with(|_| BTreeMap::new()),
+				..Default::default()
+			},
 			weight_info,
 		}
 	}
@@ -739,17 +783,24 @@ where
 	}
 
 	fn block_number(&self) -> U256 {
-		let number: u128 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
-		U256::from(number)
+		self.vicinity.block_overrides.number.unwrap_or_else(|| {
+			let number: u128 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
+			U256::from(number)
+		})
 	}
 
 	fn block_coinbase(&self) -> H160 {
-		Pallet::<T>::find_author()
+		self.vicinity
+			.block_overrides
+			.coinbase
+			.unwrap_or_else(Pallet::<T>::find_author)
 	}
 
 	fn block_timestamp(&self) -> U256 {
-		let now: u128 = T::Timestamp::now().unique_saturated_into();
-		U256::from(now / 1000)
+		self.vicinity.block_overrides.timestamp.unwrap_or_else(|| {
+			let now: u128 = T::Timestamp::now().unique_saturated_into();
+			U256::from(now / 1000)
+		})
 	}
 
 	fn block_difficulty(&self) -> U256 {
@@ -761,12 +812,20 @@ where
 	}
 
 	fn block_gas_limit(&self) -> U256 {
-		T::BlockGasLimit::get()
+		self.vicinity
+			.block_overrides
+			.gas_limit
+			.unwrap_or_else(T::BlockGasLimit::get)
 	}
 
 	fn block_base_fee_per_gas(&self) -> U256 {
-		let (base_fee, _) = T::FeeCalculator::min_gas_price();
-		base_fee
+		self.vicinity
+			.block_overrides
+			.base_fee_per_gas
+			.unwrap_or_else(|| {
+				let (base_fee, _) = T::FeeCalculator::min_gas_price();
+				base_fee
+			})
 	}
 
 	fn chain_id(&self) -> U256 {
@@ -778,6 +837,7 @@ where
 	}
 
 	fn basic(&self, address: H160) -> evm::backend::Basic {
+		fp_evm::tracing::record_account_access(address);
 		let (account, _) = Pallet::<T>::account_basic(&address);
 
 		evm::backend::Basic {
@@ -787,10 +847,12 @@ where
 	}
 
 	fn code(&self, address: H160) -> Vec<u8> {
+		fp_evm::tracing::record_account_access(address);
 		<AccountCodes<T>>::get(address)
 	}
 
 	fn storage(&self, address: H160, index: H256) -> H256 {
+		fp_evm::tracing::record_storage_access(address, index);
 		<AccountStorages<T>>::get(address, index)
 	}
 
@@ -834,6 +896,7 @@ where
 	}
 
 	fn is_empty(&self, address: H160) -> bool {
+		fp_evm::tracing::record_account_access(address);
 		Pallet::<T>::is_account_empty(&address)
 	}
 
@@ -842,12 +905,15 @@ where
 	}
 
 	fn inc_nonce(&mut self, address: H160) -> Result<(), ExitError> {
+		fp_evm::tracing::record_account_access(address);
 		let account_id = T::AddressMapping::into_account_id(address);
 		frame_system::Pallet::<T>::inc_account_nonce(&account_id);
 		Ok(())
 	}
 
 	fn set_storage(&mut self, address: H160, index: H256, value: H256) {
+		fp_evm::tracing::record_storage_access(address, index);
+
 		// We cache the current value if this is the first time we modify it
 		// in the transaction.
 		use sp_std::collections::btree_map::Entry::Vacant;
@@ -894,6 +960,7 @@ where
 	}
 
 	fn set_code(&mut self, address: H160, code: Vec<u8>) {
+		fp_evm::tracing::record_account_access(address);
 		log::debug!(
 			target: "evm",
 			"Inserting code ({} bytes) at {:?}",
@@ -904,6 +971,8 @@ where
 	}
 
 	fn transfer(&mut self, transfer: Transfer) -> Result<(), ExitError> {
+		fp_evm::tracing::record_account_access(transfer.source);
+		fp_evm::tracing::record_account_access(transfer.target);
 		let source = T::AddressMapping::into_account_id(transfer.source);
 		let target = T::AddressMapping::into_account_id(transfer.target);
 		T::Currency::transfer(
@@ -945,10 +1014,12 @@ where
 	}
 
 	fn code_size(&self, address: H160) -> U256 {
+		fp_evm::tracing::record_account_access(address);
 		U256::from(<Pallet<T>>::account_code_metadata(address).size)
 	}
 
 	fn code_hash(&self, address: H160) -> H256 {
+		fp_evm::tracing::record_account_access(address);
 		<Pallet<T>>::account_code_metadata(address).hash
 	}
 
@@ -1013,6 +1084,18 @@ where
 		_gas_cost: GasCost,
 		target: evm::gasometer::StorageTarget,
 	) -> Result<(), ExitError> {
+		if let Some(accessed) = &mut self.recorded.accessed {
+			match target {
+				StorageTarget::Address(address) => {
+					accessed.entry(address).or_default();
+				}
+				StorageTarget::Slot(address, index) => {
+					accessed.entry(address).or_default().insert(index);
+				}
+				_ => {}
+			}
+		}
+
 		// If account code or storage slot is in the overlay it is already accounted for and early exit
 		let mut accessed_storage: Option<AccessedStorage> = match target {
 			StorageTarget::Address(address) => {
@@ -1254,3 +1254,134 @@ fn metadata_empty_dont_code_gets_cached() {
 		assert!(<AccountCodesMetadata<Test>>::get(&address).is_none());
 	});
 }
+
+#[test]
+fn block_overrides_are_seen_by_the_evm() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::repeat_byte(0xaa);
+
+		// Returns NUMBER.
+		crate::Pallet::<Test>::create_account(contract, hex::decode("4360005260206000f3").unwrap());
+
+		let overrides = fp_evm::BlockOverrides {
+			number: Some(U256::from(1000)),
+			..Default::default()
+		};
+		let result = runner::stack::with_block_overrides(overrides, || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+		})
+		.expect("call succeeds");
+
+		assert_eq!(U256::from_big_endian(&result.value), U256::from(1000));
+	});
+}
+
+#[test]
+fn access_list_records_accessed_storage() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::repeat_byte(0xaa);
+		let balance_of = H160::repeat_byte(0x22);
+
+		// SLOAD(1), BALANCE(0x2222..22), BALANCE(precompile 0x01).
+		let code = hex::decode(format!("6001545073{}315060013150", "22".repeat(20))).unwrap();
+		crate::Pallet::<Test>::create_account(contract, code);
+
+		let (result, access_list) = crate::Pallet::<Test>::access_list(|| {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+		});
+
+		assert!(result.expect("call succeeds").exit_reason.is_succeed());
+		assert_eq!(
+			access_list,
+			vec![
+				(balance_of, vec![]),
+				(contract, vec![H256::from_low_u64_be(1)]),
+			]
+		);
+	});
+}
+
+#[test]
+fn fork_schedule_switches_config_at_activation_block() {
+	frame_support::parameter_types! {
+		pub const ShanghaiBlock: u64 = 10;
+	}
+	type Schedule = ForkAt<ShanghaiBlock, London, Shanghai>;
+
+	assert!(!<Schedule as ForkSchedule<u64>>::config_at(9).has_push0);
+	assert!(<Schedule as ForkSchedule<u64>>::config_at(10).has_push0);
+
+	new_test_ext().execute_with(|| {
+		// The mock schedule applies the Shanghai rules from genesis by default.
+		assert!(<Test as Config>::config().has_push0);
+	});
+}
+
+#[test]
+fn blocks_execute_with_the_config_scheduled_at_their_number() {
+	new_test_ext().execute_with(|| {
+		ShanghaiActivation::set(&10);
+		let contract = H160::repeat_byte(0xaa);
+		crate::Pallet::<Test>::create_account(
+			contract,
+			vec![
+				0x5f, // PUSH0
+				0x00, // STOP
+			],
+		);
+		let call = || {
+			EVM::call(
+				RuntimeOrigin::root(),
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::default(),
+				1000000,
+				U256::from(1_000_000_000),
+				None,
+				None,
+				Vec::new(),
+			)
+		};
+
+		// PUSH0 is rejected under the London rules, before the activation block.
+		System::set_block_number(9);
+		assert_ok!(call());
+		System::assert_last_event(crate::Event::ExecutedFailed { address: contract }.into());
+
+		// And accepted under the Shanghai rules from it.
+		System::set_block_number(10);
+		assert_ok!(call());
+		System::assert_last_event(crate::Event::Executed { address: contract }.into());
+	});
+}
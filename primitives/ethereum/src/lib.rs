@@ -132,3 +132,27 @@ impl From<&Transaction> for TransactionData {
 		}
 	}
 }
+
+/// The `s` value of the placeholder signature of impersonated transactions.
+pub const IMPERSONATION_SIGNATURE_S: H256 = H256(*b"frontier:impersonated-signature!");
+
+/// The placeholder `(r, s)` signature of a transaction sent on behalf of `source`.
+///
+/// It is not a valid ECDSA signature: runtimes that allow impersonation take the sender from
+/// `r` instead of recovering it.
+pub fn impersonation_signature(source: H160) -> (H256, H256) {
+	(H256::from(source), IMPERSONATION_SIGNATURE_S)
+}
+
+/// The sender of a transaction carrying a placeholder signature, if any.
+pub fn impersonated_source(transaction: &Transaction) -> Option<H160> {
+	let (r, s) = match transaction {
+		Transaction::Legacy(t) => (*t.signature.r(), *t.signature.s()),
+		Transaction::EIP2930(t) => (t.r, t.s),
+		Transaction::EIP1559(t) => (t.r, t.s),
+	};
+	if s != IMPERSONATION_SIGNATURE_S || r[..12] != [0u8; 12] {
+		return None;
+	}
+	Some(H160::from(r))
+}
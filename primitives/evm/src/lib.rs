@@ -19,6 +19,7 @@
 #![deny(unused_crate_dependencies)]
 
 mod precompile;
+pub mod tracing;
 mod validation;
 
 use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, Weight};
@@ -55,6 +56,24 @@ pub struct Vicinity {
 	pub gas_price: U256,
 	/// Origin of the transaction.
 	pub origin: H160,
+	/// Overrides of the block environment the transaction is executed in.
+	pub block_overrides: BlockOverrides,
+}
+
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Overrides of the block environment seen by the EVM, used to simulate calls in another block.
+pub struct BlockOverrides {
+	/// Block number.
+	pub number: Option<U256>,
+	/// Block timestamp, in seconds.
+	pub timestamp: Option<U256>,
+	/// Block author.
+	pub coinbase: Option<H160>,
+	/// Block base fee per gas.
+	pub base_fee_per_gas: Option<U256>,
+	/// Block gas limit.
+	pub gas_limit: Option<U256>,
 }
 
 /// `System::Account` 16(hash) + 20 (key) + 60 (AccountInfo::max_encoded_len)
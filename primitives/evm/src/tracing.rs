@@ -0,0 +1,745 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! EVM execution tracing.
+//!
+//! The runtime replays a transaction with a [`Tracer`] attached to the EVM event listeners and
+//! returns the collected [`TraceResult`] to the client, which renders it over RPC. Events are only
+//! emitted when the `tracing` feature is enabled; without it the tracer collects nothing.
+//!
+//! The prestate tracer does not listen to EVM events: the runner's backend reports the accounts
+//! and storage slots it touches with [`record_account_access`] and [`record_storage_access`], and
+//! the runtime reads their state the first time they are touched and after the execution.
+
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+use crate::{ExitReason, ExitRevert, ExitSucceed};
+
+/// Tracer to attach to the EVM, together with its options.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum TracerConfig {
+	/// Opcode level logger, equivalent to geth's default struct logger.
+	StructLogger(StructLoggerConfig),
+	/// Nested call frames, equivalent to geth's `callTracer`.
+	CallTracer {
+		/// Only keep the top-level call frame.
+		only_top_call: bool,
+	},
+	/// State touched by the execution, equivalent to geth's `prestateTracer`.
+	PrestateTracer {
+		/// Return the state before and after the execution, restricted to what changed.
+		diff_mode: bool,
+	},
+}
+
+/// Options of the struct logger.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Encode, Decode, TypeInfo)]
+pub struct StructLoggerConfig {
+	pub disable_stack: bool,
+	pub disable_memory: bool,
+	pub disable_storage: bool,
+	pub enable_return_data: bool,
+}
+
+/// A single executed opcode.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: u64,
+	/// Raw opcode.
+	pub op: u8,
+	/// Gas left before executing the opcode.
+	pub gas: u64,
+	/// Gas charged for the opcode, including memory expansion.
+	pub gas_cost: u64,
+	/// Call depth, starting at 1.
+	pub depth: u32,
+	/// Error the opcode failed with, if any.
+	pub error: Option<ExitReason>,
+	pub stack: Option<Vec<H256>>,
+	pub memory: Option<Vec<u8>>,
+	/// Storage slots of the current contract accessed so far in the transaction.
+	pub storage: Option<BTreeMap<H256, H256>>,
+	/// Return data of the last call made by the current frame.
+	pub return_data: Option<Vec<u8>>,
+}
+
+/// Output of the struct logger.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct StructLoggerResult {
+	pub gas: U256,
+	pub failed: bool,
+	pub return_value: Vec<u8>,
+	pub struct_logs: Vec<StructLog>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum CallType {
+	Call,
+	CallCode,
+	DelegateCall,
+	StaticCall,
+	Create,
+	Create2,
+	SelfDestruct,
+}
+
+/// A call frame, with its nested calls.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct CallTrace {
+	pub call_type: CallType,
+	pub from: H160,
+	pub to: H160,
+	pub value: U256,
+	pub gas: u64,
+	pub gas_used: u64,
+	pub input: Vec<u8>,
+	pub output: Vec<u8>,
+	/// How the frame exited, `None` if it never did.
+	pub exit_reason: Option<ExitReason>,
+	pub calls: Vec<CallTrace>,
+}
+
+/// State of an account, as reported by the prestate tracer.
+///
+/// Fields are `None` when omitted from a diff because they did not change.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Encode, Decode, TypeInfo)]
+pub struct AccountState {
+	pub balance: Option<U256>,
+	pub nonce: Option<U256>,
+	pub code: Option<Vec<u8>>,
+	pub storage: BTreeMap<H256, H256>,
+}
+
+impl AccountState {
+	fn is_empty(&self) -> bool {
+		self.balance.unwrap_or_default().is_zero()
+			&& self.nonce.unwrap_or_default().is_zero()
+			&& self.code.as_ref().map_or(true, |code| code.is_empty())
+			&& self.storage.values().all(|value| value.is_zero())
+	}
+}
+
+/// Output of the prestate tracer.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum PrestateTrace {
+	/// State of the touched accounts before the execution.
+	Prestate(BTreeMap<H160, AccountState>),
+	/// Changed accounts, before and after the execution.
+	Diff {
+		pre: BTreeMap<H160, AccountState>,
+		post: BTreeMap<H160, AccountState>,
+	},
+}
+
+impl PrestateTrace {
+	/// Diff of the touched accounts, `pre` and `post` being complete states.
+	///
+	/// Unchanged accounts are dropped, `pre` keeps the changed accounts with their changed storage
+	/// slots and `post` only the changed fields. Accounts missing from `post` were removed.
+	pub fn diff(pre: BTreeMap<H160, AccountState>, mut post: BTreeMap<H160, AccountState>) -> Self {
+		let mut changed_pre = BTreeMap::new();
+		let mut changed_post = BTreeMap::new();
+
+		for (address, mut before) in pre {
+			let after = post.remove(&address).unwrap_or_default();
+			if before == after {
+				continue;
+			}
+			let removed = after.is_empty();
+			let mut changed = AccountState {
+				balance: after
+					.balance
+					.filter(|balance| before.balance != Some(*balance)),
+				nonce: after.nonce.filter(|nonce| before.nonce != Some(*nonce)),
+				code: after.code.filter(|code| before.code.as_ref() != Some(code)),
+				storage: BTreeMap::new(),
+			};
+			before.storage.retain(|index, value| {
+				let value_after = after.storage.get(index).copied().unwrap_or_default();
+				if value_after == *value {
+					return false;
+				}
+				if !value_after.is_zero() {
+					changed.storage.insert(*index, value_after);
+				}
+				true
+			});
+			if !removed && changed != AccountState::default() {
+				changed_post.insert(address, changed);
+			}
+			changed_pre.insert(address, before);
+		}
+
+		PrestateTrace::Diff {
+			pre: changed_pre,
+			post: changed_post,
+		}
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum TraceResult {
+	StructLogger(StructLoggerResult),
+	CallTracer(CallTrace),
+	Prestate(PrestateTrace),
+}
+
+/// Listener of the accounts and storage slots touched by an EVM execution, notified before the
+/// state is read or written.
+pub trait StateAccessListener {
+	fn on_account_access(&mut self, address: H160);
+	fn on_storage_access(&mut self, address: H160, index: H256);
+}
+
+#[cfg(feature = "tracing")]
+environmental::environmental!(state_access_listener: dyn StateAccessListener + 'static);
+
+/// Report an access to the basic state or code of `address`.
+pub fn record_account_access(_address: H160) {
+	#[cfg(feature = "tracing")]
+	state_access_listener::with(|listener| listener.on_account_access(_address));
+}
+
+/// Report an access to the storage slot `index` of `address`.
+pub fn record_storage_access(_address: H160, _index: H256) {
+	#[cfg(feature = "tracing")]
+	state_access_listener::with(|listener| listener.on_storage_access(_address, _index));
+}
+
+/// Run `f`, notifying `listener` of the state the EVM backend touches.
+#[cfg(feature = "tracing")]
+pub fn listen_state_accesses<R, F: FnOnce() -> R>(
+	listener: &mut (dyn StateAccessListener + 'static),
+	f: F,
+) -> R {
+	state_access_listener::using(listener, f)
+}
+
+/// Run `f`, notifying `listener` of the state the EVM backend touches.
+#[cfg(not(feature = "tracing"))]
+pub fn listen_state_accesses<R, F: FnOnce() -> R>(
+	_listener: &mut (dyn StateAccessListener + 'static),
+	f: F,
+) -> R {
+	f()
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+struct Frame {
+	trace: CallTrace,
+	/// Whether the frame executed its first opcode. Frames without bytecode, such as precompiles
+	/// or calls failing before execution, never start.
+	started: bool,
+	gas_limit: u64,
+	used_gas: u64,
+	memory_gas: u64,
+	return_data: Vec<u8>,
+}
+
+/// Collects EVM events into a [`TraceResult`].
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub struct Tracer {
+	config: TracerConfig,
+	/// Top-level call, built from the transact event.
+	transact: Option<CallTrace>,
+	/// Call type of the next frame, taken from the transact event or the last call opcode.
+	next_call_type: Option<CallType>,
+	frames: Vec<Frame>,
+	root: Option<CallTrace>,
+	exit: Option<(ExitReason, Vec<u8>)>,
+	struct_logs: Vec<StructLog>,
+	storage: BTreeMap<H160, BTreeMap<H256, H256>>,
+	/// The last struct log still waits for its gas cost.
+	pending_cost: bool,
+}
+
+impl Tracer {
+	pub fn new(config: TracerConfig) -> Self {
+		Self {
+			config,
+			transact: None,
+			next_call_type: None,
+			frames: Vec::new(),
+			root: None,
+			exit: None,
+			struct_logs: Vec::new(),
+			storage: BTreeMap::new(),
+			pending_cost: false,
+		}
+	}
+
+	/// Run `f` with this tracer listening to the EVM events.
+	#[cfg(feature = "tracing")]
+	pub fn trace<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		use sp_std::{cell::RefCell, rc::Rc};
+
+		let tracer = Rc::new(RefCell::new(sp_std::mem::replace(
+			self,
+			Self::new(self.config.clone()),
+		)));
+		let mut gasometer = listener::Proxy(Rc::clone(&tracer));
+		let mut runtime = listener::Proxy(Rc::clone(&tracer));
+		let mut evm = listener::Proxy(Rc::clone(&tracer));
+
+		let result = evm_gasometer::tracing::using(&mut gasometer, || {
+			evm_runtime::tracing::using(&mut runtime, || evm::tracing::using(&mut evm, f))
+		});
+
+		drop((gasometer, runtime, evm));
+		*self = Rc::try_unwrap(tracer)
+			.ok()
+			.expect("listeners are dropped; qed")
+			.into_inner();
+		result
+	}
+
+	/// Run `f` with this tracer listening to the EVM events.
+	#[cfg(not(feature = "tracing"))]
+	pub fn trace<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		f()
+	}
+
+	/// Consume the tracer, `used_gas` being the gas used by the whole transaction.
+	pub fn into_result(mut self, used_gas: U256) -> TraceResult {
+		self.close_frames();
+		let (exit_reason, return_value) = self.exit.take().unzip();
+
+		match self.config {
+			TracerConfig::StructLogger(_) => TraceResult::StructLogger(StructLoggerResult {
+				gas: used_gas,
+				failed: !matches!(exit_reason, Some(ExitReason::Succeed(_))),
+				return_value: return_value.unwrap_or_default(),
+				struct_logs: self.struct_logs,
+			}),
+			TracerConfig::CallTracer { only_top_call } => {
+				let mut root = self
+					.root
+					.or(self.transact)
+					.unwrap_or_else(|| new_call_trace(CallType::Call));
+				root.gas_used = used_gas.low_u64();
+				if root.exit_reason.is_none() {
+					root.exit_reason = exit_reason;
+					root.output = return_value.unwrap_or_default();
+				}
+				if only_top_call {
+					root.calls.clear();
+				}
+				TraceResult::CallTracer(root)
+			}
+			// The prestate is read by the runtime, see `listen_state_accesses`.
+			TracerConfig::PrestateTracer { .. } => {
+				TraceResult::Prestate(PrestateTrace::Prestate(BTreeMap::new()))
+			}
+		}
+	}
+
+	fn close_frame(&mut self, exit_reason: ExitReason, output: Vec<u8>) {
+		if let Some(mut frame) = self.frames.pop() {
+			frame.trace.gas_used = frame.used_gas.saturating_add(frame.memory_gas);
+			frame.trace.exit_reason = Some(exit_reason);
+			frame.trace.output = output;
+
+			match self.frames.last_mut() {
+				Some(parent) => {
+					parent.return_data = frame.trace.output.clone();
+					parent.trace.calls.push(frame.trace);
+				}
+				None => self.root = Some(frame.trace),
+			}
+		}
+	}
+
+	fn close_frames(&mut self) {
+		while !self.frames.is_empty() {
+			let (exit_reason, output) = self
+				.exit
+				.clone()
+				.unwrap_or((ExitReason::Revert(ExitRevert::Reverted), Vec::new()));
+			self.close_frame(exit_reason, output);
+		}
+	}
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+impl Tracer {
+	fn struct_logger(&self) -> Option<&StructLoggerConfig> {
+		match &self.config {
+			TracerConfig::StructLogger(config) => Some(config),
+			_ => None,
+		}
+	}
+
+	fn push_frame(&mut self, trace: CallTrace, gas_limit: u64) {
+		self.frames.push(Frame {
+			trace,
+			started: false,
+			gas_limit,
+			used_gas: 0,
+			memory_gas: 0,
+			return_data: Vec::new(),
+		});
+	}
+
+	fn record_gas(&mut self, gas_limit: u64, used_gas: u64, memory_gas: u64, cost: u64) {
+		if let Some(frame) = self.frames.last_mut() {
+			frame.gas_limit = gas_limit;
+			frame.used_gas = used_gas;
+			frame.memory_gas = memory_gas;
+		}
+		if self.pending_cost {
+			self.pending_cost = false;
+			if let Some(log) = self.struct_logs.last_mut() {
+				log.gas_cost = cost;
+			}
+		}
+	}
+}
+
+fn new_call_trace(call_type: CallType) -> CallTrace {
+	CallTrace {
+		call_type,
+		from: H160::zero(),
+		to: H160::zero(),
+		value: U256::zero(),
+		gas: 0,
+		gas_used: 0,
+		input: Vec::new(),
+		output: Vec::new(),
+		exit_reason: None,
+		calls: Vec::new(),
+	}
+}
+
+#[cfg(feature = "tracing")]
+mod listener {
+	use super::*;
+	use evm::{Capture, CreateScheme, Opcode};
+	use sp_std::{cell::RefCell, rc::Rc};
+
+	/// Shares one tracer between the evm, runtime and gasometer listeners.
+	pub(super) struct Proxy(pub(super) Rc<RefCell<Tracer>>);
+
+	impl evm::tracing::EventListener for Proxy {
+		fn event(&mut self, event: evm::tracing::Event) {
+			self.0.borrow_mut().evm_event(event);
+		}
+	}
+
+	impl evm_runtime::tracing::EventListener for Proxy {
+		fn event(&mut self, event: evm_runtime::tracing::Event) {
+			self.0.borrow_mut().runtime_event(event);
+		}
+	}
+
+	impl evm_gasometer::tracing::EventListener for Proxy {
+		fn event(&mut self, event: evm_gasometer::tracing::Event) {
+			self.0.borrow_mut().gasometer_event(event);
+		}
+	}
+
+	impl Tracer {
+		fn root_gas(&self, target_gas: Option<u64>) -> u64 {
+			match (&self.transact, self.frames.is_empty()) {
+				(Some(transact), true) => transact.gas,
+				_ => target_gas.unwrap_or_default(),
+			}
+		}
+
+		fn evm_event(&mut self, event: evm::tracing::Event) {
+			use evm::tracing::Event;
+
+			match event {
+				Event::TransactCall {
+					caller,
+					address,
+					value,
+					data,
+					gas_limit,
+				} => {
+					self.next_call_type = Some(CallType::Call);
+					self.transact = Some(CallTrace {
+						from: caller,
+						to: address,
+						value,
+						gas: gas_limit,
+						input: data.to_vec(),
+						..new_call_trace(CallType::Call)
+					});
+				}
+				Event::TransactCreate {
+					caller,
+					value,
+					init_code,
+					gas_limit,
+					address,
+				} => {
+					self.next_call_type = Some(CallType::Create);
+					self.transact = Some(CallTrace {
+						from: caller,
+						to: address,
+						value,
+						gas: gas_limit,
+						input: init_code.to_vec(),
+						..new_call_trace(CallType::Create)
+					});
+				}
+				Event::TransactCreate2 {
+					caller,
+					value,
+					init_code,
+					gas_limit,
+					address,
+					..
+				} => {
+					self.next_call_type = Some(CallType::Create2);
+					self.transact = Some(CallTrace {
+						from: caller,
+						to: address,
+						value,
+						gas: gas_limit,
+						input: init_code.to_vec(),
+						..new_call_trace(CallType::Create2)
+					});
+				}
+				Event::Call {
+					code_address,
+					transfer,
+					input,
+					target_gas,
+					is_static,
+					context,
+				} => {
+					let call_type = self.next_call_type.take().unwrap_or(if is_static {
+						CallType::StaticCall
+					} else {
+						CallType::Call
+					});
+					let gas = self.root_gas(target_gas);
+					self.push_frame(
+						CallTrace {
+							from: context.caller,
+							to: code_address,
+							value: transfer
+								.as_ref()
+								.map(|transfer| transfer.value)
+								.unwrap_or(context.apparent_value),
+							gas,
+							input: input.to_vec(),
+							..new_call_trace(call_type)
+						},
+						target_gas.unwrap_or(gas),
+					);
+				}
+				Event::Create {
+					caller,
+					address,
+					scheme,
+					value,
+					init_code,
+					target_gas,
+				} => {
+					let call_type = self.next_call_type.take().unwrap_or(match scheme {
+						CreateScheme::Create2 { .. } => CallType::Create2,
+						_ => CallType::Create,
+					});
+					let gas = self.root_gas(target_gas);
+					self.push_frame(
+						CallTrace {
+							from: caller,
+							to: address,
+							value,
+							gas,
+							input: init_code.to_vec(),
+							..new_call_trace(call_type)
+						},
+						target_gas.unwrap_or(gas),
+					);
+				}
+				Event::Suicide {
+					address,
+					target,
+					balance,
+				} => {
+					if let Some(frame) = self.frames.last_mut() {
+						frame.trace.calls.push(CallTrace {
+							from: address,
+							to: target,
+							value: balance,
+							exit_reason: Some(ExitReason::Succeed(ExitSucceed::Suicided)),
+							..new_call_trace(CallType::SelfDestruct)
+						});
+					}
+				}
+				Event::Exit {
+					reason,
+					return_value,
+				} => {
+					self.exit = Some((reason.clone(), return_value.to_vec()));
+					self.close_frames();
+				}
+				_ => {}
+			}
+		}
+
+		fn runtime_event(&mut self, event: evm_runtime::tracing::Event) {
+			use evm_runtime::tracing::Event;
+
+			match event {
+				Event::Step {
+					context,
+					opcode,
+					position,
+					stack,
+					memory,
+				} => {
+					let pc = position.as_ref().copied().unwrap_or_default();
+					// A frame that did not start on this step never will: it is a precompile or a
+					// call that failed early, and the call opcode pushed its status on the stack.
+					while matches!(self.frames.last(), Some(frame) if !frame.started) {
+						if pc == 0 {
+							if let Some(frame) = self.frames.last_mut() {
+								frame.started = true;
+							}
+							break;
+						}
+						let success = stack.peek(0).map(|v| !v.is_zero()).unwrap_or(false);
+						let exit_reason = if success {
+							ExitReason::Succeed(ExitSucceed::Returned)
+						} else {
+							ExitReason::Revert(ExitRevert::Reverted)
+						};
+						self.close_frame(exit_reason, Vec::new());
+					}
+
+					self.next_call_type = match opcode {
+						Opcode::CREATE => Some(CallType::Create),
+						Opcode::CREATE2 => Some(CallType::Create2),
+						Opcode::CALL => Some(CallType::Call),
+						Opcode::CALLCODE => Some(CallType::CallCode),
+						Opcode::DELEGATECALL => Some(CallType::DelegateCall),
+						Opcode::STATICCALL => Some(CallType::StaticCall),
+						_ => None,
+					};
+
+					if let Some(config) = self.struct_logger().copied() {
+						let (gas, depth, return_data) = match self.frames.last() {
+							Some(frame) => (
+								frame
+									.gas_limit
+									.saturating_sub(frame.used_gas)
+									.saturating_sub(frame.memory_gas),
+								self.frames.len() as u32,
+								frame.return_data.clone(),
+							),
+							None => (0, 1, Vec::new()),
+						};
+						let storage = (!config.disable_storage).then(|| {
+							self.storage
+								.get(&context.address)
+								.cloned()
+								.unwrap_or_default()
+						});
+						self.struct_logs.push(StructLog {
+							pc: pc as u64,
+							op: opcode.0,
+							gas,
+							gas_cost: 0,
+							depth,
+							error: None,
+							stack: (!config.disable_stack).then(|| stack.data().clone()),
+							memory: (!config.disable_memory).then(|| memory.data().clone()),
+							storage,
+							return_data: config.enable_return_data.then_some(return_data),
+						});
+						self.pending_cost = true;
+					}
+				}
+				Event::StepResult {
+					result,
+					return_value,
+				} => {
+					if let Err(Capture::Exit(reason)) = result {
+						if let (ExitReason::Error(_) | ExitReason::Fatal(_), Some(log)) =
+							(reason, self.struct_logs.last_mut())
+						{
+							log.error = Some(reason.clone());
+						}
+						self.close_frame(reason.clone(), return_value.to_vec());
+					}
+				}
+				Event::SLoad {
+					address,
+					index,
+					value,
+				}
+				| Event::SStore {
+					address,
+					index,
+					value,
+				} => {
+					let storage = self.storage.entry(address).or_default();
+					storage.insert(index, value);
+					let storage = storage.clone();
+					if let Some(log) = self.struct_logs.last_mut() {
+						if log.storage.is_some() {
+							log.storage = Some(storage);
+						}
+					}
+				}
+			}
+		}
+
+		fn gasometer_event(&mut self, event: evm_gasometer::tracing::Event) {
+			use evm_gasometer::tracing::Event;
+
+			// Snapshots are taken before the event is applied to the gasometer.
+			let (snapshot, used_gas, memory_gas, cost) = match event {
+				Event::RecordCost {
+					cost,
+					snapshot: Some(s),
+				}
+				| Event::RecordTransaction {
+					cost,
+					snapshot: Some(s),
+				} => (s, s.used_gas.saturating_add(cost), s.memory_gas, cost),
+				Event::RecordDynamicCost {
+					gas_cost,
+					memory_gas,
+					snapshot: Some(s),
+					..
+				} => {
+					let memory_gas = memory_gas.max(s.memory_gas);
+					let cost = gas_cost.saturating_add(memory_gas - s.memory_gas);
+					(s, s.used_gas.saturating_add(gas_cost), memory_gas, cost)
+				}
+				Event::RecordStipend {
+					stipend,
+					snapshot: Some(s),
+				} => (s, s.used_gas.saturating_sub(stipend), s.memory_gas, 0),
+				Event::RecordRefund {
+					snapshot: Some(s), ..
+				} => (s, s.used_gas, s.memory_gas, 0),
+				_ => return,
+			};
+			self.record_gas(snapshot.gas_limit, used_gas, memory_gas, cost);
+		}
+	}
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the state proofs served by `eth_getProof`.
+//!
+//! EVM accounts live in the Substrate state, so the proofs are Substrate trie read proofs rather
+//! than Ethereum Merkle-Patricia proofs. They are checked against the `state_root` of the
+//! Substrate header of the queried block:
+//!
+//! - `accountProof` proves the `System::Account` entry of the address, holding its nonce and
+//!   balance, and the `EVM::AccountCodes` entry of the address.
+//! - each `storageProof[i].proof` proves the `EVM::AccountStorages` entry of the slot.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+use scale_codec::Decode;
+// Substrate
+use sp_core::{H160, H256};
+use sp_io::hashing::{blake2_128, twox_128};
+use sp_runtime::traits::BlakeTwo256;
+use sp_std::vec::Vec;
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+// Frontier
+use fp_storage::{EVM_ACCOUNT_CODES, EVM_ACCOUNT_STORAGES, PALLET_EVM};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProofError {
+	/// The proof is incomplete or does not match the state root.
+	InvalidProof,
+	/// The proven value cannot be decoded.
+	InvalidValue,
+}
+
+fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+	let mut hashed = blake2_128(data).to_vec();
+	hashed.extend_from_slice(data);
+	hashed
+}
+
+fn storage_prefix(pallet: &[u8], storage: &[u8]) -> Vec<u8> {
+	[twox_128(pallet), twox_128(storage)].concat()
+}
+
+/// Storage key of the `System::Account` entry of `account_id`, the encoded runtime account id.
+pub fn system_account_key(account_id: &[u8]) -> Vec<u8> {
+	let mut key = storage_prefix(b"System", b"Account");
+	key.extend(blake2_128_concat(account_id));
+	key
+}
+
+/// Storage key of the `EVM::AccountCodes` entry of `address`.
+pub fn account_code_key(address: H160) -> Vec<u8> {
+	let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_CODES);
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key
+}
+
+/// Storage key of the `EVM::AccountStorages` entry of `address` at `index`.
+pub fn account_storage_key(address: H160, index: H256) -> Vec<u8> {
+	let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_STORAGES);
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key.extend(blake2_128_concat(index.as_bytes()));
+	key
+}
+
+/// Reads the raw value of `key` from `proof`, `None` meaning the key is proven absent.
+pub fn verify_storage_proof(
+	state_root: H256,
+	proof: Vec<Vec<u8>>,
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let db = StorageProof::new(proof).into_memory_db::<BlakeTwo256>();
+	read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, &state_root, key, None, None)
+		.map_err(|_| ProofError::InvalidProof)
+}
+
+/// Verifies the `System::Account` entry of `account_id` in an account proof.
+///
+/// `AccountInfo` is the runtime `frame_system::AccountInfo` type, which holds the nonce and the
+/// balance data.
+pub fn verify_system_account<AccountInfo: Decode>(
+	state_root: H256,
+	proof: Vec<Vec<u8>>,
+	account_id: &[u8],
+) -> Result<Option<AccountInfo>, ProofError> {
+	verify_storage_proof(state_root, proof, &system_account_key(account_id))?
+		.map(|value| AccountInfo::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue))
+		.transpose()
+}
+
+/// Verifies the code of `address` in an account proof, which is empty for non-contracts.
+pub fn verify_account_code(
+	state_root: H256,
+	proof: Vec<Vec<u8>>,
+	address: H160,
+) -> Result<Vec<u8>, ProofError> {
+	match verify_storage_proof(state_root, proof, &account_code_key(address))? {
+		Some(value) => Vec::<u8>::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue),
+		None => Ok(Vec::new()),
+	}
+}
+
+/// Verifies the value of the storage slot `index` of `address` in a storage proof.
+pub fn verify_account_storage(
+	state_root: H256,
+	proof: Vec<Vec<u8>>,
+	address: H160,
+	index: H256,
+) -> Result<H256, ProofError> {
+	match verify_storage_proof(state_root, proof, &account_storage_key(address, index))? {
+		Some(value) => H256::decode(&mut &value[..]).map_err(|_| ProofError::InvalidValue),
+		None => Ok(H256::zero()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_codec::Encode;
+	use sp_core::storage::StateVersion;
+	use sp_state_machine::{prove_read, Backend, InMemoryBackend};
+
+	fn proven_state(entries: Vec<(Vec<u8>, Vec<u8>)>, keys: &[Vec<u8>]) -> (H256, Vec<Vec<u8>>) {
+		let backend = InMemoryBackend::<BlakeTwo256>::from((
+			vec![(
+				None,
+				entries
+					.into_iter()
+					.map(|(key, value)| (key, Some(value)))
+					.collect(),
+			)],
+			StateVersion::V1,
+		));
+		let root = *backend.root();
+		let proof = prove_read(backend, keys).expect("keys are readable");
+		(root, proof.into_iter_nodes().collect())
+	}
+
+	#[test]
+	fn verifies_account_storage() {
+		let address = H160::repeat_byte(0x11);
+		let index = H256::repeat_byte(0x22);
+		let value = H256::repeat_byte(0x33);
+		let key = account_storage_key(address, index);
+		let (root, proof) = proven_state(vec![(key.clone(), value.encode())], &[key]);
+
+		assert_eq!(
+			verify_account_storage(root, proof.clone(), address, index),
+			Ok(value)
+		);
+		assert_eq!(
+			verify_account_storage(root, proof, address, H256::repeat_byte(0x44)),
+			Ok(H256::zero())
+		);
+	}
+
+	#[test]
+	fn verifies_account_code() {
+		let address = H160::repeat_byte(0x11);
+		let code = vec![0x60, 0x00];
+		let key = account_code_key(address);
+		let (root, proof) = proven_state(vec![(key.clone(), code.encode())], &[key]);
+
+		assert_eq!(verify_account_code(root, proof, address), Ok(code));
+	}
+
+	#[test]
+	fn rejects_proof_of_another_root() {
+		let address = H160::repeat_byte(0x11);
+		let index = H256::repeat_byte(0x22);
+		let key = account_storage_key(address, index);
+		let (_, proof) = proven_state(vec![(key.clone(), H256::zero().encode())], &[key]);
+
+		assert_eq!(
+			verify_account_storage(H256::repeat_byte(0xff), proof, address, index),
+			Err(ProofError::InvalidProof)
+		);
+	}
+}
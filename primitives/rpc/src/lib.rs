@@ -85,7 +85,7 @@ impl<B: BlockT, C> RuntimeStorageOverride<B, C> for () {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
-	#[api_version(5)]
+	#[api_version(6)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -100,6 +100,9 @@ sp_api::decl_runtime_apis! {
 		/// For a given account address and index, returns pallet_evm::AccountStorages.
 		fn storage_at(address: H160, index: U256) -> H256;
 		/// Returns a frame_ethereum::call response. If `estimate` is true,
+		///
+		/// Since version 6, also returns the accounts and storage slots accessed by the call,
+		/// precompiles left out, as an EIP-2930 access list.
 		#[changed_in(2)]
 		fn call(
 			from: H160,
@@ -136,6 +139,7 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfo::<Vec<u8>>, sp_runtime::DispatchError>;
+		#[changed_in(6)]
 		fn call(
 			from: H160,
 			to: H160,
@@ -148,7 +152,26 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+		) -> Result<
+			(fp_evm::ExecutionInfoV2::<Vec<u8>>, Vec<(H160, Vec<H256>)>),
+			sp_runtime::DispatchError,
+		>;
 		/// Returns a frame_ethereum::create response.
+		///
+		/// Since version 6, also returns the accounts and storage slots accessed by the creation,
+		/// precompiles left out, as an EIP-2930 access list.
 		#[changed_in(2)]
 		fn create(
 			from: H160,
@@ -182,6 +205,7 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfo::<H160>, sp_runtime::DispatchError>;
+		#[changed_in(6)]
 		fn create(
 			from: H160,
 			data: Vec<u8>,
@@ -193,6 +217,21 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<H160>, sp_runtime::DispatchError>;
+		fn create(
+			from: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+		) -> Result<
+			(fp_evm::ExecutionInfoV2::<H160>, Vec<(H160, Vec<H256>)>),
+			sp_runtime::DispatchError,
+		>;
 		/// Return the current block. Legacy.
 		#[changed_in(2)]
 		fn current_block() -> Option<ethereum::BlockV0>;
@@ -244,6 +283,40 @@ sp_api::decl_runtime_apis! {
 		) -> (Option<ethereum::BlockV2>, Option<Vec<TransactionStatus>>);
 	}
 
+	/// API to replay Ethereum transactions and calls with an EVM tracer attached.
+	pub trait DebugRuntimeApi {
+		/// Applies `extrinsics` on top of the initialized block state until `transaction` is
+		/// found, then executes it with `tracer` attached.
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &ethereum::TransactionV2,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, sp_runtime::DispatchError>;
+		/// Executes a call, the same way as `EthereumRuntimeRPCApi::call`, with `tracer` attached.
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, sp_runtime::DispatchError>;
+		/// Applies `extrinsics` on top of the initialized block state, executing the Ethereum
+		/// transactions with `tracer` attached. Traces are returned in transaction order, a
+		/// transaction failing to be traced does not prevent tracing the following ones.
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<
+			Vec<Result<fp_evm::tracing::TraceResult, sp_runtime::DispatchError>>,
+			sp_runtime::DispatchError,
+		>;
+	}
+
 	#[api_version(2)]
 	pub trait ConvertTransactionRuntimeApi {
 		fn convert_transaction(transaction: ethereum::TransactionV2) -> <Block as BlockT>::Extrinsic;
@@ -12,6 +12,7 @@ use sp_core::{storage::Storage, Pair, Public, H160, U256};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 use sp_state_machine::BasicExternalities;
 // Frontier
+use fc_cli::EthereumGenesis;
 use frontier_template_runtime::{
 	AccountId, Balance, EnableManualSeal, GenesisConfig, SS58Prefix, Signature, WASM_BINARY,
 };
@@ -79,7 +80,10 @@ fn properties() -> Properties {
 
 const UNITS: Balance = 1_000_000_000_000_000_000;
 
-pub fn development_config(enable_manual_seal: Option<bool>) -> DevChainSpec {
+pub fn development_config(
+	enable_manual_seal: Option<bool>,
+	ethereum_genesis: Option<EthereumGenesis>,
+) -> DevChainSpec {
 	let wasm_binary = WASM_BINARY.expect("WASM not available");
 
 	DevChainSpec::from_genesis(
@@ -89,25 +93,35 @@ pub fn development_config(enable_manual_seal: Option<bool>) -> DevChainSpec {
 		"dev",
 		ChainType::Development,
 		move || {
+			let mut genesis_config = testnet_genesis(
+				wasm_binary,
+				// Sudo account (Alith)
+				AccountId::from(hex!("f24FF3a9CF04c71Dbc94D0b566f7A27B94566cac")),
+				// Pre-funded accounts
+				vec![
+					AccountId::from(hex!("f24FF3a9CF04c71Dbc94D0b566f7A27B94566cac")), // Alith
+					AccountId::from(hex!("3Cd0A705a2DC65e5b1E1205896BaA2be8A07c6e0")), // Baltathar
+					AccountId::from(hex!("798d4Ba9baf0064Ec19eB4F0a1a45785ae9D6DFc")), // Charleth
+					AccountId::from(hex!("773539d4Ac0e786233D90A233654ccEE26a613D9")), // Dorothy
+					AccountId::from(hex!("Ff64d3F6efE2317EE2807d223a0Bdc4c0c49dfDB")), // Ethan
+					AccountId::from(hex!("C0F0f4ab324C46e55D02D0033343B4Be8A55532d")), // Faith
+				],
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice")],
+				// Ethereum chain ID
+				SS58Prefix::get() as u64,
+			);
+			if let Some(ethereum_genesis) = &ethereum_genesis {
+				if let Some(chain_id) = ethereum_genesis.chain_id {
+					genesis_config.evm_chain_id.chain_id = chain_id;
+				}
+				genesis_config
+					.evm
+					.accounts
+					.extend(ethereum_genesis.alloc.clone());
+			}
 			DevGenesisExt {
-				genesis_config: testnet_genesis(
-					wasm_binary,
-					// Sudo account (Alith)
-					AccountId::from(hex!("f24FF3a9CF04c71Dbc94D0b566f7A27B94566cac")),
-					// Pre-funded accounts
-					vec![
-						AccountId::from(hex!("f24FF3a9CF04c71Dbc94D0b566f7A27B94566cac")), // Alith
-						AccountId::from(hex!("3Cd0A705a2DC65e5b1E1205896BaA2be8A07c6e0")), // Baltathar
-						AccountId::from(hex!("798d4Ba9baf0064Ec19eB4F0a1a45785ae9D6DFc")), // Charleth
-						AccountId::from(hex!("773539d4Ac0e786233D90A233654ccEE26a613D9")), // Dorothy
-						AccountId::from(hex!("Ff64d3F6efE2317EE2807d223a0Bdc4c0c49dfDB")), // Ethan
-						AccountId::from(hex!("C0F0f4ab324C46e55D02D0033343B4Be8A55532d")), // Faith
-					],
-					// Initial PoA authorities
-					vec![authority_keys_from_seed("Alice")],
-					// Ethereum chain ID
-					SS58Prefix::get() as u64,
-				),
+				genesis_config,
 				enable_manual_seal,
 			}
 		},
@@ -267,5 +281,6 @@ fn testnet_genesis(
 		ethereum: Default::default(),
 		dynamic_fee: Default::default(),
 		base_fee: Default::default(),
+		assets: Default::default(),
 	}
 }
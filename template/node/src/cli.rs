@@ -23,6 +23,12 @@ pub struct Cli {
 	#[arg(long, value_enum, ignore_case = true)]
 	pub sealing: Option<Sealing>,
 
+	/// Add the accounts and chain id of a geth-style `genesis.json` to the dev chain spec.
+	///
+	/// Only the `dev` chain spec is affected, `local` and chain spec files ignore it.
+	#[arg(long, value_name = "PATH")]
+	pub ethereum_genesis: Option<std::path::PathBuf>,
+
 	#[command(flatten)]
 	pub eth: EthConfiguration,
 }
@@ -65,4 +71,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	FrontierDb(fc_cli::FrontierDbCmd),
+
+	/// Export the EVM state of a given block as a geth-style `genesis.json`.
+	ExportEvmState(fc_cli::ExportEvmStateCmd),
 }
@@ -60,7 +60,15 @@ impl SubstrateCli for Cli {
 		Ok(match id {
 			"dev" => {
 				let enable_manual_seal = self.sealing.map(|_| true);
-				Box::new(chain_spec::development_config(enable_manual_seal))
+				let ethereum_genesis = self
+					.ethereum_genesis
+					.as_deref()
+					.map(fc_cli::EthereumGenesis::from_json_file)
+					.transpose()?;
+				Box::new(chain_spec::development_config(
+					enable_manual_seal,
+					ethereum_genesis,
+				))
 			}
 			"" | "local" => Box::new(chain_spec::local_testnet_config()),
 			path => Box::new(chain_spec::ChainSpec::from_json_file(
@@ -141,6 +149,11 @@ pub fn run() -> sc_cli::Result<()> {
 						};
 						cmd.run(frontier_database_config)?;
 					}
+					crate::eth::BackendType::Sql
+						if cli.eth.frontier_sql_backend_postgres_url.is_some() =>
+					{
+						return Err("Cannot purge a Postgres database, drop it manually".into());
+					}
 					crate::eth::BackendType::Sql => {
 						let db_path = db_config_dir.join("sql");
 						match std::fs::remove_dir_all(&db_path) {
@@ -236,8 +249,15 @@ pub fn run() -> sc_cli::Result<()> {
 		Some(Subcommand::FrontierDb(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|mut config| {
+				let tokio_handle = config.tokio_handle.clone();
 				let (client, _, _, _, frontier_backend) =
 					service::new_chain_ops(&mut config, &cli.eth)?;
+				if let Some(maintenance) = &cmd.maintenance {
+					let overrides = crate::rpc::overrides_handle(client.clone());
+					// The SQL backend indexes the blocks on the tokio runtime.
+					let _guard = tokio_handle.enter();
+					return maintenance.run(client, frontier_backend, overrides);
+				}
 				let frontier_backend = match frontier_backend {
 					fc_db::Backend::KeyValue(kv) => std::sync::Arc::new(kv),
 					_ => panic!("Only fc_db::Backend::KeyValue supported"),
@@ -245,6 +265,13 @@ pub fn run() -> sc_cli::Result<()> {
 				cmd.run(client, frontier_backend)
 			})
 		}
+		Some(Subcommand::ExportEvmState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|mut config| {
+				let (client, _, _, _, _) = service::new_chain_ops(&mut config, &cli.eth)?;
+				cmd.run(client)
+			})
+		}
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
@@ -5,6 +5,7 @@ use jsonrpsee::RpcModule;
 use sc_client_api::{
 	backend::{Backend, StorageProvider},
 	client::BlockchainEvents,
+	BlockBackend, ProofProvider,
 };
 use sc_network::NetworkService;
 use sc_network_sync::SyncingService;
@@ -20,7 +21,9 @@ use sp_runtime::traits::Block as BlockT;
 pub use fc_rpc::{EthBlockDataCacheTask, EthConfig, OverrideHandle, StorageOverride, TxPool};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 pub use fc_storage::overrides_handle;
-use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
+use fp_rpc::{
+	ConvertTransaction, ConvertTransactionRuntimeApi, DebugRuntimeApi, EthereumRuntimeRPCApi,
+};
 
 /// Extra dependencies for Ethereum compatibility.
 pub struct EthDeps<C, P, A: ChainApi, CT, B: BlockT> {
@@ -36,6 +39,10 @@ pub struct EthDeps<C, P, A: ChainApi, CT, B: BlockT> {
 	pub is_authority: bool,
 	/// Whether to enable dev signer
 	pub enable_dev_signer: bool,
+	/// Whether to enable the EVM tracing RPC
+	pub enable_evm_tracing: bool,
+	/// Dev rpc state of manual seal nodes, enables account impersonation
+	pub dev_state: Option<fc_rpc::DevState>,
 	/// Network service
 	pub network: Arc<NetworkService<B, B::Hash>>,
 	/// Chain syncing service
@@ -50,6 +57,8 @@ pub struct EthDeps<C, P, A: ChainApi, CT, B: BlockT> {
 	pub filter_pool: Option<FilterPool>,
 	/// Maximum number of logs in a query.
 	pub max_past_logs: u32,
+	/// Maximum number of blocks a `trace_filter` query can span.
+	pub max_trace_filter_range: u32,
 	/// Fee history cache.
 	pub fee_history_cache: FeeHistoryCache,
 	/// Maximum fee history cache size.
@@ -70,6 +79,8 @@ impl<C, P, A: ChainApi, CT: Clone, B: BlockT> Clone for EthDeps<C, P, A, CT, B>
 			converter: self.converter.clone(),
 			is_authority: self.is_authority,
 			enable_dev_signer: self.enable_dev_signer,
+			enable_evm_tracing: self.enable_evm_tracing,
+			dev_state: self.dev_state.clone(),
 			network: self.network.clone(),
 			sync: self.sync.clone(),
 			frontier_backend: self.frontier_backend.clone(),
@@ -77,6 +88,7 @@ impl<C, P, A: ChainApi, CT: Clone, B: BlockT> Clone for EthDeps<C, P, A, CT, B>
 			block_data_cache: self.block_data_cache.clone(),
 			filter_pool: self.filter_pool.clone(),
 			max_past_logs: self.max_past_logs,
+			max_trace_filter_range: self.max_trace_filter_range,
 			fee_history_cache: self.fee_history_cache.clone(),
 			fee_history_cache_limit: self.fee_history_cache_limit,
 			execute_gas_limit_multiplier: self.execute_gas_limit_multiplier,
@@ -99,17 +111,24 @@ pub fn create_eth<C, BE, P, A, CT, B, EC: EthConfig<B, C>>(
 where
 	B: BlockT<Hash = sp_core::H256>,
 	C: CallApiAt<B> + ProvideRuntimeApi<B>,
-	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
-	C: BlockchainEvents<B> + 'static,
-	C: HeaderBackend<B> + HeaderMetadata<B, Error = BlockChainError> + StorageProvider<B, BE>,
+	C::Api: BlockBuilderApi<B>
+		+ ConvertTransactionRuntimeApi<B>
+		+ DebugRuntimeApi<B>
+		+ EthereumRuntimeRPCApi<B>,
+	C: BlockchainEvents<B> + BlockBackend<B> + 'static,
+	C: HeaderBackend<B>
+		+ HeaderMetadata<B, Error = BlockChainError>
+		+ ProofProvider<B>
+		+ StorageProvider<B, BE>,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B> + 'static,
 	A: ChainApi<Block = B> + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
 	use fc_rpc::{
-		Eth, EthApiServer, EthDevSigner, EthFilter, EthFilterApiServer, EthPubSub,
-		EthPubSubApiServer, EthSigner, Net, NetApiServer, TxPoolApiServer, Web3, Web3ApiServer,
+		Debug, DebugApiServer, Eth, EthApiServer, EthDevSigner, EthFilter, EthFilterApiServer,
+		EthImpersonationSigner, EthPubSub, EthPubSubApiServer, EthSigner, Net, NetApiServer, Ots,
+		OtsApiServer, Trace, TraceApiServer, TxPoolApiServer, Web3, Web3ApiServer,
 	};
 
 	let EthDeps {
@@ -119,6 +138,8 @@ where
 		converter,
 		is_authority,
 		enable_dev_signer,
+		enable_evm_tracing,
+		dev_state,
 		network,
 		sync,
 		frontier_backend,
@@ -126,6 +147,7 @@ where
 		block_data_cache,
 		filter_pool,
 		max_past_logs,
+		max_trace_filter_range,
 		fee_history_cache,
 		fee_history_cache_limit,
 		execute_gas_limit_multiplier,
@@ -136,6 +158,9 @@ where
 	if enable_dev_signer {
 		signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
 	}
+	if let Some(dev_state) = dev_state {
+		signers.push(Box::new(EthImpersonationSigner::new(dev_state)) as Box<dyn EthSigner>);
+	}
 
 	io.merge(
 		Eth::new(
@@ -158,6 +183,35 @@ where
 		.into_rpc(),
 	)?;
 
+	if enable_evm_tracing {
+		io.merge(
+			Debug::new(
+				client.clone(),
+				frontier_backend.clone(),
+				block_data_cache.clone(),
+			)
+			.into_rpc(),
+		)?;
+	}
+	io.merge(
+		Trace::new(
+			client.clone(),
+			frontier_backend.clone(),
+			max_past_logs,
+			max_trace_filter_range,
+		)
+		.into_rpc(),
+	)?;
+	io.merge(
+		Ots::new(
+			client.clone(),
+			frontier_backend.clone(),
+			overrides.clone(),
+			block_data_cache.clone(),
+		)
+		.into_rpc(),
+	)?;
+
 	let tx_pool = TxPool::new(client.clone(), graph);
 	if let Some(filter_pool) = filter_pool {
 		io.merge(
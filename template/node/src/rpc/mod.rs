@@ -8,6 +8,7 @@ use jsonrpsee::RpcModule;
 use sc_client_api::{
 	backend::{Backend, StorageProvider},
 	client::BlockchainEvents,
+	BlockBackend, ProofProvider,
 };
 use sc_consensus_manual_seal::rpc::EngineCommand;
 use sc_rpc::SubscriptionTaskExecutor;
@@ -24,9 +25,13 @@ mod eth;
 pub use self::eth::{create_eth, overrides_handle, EthDeps};
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, A: ChainApi, CT> {
+pub struct FullDeps<C, P, BE, A: ChainApi, CT> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<BE>,
+	/// Frontier backend, reverted along with `backend` by the dev rpc.
+	pub frontier_backend: fc_db::Backend<Block>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
@@ -51,7 +56,7 @@ where
 
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, BE, A, CT>(
-	deps: FullDeps<C, P, A, CT>,
+	deps: FullDeps<C, P, BE, A, CT>,
 	subscription_task_executor: SubscriptionTaskExecutor,
 	pubsub_notification_sinks: Arc<
 		fc_mapping_sync::EthereumBlockNotificationSinks<
@@ -66,15 +71,18 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
-	C: BlockchainEvents<Block> + 'static,
+	C::Api: fp_rpc::DebugRuntimeApi<Block>,
+	C: BlockchainEvents<Block> + BlockBackend<Block> + 'static,
 	C: HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ ProofProvider<Block>
 		+ StorageProvider<Block, BE>,
 	BE: Backend<Block> + 'static,
 	P: TransactionPool<Block = Block> + 'static,
 	A: ChainApi<Block = Block> + 'static,
 	CT: fp_rpc::ConvertTransaction<<Block as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
+	use fc_rpc::{Dev, DevApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
@@ -82,6 +90,8 @@ where
 	let mut io = RpcModule::new(());
 	let FullDeps {
 		client,
+		backend,
+		frontier_backend,
 		pool,
 		deny_unsafe,
 		command_sink,
@@ -89,14 +99,20 @@ where
 	} = deps;
 
 	io.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-	io.merge(TransactionPayment::new(client).into_rpc())?;
+	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 
 	if let Some(command_sink) = command_sink {
 		io.merge(
 			// We provide the rpc handler with the sending end of the channel to allow the rpc
 			// send EngineCommands to the background block authorship task.
-			ManualSeal::new(command_sink).into_rpc(),
+			ManualSeal::new(command_sink.clone()).into_rpc(),
 		)?;
+		// Anvil/Hardhat compatible `evm_*` and `anvil_*` methods.
+		if let Some(dev_state) = eth.dev_state.clone() {
+			io.merge(
+				Dev::new(client, backend, frontier_backend, command_sink, dev_state).into_rpc(),
+			)?;
+		}
 	}
 
 	// Ethereum compatibility RPCs
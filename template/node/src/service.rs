@@ -1,6 +1,6 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use std::{cell::RefCell, path::Path, sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use futures::{channel::mpsc, prelude::*};
 // Substrate
@@ -83,6 +83,17 @@ where
 		ServiceError,
 	>,
 {
+	// The tracing runtime api only replays the EVM execution in runtimes built with tracing.
+	if !cfg!(feature = "evm-tracing")
+		&& (eth_config.enable_evm_tracing || eth_config.frontier_sql_backend_index_traces)
+	{
+		return Err(ServiceError::Other(
+			"`--enable-evm-tracing` and `--frontier-sql-backend-index-traces` need a node built \
+			with the `evm-tracing` feature"
+				.to_string(),
+		));
+	}
+
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -127,24 +138,43 @@ where
 			&db_config_dir(config),
 		)?),
 		BackendType::Sql => {
-			let db_path = db_config_dir(config).join("sql");
-			std::fs::create_dir_all(&db_path).expect("failed creating sql db directory");
+			let sqlite_path;
+			let backend_config = match &eth_config.frontier_sql_backend_postgres_url {
+				Some(url) => {
+					fc_db::sql::BackendConfig::Postgres(fc_db::sql::PostgresBackendConfig {
+						url,
+						store_block_data: eth_config.frontier_store_block_data,
+						index_addresses: eth_config.frontier_sql_backend_index_addresses,
+					})
+				}
+				None => {
+					let db_path = db_config_dir(config).join("sql");
+					std::fs::create_dir_all(&db_path).expect("failed creating sql db directory");
+					sqlite_path = Path::new("sqlite:///").join(db_path).join("frontier.db3");
+					fc_db::sql::BackendConfig::Sqlite(fc_db::sql::SqliteBackendConfig {
+						path: sqlite_path.to_str().unwrap(),
+						create_if_missing: true,
+						thread_count: eth_config.frontier_sql_backend_thread_count,
+						cache_size: eth_config.frontier_sql_backend_cache_size,
+						store_block_data: eth_config.frontier_store_block_data,
+						index_addresses: eth_config.frontier_sql_backend_index_addresses,
+					})
+				}
+			};
 			let backend = futures::executor::block_on(fc_db::sql::Backend::new(
-				fc_db::sql::BackendConfig::Sqlite(fc_db::sql::SqliteBackendConfig {
-					path: Path::new("sqlite:///")
-						.join(db_path)
-						.join("frontier.db3")
-						.to_str()
-						.unwrap(),
-					create_if_missing: true,
-					thread_count: eth_config.frontier_sql_backend_thread_count,
-					cache_size: eth_config.frontier_sql_backend_cache_size,
-				}),
+				backend_config,
 				eth_config.frontier_sql_backend_pool_size,
 				std::num::NonZeroU32::new(eth_config.frontier_sql_backend_num_ops_timeout),
 				overrides.clone(),
 			))
 			.unwrap_or_else(|err| panic!("failed creating sql backend: {:?}", err));
+			let backend = if eth_config.frontier_sql_backend_index_traces {
+				backend.with_block_tracer(Arc::new(fc_db::sql::RuntimeBlockTracer::new(
+					client.clone(),
+				)))
+			} else {
+				backend
+			};
 			FrontierBackend::Sql(backend)
 		}
 	};
@@ -359,6 +389,8 @@ where
 
 	// Channel for the rpc handler to communicate with the authorship task.
 	let (command_sink, commands_stream) = mpsc::channel(1000);
+	// Block time and state changes requested through the dev rpc.
+	let dev_state = fc_rpc::DevState::default();
 
 	// Sinks for pubsub notifications.
 	// Everytime a new subscription is created, a new mpsc channel is added to the sink pool.
@@ -371,6 +403,11 @@ where
 
 	// for ethereum-compatibility rpc.
 	config.rpc_id_provider = Some(Box::new(fc_rpc::EthereumSubIdProvider));
+	let frontier_backend_reader: Arc<dyn fc_db::BackendReader<Block> + Send + Sync> =
+		match frontier_backend.clone() {
+			fc_db::Backend::KeyValue(b) => Arc::new(b),
+			fc_db::Backend::Sql(b) => Arc::new(b),
+		};
 	let eth_rpc_params = crate::rpc::EthDeps {
 		client: client.clone(),
 		pool: transaction_pool.clone(),
@@ -378,22 +415,23 @@ where
 		converter: Some(TransactionConverter),
 		is_authority: config.role.is_authority(),
 		enable_dev_signer: eth_config.enable_dev_signer,
+		enable_evm_tracing: eth_config.enable_evm_tracing,
+		dev_state: sealing.is_some().then(|| dev_state.clone()),
 		network: network.clone(),
 		sync: sync_service.clone(),
-		frontier_backend: match frontier_backend.clone() {
-			fc_db::Backend::KeyValue(b) => Arc::new(b),
-			fc_db::Backend::Sql(b) => Arc::new(b),
-		},
+		frontier_backend: frontier_backend_reader.clone(),
 		overrides: overrides.clone(),
 		block_data_cache: Arc::new(fc_rpc::EthBlockDataCacheTask::new(
 			task_manager.spawn_handle(),
 			overrides.clone(),
+			frontier_backend_reader,
 			eth_config.eth_log_block_cache,
 			eth_config.eth_statuses_cache,
 			prometheus_registry.clone(),
 		)),
 		filter_pool: filter_pool.clone(),
 		max_past_logs: eth_config.max_past_logs,
+		max_trace_filter_range: eth_config.max_trace_filter_range,
 		fee_history_cache: fee_history_cache.clone(),
 		fee_history_cache_limit,
 		execute_gas_limit_multiplier: eth_config.execute_gas_limit_multiplier,
@@ -402,12 +440,16 @@ where
 
 	let rpc_builder = {
 		let client = client.clone();
+		let backend = backend.clone();
+		let frontier_backend = frontier_backend.clone();
 		let pool = transaction_pool.clone();
 		let pubsub_notification_sinks = pubsub_notification_sinks.clone();
 
 		Box::new(move |deny_unsafe, subscription_task_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
+				backend: backend.clone(),
+				frontier_backend: frontier_backend.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
 				command_sink: if sealing.is_some() {
@@ -447,12 +489,14 @@ where
 		client.clone(),
 		backend,
 		frontier_backend,
+		eth_config.frontier_store_block_data,
 		filter_pool,
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
 		sync_service.clone(),
 		pubsub_notification_sinks,
+		prometheus_registry.clone(),
 	)
 	.await;
 
@@ -470,6 +514,7 @@ where
 				prometheus_registry.as_ref(),
 				telemetry.as_ref(),
 				commands_stream,
+				dev_state,
 			)?;
 
 			network_starter.start_network();
@@ -584,6 +629,7 @@ fn run_manual_seal_authorship<RuntimeApi, Executor>(
 	prometheus_registry: Option<&Registry>,
 	telemetry: Option<&Telemetry>,
 	commands_stream: mpsc::Receiver<sc_consensus_manual_seal::rpc::EngineCommand<Hash>>,
+	dev_state: fc_rpc::DevState,
 ) -> Result<(), ServiceError>
 where
 	RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>>,
@@ -600,11 +646,10 @@ where
 		telemetry.as_ref().map(|x| x.handle()),
 	);
 
-	thread_local!(static TIMESTAMP: RefCell<u64> = RefCell::new(0));
-
 	/// Provide a mock duration starting at 0 in millisecond for timestamp inherent.
-	/// Each call will increment timestamp by slot_duration making Aura think time has passed.
-	struct MockTimestampInherentDataProvider;
+	/// Each call will increment timestamp by slot_duration making Aura think time has passed,
+	/// unless the dev rpc moved the clock.
+	struct MockTimestampInherentDataProvider(fc_rpc::DevState);
 
 	#[async_trait::async_trait]
 	impl sp_inherents::InherentDataProvider for MockTimestampInherentDataProvider {
@@ -612,10 +657,10 @@ where
 			&self,
 			inherent_data: &mut sp_inherents::InherentData,
 		) -> Result<(), sp_inherents::Error> {
-			TIMESTAMP.with(|x| {
-				*x.borrow_mut() += frontier_template_runtime::SLOT_DURATION;
-				inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &*x.borrow())
-			})
+			let timestamp = self
+				.0
+				.next_timestamp(frontier_template_runtime::SLOT_DURATION);
+			inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
 		}
 
 		async fn try_handle_error(
@@ -629,10 +674,14 @@ where
 	}
 
 	let target_gas_price = eth_config.target_gas_price;
-	let create_inherent_data_providers = move |_, ()| async move {
-		let timestamp = MockTimestampInherentDataProvider;
-		let dynamic_fee = fp_dynamic_fee::InherentDataProvider(U256::from(target_gas_price));
-		Ok((timestamp, dynamic_fee))
+	let create_inherent_data_providers = move |_, ()| {
+		let dev_state = dev_state.clone();
+		async move {
+			let timestamp = MockTimestampInherentDataProvider(dev_state.clone());
+			let dynamic_fee = fp_dynamic_fee::InherentDataProvider(U256::from(target_gas_price));
+			let dev = dev_state.inherent_data_provider();
+			Ok((timestamp, dynamic_fee, dev))
+		}
 	};
 
 	let manual_seal = match sealing {
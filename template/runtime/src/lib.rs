@@ -35,21 +35,25 @@ use frame_support::weights::constants::ParityDbWeight as RuntimeDbWeight;
 use frame_support::weights::constants::RocksDbWeight as RuntimeDbWeight;
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU32, ConstU8, FindAuthor, OnFinalize, OnTimestampSet},
+	traits::{AsEnsureOriginWithArg, ConstU32, ConstU8, FindAuthor, OnFinalize, OnTimestampSet},
 	weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, ConstantMultiplier, IdentityFee, Weight},
 };
+use frame_system::{EnsureRoot, EnsureSigned};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
 use pallet_transaction_payment::CurrencyAdapter;
 // Frontier
 use fp_account::EthereumSignature;
-use fp_evm::weight_per_gas;
+use fp_evm::{
+	tracing::{TraceResult, TracerConfig},
+	weight_per_gas, BlockOverrides,
+};
 use fp_rpc::TransactionStatus;
 use pallet_ethereum::{Call::transact, PostLogContent, Transaction as EthereumTransaction};
 use pallet_evm::{
-	Account as EVMAccount, EnsureAccountId20, FeeCalculator, GasWeightMapping,
-	IdentityAddressMapping, Runner,
+	Account as EVMAccount, EnsureAccountId20, EvmConfig, FeeCalculator, ForkAt, ForkSchedule,
+	GasWeightMapping, IdentityAddressMapping, London, Runner, Shanghai,
 };
 
 // A few exports that help ease life for downstream crates.
@@ -322,6 +326,9 @@ parameter_types! {
 	pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
 	pub PrecompilesValue: FrontierPrecompiles<Runtime> = FrontierPrecompiles::<_>::new();
 	pub WeightPerGas: Weight = Weight::from_parts(weight_per_gas(BLOCK_GAS_LIMIT, NORMAL_DISPATCH_RATIO, WEIGHT_MILLISECS_PER_BLOCK), 0);
+	/// Block from which the Shanghai rules apply, the London rules applying before it. Chains
+	/// started on London schedule Shanghai by setting it, without a runtime upgrade.
+	pub storage ShanghaiBlock: BlockNumber = 0;
 }
 
 impl pallet_evm::Config for Runtime {
@@ -345,6 +352,7 @@ impl pallet_evm::Config for Runtime {
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
+	type ForkSchedule = ForkAt<ShanghaiBlock, London, Shanghai>;
 }
 
 parameter_types! {
@@ -356,6 +364,8 @@ impl pallet_ethereum::Config for Runtime {
 	type StateRoot = pallet_ethereum::IntermediateStateRoot<Self>;
 	type PostLogContent = PostBlockAndTxnHashes;
 	type ExtraDataLength = ConstU32<30>;
+	// Impersonated transactions are only accepted on manual seal chains.
+	type AllowImpersonation = EnableManualSeal;
 }
 
 parameter_types! {
@@ -396,6 +406,11 @@ impl pallet_hotfix_sufficients::Config for Runtime {
 	type WeightInfo = pallet_hotfix_sufficients::weights::SubstrateWeight<Self>;
 }
 
+impl pallet_evm_dev::Config for Runtime {
+	// Dev state changes are only accepted on manual seal chains.
+	type Enabled = EnableManualSeal;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -416,6 +431,8 @@ construct_runtime!(
 		DynamicFee: pallet_dynamic_fee,
 		BaseFee: pallet_base_fee,
 		HotfixSufficients: pallet_hotfix_sufficients,
+		EVMDev: pallet_evm_dev,
+		Assets: pallet_assets,
 	}
 );
 
@@ -540,6 +557,18 @@ impl fp_self_contained::SelfContainedCall for RuntimeCall {
 	}
 }
 
+/// EVM config of a runtime API call, following the fork schedule at the overridden block number.
+fn scheduled_evm_config(block_overrides: &Option<BlockOverrides>) -> &'static EvmConfig {
+	type Schedule = <Runtime as pallet_evm::Config>::ForkSchedule;
+	match block_overrides
+		.as_ref()
+		.and_then(|overrides| overrides.number)
+	{
+		Some(number) => Schedule::config_at(number.min(BlockNumber::MAX.into()).low_u32()),
+		None => <Runtime as pallet_evm::Config>::config(),
+	}
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -678,9 +707,10 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
-		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
+			block_overrides: Option<BlockOverrides>,
+		) -> Result<(pallet_evm::CallInfo, Vec<(H160, Vec<H256>)>), sp_runtime::DispatchError> {
 			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				let mut config = scheduled_evm_config(&block_overrides).clone();
 				config.estimate = true;
 				Some(config)
 			} else {
@@ -689,7 +719,7 @@ impl_runtime_apis! {
 
 			let is_transactional = false;
 			let validate = true;
-			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			let evm_config = config.as_ref().unwrap_or(scheduled_evm_config(&block_overrides));
 
 			let mut estimated_transaction_len = data.len() +
 				20 + // to
@@ -725,22 +755,27 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::call(
-				from,
-				to,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				is_transactional,
-				validate,
-				weight_limit,
-				proof_size_base_cost,
-				evm_config,
-			).map_err(|err| err.error.into())
+			let (info, accessed) = pallet_evm::Pallet::<Runtime>::access_list(|| {
+				pallet_evm::runner::stack::with_block_overrides(block_overrides.unwrap_or_default(), || {
+					<Runtime as pallet_evm::Config>::Runner::call(
+						from,
+						to,
+						data,
+						value,
+						gas_limit.unique_saturated_into(),
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list.unwrap_or_default(),
+						is_transactional,
+						validate,
+						weight_limit,
+						proof_size_base_cost,
+						evm_config,
+					)
+				})
+			});
+			info.map(|info| (info, accessed)).map_err(|err| err.error.into())
 		}
 
 		fn create(
@@ -753,9 +788,10 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
-		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
+			block_overrides: Option<BlockOverrides>,
+		) -> Result<(pallet_evm::CreateInfo, Vec<(H160, Vec<H256>)>), sp_runtime::DispatchError> {
 			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				let mut config = scheduled_evm_config(&block_overrides).clone();
 				config.estimate = true;
 				Some(config)
 			} else {
@@ -764,7 +800,7 @@ impl_runtime_apis! {
 
 			let is_transactional = false;
 			let validate = true;
-			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			let evm_config = config.as_ref().unwrap_or(scheduled_evm_config(&block_overrides));
 
 			let mut estimated_transaction_len = data.len() +
 				20 + // from
@@ -803,21 +839,26 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::create(
-				from,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				is_transactional,
-				validate,
-				weight_limit,
-				proof_size_base_cost,
-				evm_config,
-			).map_err(|err| err.error.into())
+			let (info, accessed) = pallet_evm::Pallet::<Runtime>::access_list(|| {
+				pallet_evm::runner::stack::with_block_overrides(block_overrides.unwrap_or_default(), || {
+					<Runtime as pallet_evm::Config>::Runner::create(
+						from,
+						data,
+						value,
+						gas_limit.unique_saturated_into(),
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list.unwrap_or_default(),
+						is_transactional,
+						validate,
+						weight_limit,
+						proof_size_base_cost,
+						evm_config,
+					)
+				})
+			});
+			info.map(|info| (info, accessed)).map_err(|err| err.error.into())
 		}
 
 		fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
@@ -875,6 +916,148 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl fp_rpc::DebugRuntimeApi<Block> for Runtime {
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &EthereumTransaction,
+			tracer: TracerConfig,
+		) -> Result<TraceResult, sp_runtime::DispatchError> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				for ext in extrinsics.into_iter() {
+					match &ext.0.function {
+						RuntimeCall::Ethereum(transact { transaction: t }) if t == transaction => {
+							return Ethereum::trace_transaction(transaction, tracer);
+						}
+						_ => {
+							let _ = Executive::apply_extrinsic(ext);
+						}
+					}
+				}
+
+				Err(sp_runtime::DispatchError::Other("transaction not found in block"))
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			{
+				let _ = (extrinsics, transaction, tracer);
+				Err(sp_runtime::DispatchError::Other(
+					"Missing `evm-tracing` compile time feature flag",
+				))
+			}
+		}
+
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			tracer: TracerConfig,
+		) -> Result<TraceResult, sp_runtime::DispatchError> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				let is_transactional = false;
+				let validate = true;
+				let evm_config = <Runtime as pallet_evm::Config>::config();
+				let gas_limit = gas_limit.min(u64::MAX.into()).low_u64();
+
+				let execute = || -> Result<U256, sp_runtime::DispatchError> {
+					match to {
+						Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+							from,
+							to,
+							data.clone(),
+							value,
+							gas_limit,
+							max_fee_per_gas,
+							max_priority_fee_per_gas,
+							nonce,
+							access_list.clone().unwrap_or_default(),
+							is_transactional,
+							validate,
+							None,
+							None,
+							evm_config,
+						)
+						.map(|info| info.used_gas.standard)
+						.map_err(|err| err.error.into()),
+						None => <Runtime as pallet_evm::Config>::Runner::create(
+							from,
+							data.clone(),
+							value,
+							gas_limit,
+							max_fee_per_gas,
+							max_priority_fee_per_gas,
+							nonce,
+							access_list.clone().unwrap_or_default(),
+							is_transactional,
+							validate,
+							None,
+							None,
+							evm_config,
+						)
+						.map(|info| info.used_gas.standard)
+						.map_err(|err| err.error.into()),
+					}
+				};
+
+				if let TracerConfig::PrestateTracer { diff_mode } = tracer {
+					let (used_gas, trace) = EVM::trace_prestate(diff_mode, execute);
+					used_gas?;
+					return Ok(TraceResult::Prestate(trace));
+				}
+
+				let mut tracer = fp_evm::tracing::Tracer::new(tracer);
+				let used_gas = tracer.trace(execute);
+
+				Ok(tracer.into_result(used_gas?))
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			{
+				let _ = (from, to, data, value, gas_limit, max_fee_per_gas, max_priority_fee_per_gas, nonce, access_list, tracer);
+				Err(sp_runtime::DispatchError::Other(
+					"Missing `evm-tracing` compile time feature flag",
+				))
+			}
+		}
+
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			tracer: TracerConfig,
+		) -> Result<
+			Vec<Result<TraceResult, sp_runtime::DispatchError>>,
+			sp_runtime::DispatchError,
+		> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				let mut traces = Vec::new();
+				for ext in extrinsics.into_iter() {
+					match &ext.0.function {
+						RuntimeCall::Ethereum(transact { transaction }) => {
+							traces.push(Ethereum::trace_transaction(transaction, tracer.clone()));
+						}
+						_ => {
+							let _ = Executive::apply_extrinsic(ext);
+						}
+					}
+				}
+
+				Ok(traces)
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			{
+				let _ = (extrinsics, tracer);
+				Err(sp_runtime::DispatchError::Other(
+					"Missing `evm-tracing` compile time feature flag",
+				))
+			}
+		}
+	}
+
 	impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
 		fn convert_transaction(transaction: EthereumTransaction) -> <Block as BlockT>::Extrinsic {
 			UncheckedExtrinsic::new_unsigned(
@@ -1000,7 +1183,7 @@ impl_runtime_apis! {
 
 #[cfg(test)]
 mod tests {
-	use super::{Runtime, WeightPerGas};
+	use super::{scheduled_evm_config, BlockOverrides, Runtime, ShanghaiBlock, WeightPerGas};
 	#[test]
 	fn configured_base_extrinsic_weight_is_evm_compatible() {
 		let min_ethereum_transaction_weight = WeightPerGas::get() * 21_000;
@@ -1009,4 +1192,30 @@ mod tests {
 			.base_extrinsic;
 		assert!(base_extrinsic.ref_time() <= min_ethereum_transaction_weight.ref_time());
 	}
+
+	#[test]
+	fn evm_config_follows_the_fork_schedule() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let overridden = |number: u32| {
+				Some(BlockOverrides {
+					number: Some(number.into()),
+					..Default::default()
+				})
+			};
+
+			// Shanghai from genesis by default.
+			assert!(<Runtime as pallet_evm::Config>::config().has_push0);
+
+			ShanghaiBlock::set(&10);
+			frame_system::Pallet::<Runtime>::set_block_number(20);
+			assert!(<Runtime as pallet_evm::Config>::config().has_push0);
+			// Calls at a past block follow the rules in force at that block.
+			assert!(!scheduled_evm_config(&overridden(9)).has_push0);
+			assert!(scheduled_evm_config(&overridden(10)).has_push0);
+
+			frame_system::Pallet::<Runtime>::set_block_number(9);
+			assert!(!<Runtime as pallet_evm::Config>::config().has_push0);
+			assert!(!scheduled_evm_config(&None).has_push0);
+		});
+	}
 }
@@ -1,15 +1,46 @@
+use frame_support::parameter_types;
 use pallet_evm::{
 	IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
 };
 use sp_core::H160;
 use sp_std::marker::PhantomData;
 
+use pallet_evm_precompile_assets_erc20::Erc20AssetsPrecompileSet;
+use pallet_evm_precompile_balances_erc20::{Erc20BalancesPrecompile, Erc20Metadata};
+use pallet_evm_precompile_batch::BatchPrecompile;
+use pallet_evm_precompile_call_permit::CallPermitPrecompile;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_secp256r1::P256Verify;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 
 pub struct FrontierPrecompiles<R>(PhantomData<R>);
 
+parameter_types! {
+	/// Prefix of the addresses at which `pallet-assets` assets are exposed as ERC-20 tokens.
+	pub const AssetPrecompilePrefix: [u8; 4] = [0xff; 4];
+}
+
+type AssetsPrecompiles<R> =
+	Erc20AssetsPrecompileSet<R, pallet_assets::Pallet<R>, AssetPrecompilePrefix>;
+
+/// Metadata of the native currency exposed as an ERC-20 token.
+pub struct NativeErc20Metadata;
+
+impl Erc20Metadata for NativeErc20Metadata {
+	fn name() -> &'static str {
+		"Unit"
+	}
+
+	fn symbol() -> &'static str {
+		"UNIT"
+	}
+
+	fn decimals() -> u8 {
+		18
+	}
+}
+
 impl<R> FrontierPrecompiles<R>
 where
 	R: pallet_evm::Config,
@@ -17,21 +48,26 @@ where
 	pub fn new() -> Self {
 		Self(Default::default())
 	}
-	pub fn used_addresses() -> [H160; 7] {
+	pub fn used_addresses() -> [H160; 11] {
 		[
 			hash(1),
 			hash(2),
 			hash(3),
 			hash(4),
 			hash(5),
+			hash(256),
 			hash(1024),
 			hash(1025),
+			hash(2050),
+			hash(2056),
+			hash(2058),
 		]
 	}
 }
 impl<R> PrecompileSet for FrontierPrecompiles<R>
 where
-	R: pallet_evm::Config,
+	R: pallet_evm::Config + pallet_assets::Config,
+	R::AssetId: From<u128> + Into<u128>,
 {
 	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
 		match handle.code_address() {
@@ -41,18 +77,29 @@ where
 			a if a == hash(3) => Some(Ripemd160::execute(handle)),
 			a if a == hash(4) => Some(Identity::execute(handle)),
 			a if a == hash(5) => Some(Modexp::execute(handle)),
+			// RIP precompiles :
+			a if a == hash(256) => Some(P256Verify::execute(handle)),
 			// Non-Frontier specific nor Ethereum precompiles :
 			a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
 			a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
-			_ => None,
+			a if a == hash(2050) => Some(
+				Erc20BalancesPrecompile::<R, NativeErc20Metadata>::execute(handle),
+			),
+			a if a == hash(2056) => Some(BatchPrecompile::execute(handle)),
+			a if a == hash(2058) => Some(CallPermitPrecompile::<R>::execute(handle)),
+			// ERC-20 view of `pallet-assets` assets :
+			_ => AssetsPrecompiles::<R>::new().execute(handle),
 		}
 	}
 
-	fn is_precompile(&self, address: H160, _gas: u64) -> IsPrecompileResult {
-		IsPrecompileResult::Answer {
-			is_precompile: Self::used_addresses().contains(&address),
-			extra_cost: 0,
+	fn is_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+		if Self::used_addresses().contains(&address) {
+			return IsPrecompileResult::Answer {
+				is_precompile: true,
+				extra_cost: 0,
+			};
 		}
+		AssetsPrecompiles::<R>::new().is_precompile(address, gas)
 	}
 }
 
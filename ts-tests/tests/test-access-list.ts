@@ -0,0 +1,79 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+import Storage from "../build/contracts/Storage.json";
+
+describeWithFrontier("Frontier RPC (Access list)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const KEY = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+	let contractAddress: string;
+
+	before("create the contract", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: STORAGE_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		contractAddress = (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+	});
+
+	step("eth_createAccessList should list the accessed storage", async function () {
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI, contractAddress);
+		const result = (
+			await customRequest(context.web3, "eth_createAccessList", [
+				{
+					from: GENESIS_ACCOUNT,
+					to: contractAddress,
+					data: contract.methods.getStorage(KEY).encodeABI(),
+				},
+			])
+		).result;
+
+		expect(result.accessList).to.deep.equal([
+			{
+				address: contractAddress.toLowerCase(),
+				storageKeys: [KEY],
+			},
+		]);
+		expect(Number(result.gasUsed)).to.be.greaterThan(21000);
+	});
+
+	step("eth_createAccessList should return an empty list for a transfer", async function () {
+		const result = (
+			await customRequest(context.web3, "eth_createAccessList", [
+				{
+					from: GENESIS_ACCOUNT,
+					to: "0x1111111111111111111111111111111111111111",
+					value: "0x1",
+				},
+			])
+		).result;
+
+		expect(result.accessList).to.be.empty;
+	});
+
+	step("eth_createAccessList should fail when the execution fails", async function () {
+		const result = await customRequest(context.web3, "eth_createAccessList", [
+			{
+				from: GENESIS_ACCOUNT,
+				data: "0xfe",
+			},
+		]);
+
+		expect(result.error.message).to.contain("evm error");
+	});
+});
@@ -0,0 +1,166 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontierTracing } from "./util";
+
+import Storage from "../build/contracts/Storage.json";
+
+describeWithFrontierTracing("Frontier RPC (debug trace)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const KEY = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+	const VALUE = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+	let contractAddress: string;
+	let transactionHash: string;
+
+	before("create the contract and set its storage", async function () {
+		this.timeout(15000);
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI);
+
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: STORAGE_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		contractAddress = (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+
+		const tx1 = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: contractAddress,
+				data: contract.methods.setStorage(KEY, VALUE).encodeABI(),
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx1.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		transactionHash = tx1.transactionHash;
+	});
+
+	step("debug_traceTransaction should return struct logs", async function () {
+		const receipt = await context.web3.eth.getTransactionReceipt(transactionHash);
+		const trace = (await customRequest(context.web3, "debug_traceTransaction", [transactionHash])).result;
+
+		expect(trace.failed).to.be.false;
+		expect(trace.gas).to.equal(receipt.gasUsed);
+		expect(trace.structLogs[0]).to.include({ pc: 0, op: "PUSH1", depth: 1 });
+
+		const sstore = trace.structLogs.find((log) => log.op === "SSTORE");
+		expect(sstore).to.not.be.undefined;
+		expect(sstore.storage[KEY.slice(2)]).to.equal(VALUE.slice(2));
+		expect(sstore.memory).to.be.undefined;
+	});
+
+	step("debug_traceTransaction should honor struct logger options", async function () {
+		const trace = (
+			await customRequest(context.web3, "debug_traceTransaction", [
+				transactionHash,
+				{ disableStack: true, disableStorage: true, enableMemory: true },
+			])
+		).result;
+
+		const log = trace.structLogs[trace.structLogs.length - 1];
+		expect(log.stack).to.be.undefined;
+		expect(log.storage).to.be.undefined;
+		expect(log.memory).to.be.an("array");
+	});
+
+	step("debug_traceTransaction should support the callTracer", async function () {
+		const receipt = await context.web3.eth.getTransactionReceipt(transactionHash);
+		const trace = (
+			await customRequest(context.web3, "debug_traceTransaction", [transactionHash, { tracer: "callTracer" }])
+		).result;
+
+		expect(trace.type).to.equal("CALL");
+		expect(trace.from).to.equal(GENESIS_ACCOUNT.toLowerCase());
+		expect(trace.to).to.equal(contractAddress.toLowerCase());
+		expect(context.web3.utils.hexToNumber(trace.gasUsed)).to.equal(receipt.gasUsed);
+		expect(trace.calls).to.be.undefined;
+	});
+
+	step("debug_traceTransaction should reject unknown tracers", async function () {
+		const response = await customRequest(context.web3, "debug_traceTransaction", [
+			transactionHash,
+			{ tracer: "unknownTracer" },
+		]);
+		expect(response.error.message).to.equal("tracer unknownTracer is not supported");
+	});
+
+	step("debug_traceBlockByNumber should trace the block transactions", async function () {
+		const receipt = await context.web3.eth.getTransactionReceipt(transactionHash);
+		const traces = (
+			await customRequest(context.web3, "debug_traceBlockByNumber", [
+				context.web3.utils.numberToHex(receipt.blockNumber),
+				{ tracer: "callTracer" },
+			])
+		).result;
+
+		expect(traces).to.have.lengthOf(1);
+		expect(traces[0].txHash).to.equal(transactionHash);
+		expect(traces[0].result.to).to.equal(contractAddress.toLowerCase());
+	});
+
+	step("debug_traceBlockByHash should trace the block transactions", async function () {
+		const receipt = await context.web3.eth.getTransactionReceipt(transactionHash);
+		const traces = (await customRequest(context.web3, "debug_traceBlockByHash", [receipt.blockHash])).result;
+
+		expect(traces).to.have.lengthOf(1);
+		expect(traces[0].txHash).to.equal(transactionHash);
+		expect(traces[0].result.gas).to.equal(receipt.gasUsed);
+	});
+
+	step("debug_traceTransaction should support the prestateTracer", async function () {
+		const trace = (
+			await customRequest(context.web3, "debug_traceTransaction", [transactionHash, { tracer: "prestateTracer" }])
+		).result;
+
+		const contract = trace[contractAddress.toLowerCase()];
+		expect(contract.code).to.not.be.undefined;
+		expect(contract.storage[KEY]).to.equal("0x" + "00".repeat(32));
+		expect(trace[GENESIS_ACCOUNT.toLowerCase()].balance).to.not.be.undefined;
+	});
+
+	step("debug_traceTransaction should support the prestateTracer diff mode", async function () {
+		const trace = (
+			await customRequest(context.web3, "debug_traceTransaction", [
+				transactionHash,
+				{ tracer: "prestateTracer", tracerConfig: { diffMode: true } },
+			])
+		).result;
+
+		const sender = GENESIS_ACCOUNT.toLowerCase();
+		expect(trace.post[sender].nonce).to.equal(trace.pre[sender].nonce + 1);
+		expect(trace.post[contractAddress.toLowerCase()].storage).to.deep.equal({ [KEY]: VALUE });
+	});
+
+	step("debug_traceCall should trace a call", async function () {
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI, contractAddress);
+		const trace = (
+			await customRequest(context.web3, "debug_traceCall", [
+				{
+					from: GENESIS_ACCOUNT,
+					to: contractAddress,
+					data: contract.methods.getStorage(KEY).encodeABI(),
+				},
+				"latest",
+				{ tracer: "callTracer" },
+			])
+		).result;
+
+		expect(trace.type).to.equal("CALL");
+		expect(trace.output).to.equal(VALUE);
+	});
+});
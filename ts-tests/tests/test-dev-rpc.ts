@@ -0,0 +1,138 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { BLOCK_TIMESTAMP } from "./config";
+import { customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (dev namespace)", (context) => {
+	const ADDRESS = "0x1111111111111111111111111111111111111111";
+	const SLOT = "0x0000000000000000000000000000000000000000000000000000000000000001";
+	const VALUE = "0x0000000000000000000000000000000000000000000000000000000000000042";
+
+	async function waitForMappingSync() {
+		await new Promise<void>((resolve) => setTimeout(() => resolve(), 500));
+	}
+
+	step("evm_mine should seal the requested number of blocks", async function () {
+		const before = await context.web3.eth.getBlockNumber();
+		expect((await customRequest(context.web3, "evm_mine", [3])).result).to.equal("0x0");
+		await waitForMappingSync();
+		expect(await context.web3.eth.getBlockNumber()).to.equal(before + 3);
+	});
+
+	step("evm_increaseTime should move the next block timestamp forward", async function () {
+		const previous = (await context.web3.eth.getBlock("latest")).timestamp as number;
+		expect((await customRequest(context.web3, "evm_increaseTime", [100])).result).to.equal(100);
+		await customRequest(context.web3, "evm_mine", []);
+		await waitForMappingSync();
+		const timestamp = (await context.web3.eth.getBlock("latest")).timestamp as number;
+		expect(timestamp).to.equal(previous + BLOCK_TIMESTAMP + 100);
+	});
+
+	step("evm_setNextBlockTimestamp should set the next block timestamp", async function () {
+		const previous = (await context.web3.eth.getBlock("latest")).timestamp as number;
+		await customRequest(context.web3, "evm_setNextBlockTimestamp", [previous + 1000]);
+		await customRequest(context.web3, "evm_mine", []);
+		await waitForMappingSync();
+		expect((await context.web3.eth.getBlock("latest")).timestamp).to.equal(previous + 1000);
+
+		const error = (await customRequest(context.web3, "evm_setNextBlockTimestamp", [previous])).error;
+		expect(error.message).to.contain("is not after the latest block timestamp");
+	});
+
+	step("anvil_set* should rewrite the account state", async function () {
+		await customRequest(context.web3, "anvil_setBalance", [ADDRESS, "0x3e8"]);
+		await customRequest(context.web3, "anvil_setNonce", [ADDRESS, "0x7"]);
+		await customRequest(context.web3, "anvil_setCode", [ADDRESS, "0x6000"]);
+		expect((await customRequest(context.web3, "anvil_setStorageAt", [ADDRESS, "0x1", VALUE])).result).to.be
+			.true;
+		await waitForMappingSync();
+
+		expect(await context.web3.eth.getBalance(ADDRESS)).to.equal("1000");
+		expect(await context.web3.eth.getTransactionCount(ADDRESS)).to.equal(7);
+		expect(await context.web3.eth.getCode(ADDRESS)).to.equal("0x6000");
+		expect(await context.web3.eth.getStorageAt(ADDRESS, SLOT)).to.equal(VALUE);
+	});
+
+	step("anvil_impersonateAccount should let eth_sendTransaction use the account", async function () {
+		const WHALE = "0x2222222222222222222222222222222222222222";
+		const RECIPIENT = "0x3333333333333333333333333333333333333333";
+		await customRequest(context.web3, "anvil_setBalance", [WHALE, "0x56bc75e2d63100000"]);
+
+		const rejected = await customRequest(context.web3, "eth_sendTransaction", [
+			{ from: WHALE, to: RECIPIENT, value: "0x1", gas: "0x5208" },
+		]);
+		expect(rejected.error.message).to.equal("no signer available");
+
+		await customRequest(context.web3, "anvil_impersonateAccount", [WHALE]);
+		const hash = (
+			await customRequest(context.web3, "eth_sendTransaction", [
+				{ from: WHALE, to: RECIPIENT, value: "0x1", gas: "0x5208" },
+			])
+		).result;
+		await customRequest(context.web3, "evm_mine", []);
+		await waitForMappingSync();
+
+		const receipt = await context.web3.eth.getTransactionReceipt(hash);
+		expect(receipt.status).to.be.true;
+		expect(receipt.from).to.equal(WHALE);
+		expect(await context.web3.eth.getBalance(RECIPIENT)).to.equal("1");
+
+		await customRequest(context.web3, "anvil_stopImpersonatingAccount", [WHALE]);
+		const stopped = await customRequest(context.web3, "eth_sendTransaction", [
+			{ from: WHALE, to: RECIPIENT, value: "0x1", gas: "0x5208" },
+		]);
+		expect(stopped.error.message).to.equal("no signer available");
+	});
+
+	step("evm_revert should drop the blocks sealed after the snapshot", async function () {
+		const snapshot = (await customRequest(context.web3, "evm_snapshot", [])).result;
+		const before = await context.web3.eth.getBlockNumber();
+
+		await customRequest(context.web3, "anvil_setBalance", [ADDRESS, "0x0"]);
+		await customRequest(context.web3, "evm_mine", [2]);
+		await waitForMappingSync();
+		expect(await context.web3.eth.getBlockNumber()).to.equal(before + 3);
+
+		expect((await customRequest(context.web3, "evm_revert", [snapshot])).result).to.be.true;
+		await waitForMappingSync();
+		expect(await context.web3.eth.getBlockNumber()).to.equal(before);
+		expect(await context.web3.eth.getBalance(ADDRESS)).to.equal("1000");
+
+		// A snapshot can only be reverted to once.
+		expect((await customRequest(context.web3, "evm_revert", [snapshot])).result).to.be.false;
+	});
+
+	step("evm_revert should drop the reverted transactions from the frontier db", async function () {
+		const WHALE = "0x2222222222222222222222222222222222222222";
+		const RECIPIENT = "0x4444444444444444444444444444444444444444";
+		const snapshot = (await customRequest(context.web3, "evm_snapshot", [])).result;
+		const before = await context.web3.eth.getBlockNumber();
+
+		await customRequest(context.web3, "anvil_impersonateAccount", [WHALE]);
+		const hash = (
+			await customRequest(context.web3, "eth_sendTransaction", [
+				{ from: WHALE, to: RECIPIENT, value: "0x1", gas: "0x5208" },
+			])
+		).result;
+		await customRequest(context.web3, "anvil_stopImpersonatingAccount", [WHALE]);
+		await customRequest(context.web3, "evm_mine", []);
+		await waitForMappingSync();
+		expect(await context.web3.eth.getTransactionReceipt(hash)).to.not.be.null;
+
+		expect((await customRequest(context.web3, "evm_revert", [snapshot])).result).to.be.true;
+		await waitForMappingSync();
+		expect(await context.web3.eth.getTransactionReceipt(hash)).to.be.null;
+		expect(await context.web3.eth.getBalance(RECIPIENT)).to.equal("0");
+
+		// The blocks sealed after the revert are synced.
+		await customRequest(context.web3, "evm_mine", []);
+		await waitForMappingSync();
+		expect((await context.web3.eth.getBlock(before + 1)).number).to.equal(before + 1);
+	});
+
+	step("evm_mine should reject too many blocks", async function () {
+		const error = (await customRequest(context.web3, "evm_mine", [1001])).error;
+		expect(error.message).to.equal("too many blocks to mine (up to 1000 are allowed)");
+	});
+});
@@ -0,0 +1,69 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+import Storage from "../build/contracts/Storage.json";
+
+describeWithFrontier("Frontier RPC (eth_getProof)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const KEY = "0x0000000000000000000000000000000000000000000000000000000000000001";
+	const VALUE = "0x0000000000000000000000000000000000000000000000000000000000000042";
+
+	let contractAddress: string;
+
+	before("create the contract and set a slot", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: STORAGE_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		contractAddress = (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI, contractAddress);
+		const setTx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: contractAddress,
+				data: contract.methods.setStorage(KEY, VALUE).encodeABI(),
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [setTx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+	});
+
+	step("eth_getProof should prove the contract storage", async function () {
+		const proof = (await customRequest(context.web3, "eth_getProof", [contractAddress, [KEY], "latest"]))
+			.result;
+		const code = await context.web3.eth.getCode(contractAddress);
+
+		expect(proof.address).to.equal(contractAddress.toLowerCase());
+		expect(proof.codeHash).to.equal(context.web3.utils.keccak256(code));
+		expect(proof.accountProof).to.not.be.empty;
+		expect(proof.storageProof).to.have.lengthOf(1);
+		expect(proof.storageProof[0].value).to.equal("0x42");
+		expect(proof.storageProof[0].proof).to.not.be.empty;
+	});
+
+	step("eth_getProof should prove the account balance and nonce", async function () {
+		const proof = (await customRequest(context.web3, "eth_getProof", [GENESIS_ACCOUNT, [], "latest"])).result;
+
+		expect(proof.balance).to.equal(context.web3.utils.numberToHex(await context.web3.eth.getBalance(GENESIS_ACCOUNT)));
+		expect(proof.nonce).to.equal("0x2");
+		expect(proof.accountProof).to.not.be.empty;
+	});
+});
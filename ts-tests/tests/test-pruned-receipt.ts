@@ -0,0 +1,64 @@
+import { ethers } from "ethers";
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY, CHAIN_ID } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+const STATE_PRUNING = 4;
+
+describeWithFrontier(
+	"Frontier RPC (Pruned Receipt)",
+	(context) => {
+		const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+		let txHash: string;
+		let blockNumber: string;
+		let receipt: any;
+		let baseFeePerGas: string;
+
+		step("should return the receipt of an EIP-1559 transaction", async function () {
+			this.timeout(15000);
+
+			const signer = new ethers.Wallet(GENESIS_ACCOUNT_PRIVATE_KEY, (context as any).ethersjs);
+			const tx = await signer.sendTransaction({
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200",
+				maxFeePerGas: "0x3B9ACA00",
+				maxPriorityFeePerGas: "0x01",
+				type: 2,
+				nonce: 0,
+				gasLimit: "0x100000",
+				chainId: CHAIN_ID,
+			});
+			txHash = tx.hash;
+			await createAndFinalizeBlock(context.web3);
+
+			receipt = (await customRequest(context.web3, "eth_getTransactionReceipt", [txHash])).result;
+			expect(receipt.transactionHash).to.be.eq(txHash);
+			expect(receipt.type).to.be.eq("0x2");
+			blockNumber = receipt.blockNumber;
+			const block = (await customRequest(context.web3, "eth_getBlockByNumber", [blockNumber, false])).result;
+			baseFeePerGas = block.baseFeePerGas;
+			expect(baseFeePerGas).to.not.be.undefined;
+		});
+
+		step("should return the same receipt once the state of the block is pruned", async function () {
+			this.timeout(30000);
+
+			for (let i = 0; i < STATE_PRUNING + 2; i++) {
+				await createAndFinalizeBlock(context.web3);
+			}
+			const balance = await customRequest(context.web3, "eth_getBalance", [TEST_ACCOUNT, blockNumber]);
+			expect(balance.error).to.not.be.undefined;
+
+			const prunedReceipt = (await customRequest(context.web3, "eth_getTransactionReceipt", [txHash])).result;
+			expect(prunedReceipt).to.be.deep.eq(receipt);
+			const block = (await customRequest(context.web3, "eth_getBlockByNumber", [blockNumber, false])).result;
+			expect(block.baseFeePerGas).to.be.eq(baseFeePerGas);
+		});
+	},
+	"http",
+	[`--state-pruning=${STATE_PRUNING}`, `--frontier-store-block-data`]
+);
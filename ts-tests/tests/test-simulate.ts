@@ -0,0 +1,142 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+import Storage from "../build/contracts/Storage.json";
+import ExplicitRevertReason from "../build/contracts/ExplicitRevertReason.json";
+
+describeWithFrontier("Frontier RPC (eth_simulateV1)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const REVERT_CONTRACT_BYTECODE = ExplicitRevertReason.bytecode;
+	const REVERT_CONTRACT_ABI = ExplicitRevertReason.abi as AbiItem[];
+	const KEY = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+	const VALUE = "0x0000000000000000000000000000000000000000000000000000000000000042";
+
+	let storageAddress: string;
+	let revertAddress: string;
+
+	async function deploy(bytecode: string, nonce: number): Promise<string> {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: bytecode,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		return (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+	}
+
+	before("create the contracts", async function () {
+		this.timeout(15000);
+		storageAddress = await deploy(STORAGE_CONTRACT_BYTECODE, 0);
+		revertAddress = await deploy(REVERT_CONTRACT_BYTECODE, 1);
+	});
+
+	step("eth_simulateV1 should chain the state between calls and blocks", async function () {
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI, storageAddress);
+		const result = (
+			await customRequest(context.web3, "eth_simulateV1", [
+				{
+					blockStateCalls: [
+						{
+							calls: [
+								{
+									from: GENESIS_ACCOUNT,
+									to: storageAddress,
+									data: contract.methods.setStorage(KEY, VALUE).encodeABI(),
+								},
+							],
+						},
+						{
+							calls: [
+								{
+									from: GENESIS_ACCOUNT,
+									to: storageAddress,
+									data: contract.methods.getStorage(KEY).encodeABI(),
+								},
+							],
+						},
+					],
+				},
+				"latest",
+			])
+		).result;
+
+		const latest = await context.web3.eth.getBlock("latest");
+		expect(result).to.have.lengthOf(2);
+		expect(Number(result[0].number)).to.equal(latest.number + 1);
+		expect(Number(result[1].number)).to.equal(latest.number + 2);
+		expect(result[1].parentHash).to.equal(result[0].hash);
+		expect(result[0].calls[0].status).to.equal("0x1");
+		expect(result[1].calls[0].returnData).to.equal(VALUE);
+		// Simulations must not touch the chain state.
+		expect(await contract.methods.getStorage(KEY).call()).to.not.equal(VALUE);
+	});
+
+	step("eth_simulateV1 should apply the block overrides", async function () {
+		const result = (
+			await customRequest(context.web3, "eth_simulateV1", [
+				{
+					blockStateCalls: [
+						{
+							blockOverrides: { number: "0x100", time: "0x10000000" },
+							calls: [],
+						},
+					],
+				},
+				"latest",
+			])
+		).result;
+
+		expect(result[0].number).to.equal("0x100");
+		expect(result[0].timestamp).to.equal("0x10000000");
+	});
+
+	step("eth_simulateV1 should report reverted calls", async function () {
+		const contract = new context.web3.eth.Contract(REVERT_CONTRACT_ABI, revertAddress);
+		const result = (
+			await customRequest(context.web3, "eth_simulateV1", [
+				{
+					blockStateCalls: [
+						{
+							calls: [
+								{
+									from: GENESIS_ACCOUNT,
+									to: revertAddress,
+									data: contract.methods.max10(30).encodeABI(),
+								},
+							],
+						},
+					],
+				},
+				"latest",
+			])
+		).result;
+
+		const call = result[0].calls[0];
+		expect(call.status).to.equal("0x0");
+		expect(call.error.code).to.equal(3);
+		expect(call.error.message).to.contain("Value must not be greater than 10.");
+	});
+
+	step("eth_simulateV1 should reject decreasing block numbers", async function () {
+		const result = await customRequest(context.web3, "eth_simulateV1", [
+			{
+				blockStateCalls: [{ blockOverrides: { number: "0x1" } }],
+			},
+			"latest",
+		]);
+
+		expect(result.error.message).to.equal("simulated block numbers must be increasing");
+	});
+});
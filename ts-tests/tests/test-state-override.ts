@@ -243,4 +243,64 @@ describeWithFrontier("Frontier RPC (StateOverride)", (context) => {
 		]);
 		expect(Web3.utils.hexToNumberString(result)).to.equal("35");
 	});
+
+	it("should estimate gas with state override", async function () {
+		const testContract = new context.web3.eth.Contract(Test.abi as AbiItem[]);
+		const request = {
+			from: GENESIS_ACCOUNT,
+			to: contractAddress,
+			data: testContract.methods.multiply(5).encodeABI(),
+		};
+
+		const { error } = await customRequest(context.web3, "eth_estimateGas", [request]);
+		expect(error.message).to.equal("VM Exception while processing transaction: revert");
+
+		const { result } = await customRequest(context.web3, "eth_estimateGas", [
+			request,
+			"latest",
+			{
+				[contractAddress]: {
+					code: Test.deployedBytecode,
+				},
+			},
+		]);
+		expect(Web3.utils.hexToNumber(result)).to.be.greaterThan(21000);
+	});
+
+	it("should read the block number and gas limit from block overrides", async function () {
+		const testContract = new context.web3.eth.Contract(Test.abi as AbiItem[]);
+		const stateOverrides = {
+			[contractAddress]: {
+				code: Test.deployedBytecode,
+			},
+		};
+		const blockOverrides = {
+			number: Web3.utils.numberToHex(1000),
+			gasLimit: Web3.utils.numberToHex(100_000_000),
+		};
+
+		const { result: number } = await customRequest(context.web3, "eth_call", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: contractAddress,
+				data: testContract.methods.currentBlock().encodeABI(),
+			},
+			"latest",
+			stateOverrides,
+			blockOverrides,
+		]);
+		expect(Web3.utils.hexToNumberString(number)).to.equal("1000");
+
+		const { result: gasLimit } = await customRequest(context.web3, "eth_call", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: contractAddress,
+				data: testContract.methods.gasLimit().encodeABI(),
+			},
+			"latest",
+			stateOverrides,
+			blockOverrides,
+		]);
+		expect(Web3.utils.hexToNumberString(gasLimit)).to.equal("100000000");
+	});
 });
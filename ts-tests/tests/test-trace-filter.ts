@@ -0,0 +1,108 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontierTracing, FRONTIER_BACKEND_TYPE } from "./util";
+
+import Storage from "../build/contracts/Storage.json";
+
+describeWithFrontierTracing("Frontier RPC (trace filter)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const KEY = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+	const VALUE = "0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+	let contractAddress: string;
+	let creationHash: string;
+	let callHash: string;
+
+	before("create the contract and set its storage", async function () {
+		this.timeout(15000);
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI);
+
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: STORAGE_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		creationHash = tx.transactionHash;
+		contractAddress = (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+
+		const tx1 = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: contractAddress,
+				data: contract.methods.setStorage(KEY, VALUE).encodeABI(),
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx1.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		callHash = tx1.transactionHash;
+	});
+
+	if (FRONTIER_BACKEND_TYPE != "sql") {
+		step("trace_filter should require the sql backend", async function () {
+			const response = await customRequest(context.web3, "trace_filter", [{ fromBlock: "0x1" }]);
+			expect(response.error.message).to.equal("traces are only indexed by the SQL backend");
+		});
+		return;
+	}
+
+	step("trace_transaction should return the contract creation", async function () {
+		const traces = (await customRequest(context.web3, "trace_transaction", [creationHash])).result;
+
+		expect(traces).to.have.lengthOf(1);
+		expect(traces[0].type).to.equal("create");
+		expect(traces[0].action.from).to.equal(GENESIS_ACCOUNT.toLowerCase());
+		expect(traces[0].result.address).to.equal(contractAddress.toLowerCase());
+		expect(traces[0].traceAddress).to.deep.equal([]);
+	});
+
+	step("trace_block should return the block traces", async function () {
+		const receipt = await context.web3.eth.getTransactionReceipt(callHash);
+		const traces = (
+			await customRequest(context.web3, "trace_block", [context.web3.utils.numberToHex(receipt.blockNumber)])
+		).result;
+
+		expect(traces).to.have.lengthOf(1);
+		expect(traces[0]).to.include({
+			type: "call",
+			blockHash: receipt.blockHash,
+			blockNumber: receipt.blockNumber,
+			transactionHash: callHash,
+			transactionPosition: 0,
+			subtraces: 0,
+		});
+		expect(traces[0].action.callType).to.equal("call");
+	});
+
+	step("trace_filter should filter by address", async function () {
+		const filter = { fromBlock: "0x1", toBlock: "latest" };
+
+		let traces = (await customRequest(context.web3, "trace_filter", [filter])).result;
+		expect(traces.map((trace) => trace.transactionHash)).to.deep.equal([creationHash, callHash]);
+
+		traces = (await customRequest(context.web3, "trace_filter", [{ ...filter, toAddress: [contractAddress] }]))
+			.result;
+		expect(traces.map((trace) => trace.transactionHash)).to.deep.equal([creationHash, callHash]);
+
+		traces = (await customRequest(context.web3, "trace_filter", [{ ...filter, fromAddress: [contractAddress] }]))
+			.result;
+		expect(traces).to.be.empty;
+
+		traces = (await customRequest(context.web3, "trace_filter", [{ ...filter, after: 1, count: 1 }])).result;
+		expect(traces.map((trace) => trace.transactionHash)).to.deep.equal([callHash]);
+	});
+});
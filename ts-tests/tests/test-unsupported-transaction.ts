@@ -0,0 +1,27 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { describeWithFrontier, customRequest } from "./util";
+
+describeWithFrontier("Frontier RPC (Unsupported transaction types)", (context) => {
+	step("should report EIP-7702 set code transactions as unsupported", async function () {
+		const tx = await customRequest(context.web3, "eth_sendRawTransaction", ["0x04c0"]);
+		expect(tx.error).to.include({
+			message: "EIP-7702 set code transactions are not supported",
+		});
+	});
+
+	step("should report EIP-4844 blob transactions as unsupported", async function () {
+		const tx = await customRequest(context.web3, "eth_sendRawTransaction", ["0x03c0"]);
+		expect(tx.error).to.include({
+			message: "EIP-4844 blob transactions are not supported",
+		});
+	});
+
+	step("should report unknown transaction types as unsupported", async function () {
+		const tx = await customRequest(context.web3, "eth_sendRawTransaction", ["0x05c0"]);
+		expect(tx.error).to.include({
+			message: "transaction type 0x05 is not supported",
+		});
+	});
+});
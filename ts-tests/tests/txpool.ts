@@ -56,4 +56,20 @@ describeWithFrontier("Frontier RPC (TxPoolApi)", (context) => {
 			"0x0000000000000000000000000000000000000000: 0 wei + 1048576 gas x 1000000000 wei"
 		);
 	});
+
+	step("txpool_content should list impersonated transactions under their sender", async function () {
+		const WHALE = "0x2222222222222222222222222222222222222222";
+		await customRequest(context.web3, "anvil_setBalance", [WHALE, "0x56bc75e2d63100000"]);
+		await customRequest(context.web3, "anvil_impersonateAccount", [WHALE]);
+		const hash = (
+			await customRequest(context.web3, "eth_sendTransaction", [
+				{ from: WHALE, to: GENESIS_ACCOUNT, value: "0x1", gas: "0x5208" },
+			])
+		).result;
+		await customRequest(context.web3, "anvil_stopImpersonatingAccount", [WHALE]);
+
+		let txpoolContent = await customRequest(context.web3, "txpool_content", []);
+		expect(txpoolContent.result.pending[WHALE]["0x0"].hash).to.be.equal(hash);
+		expect(txpoolContent.result.pending[WHALE]["0x0"].from).to.be.equal(WHALE);
+	});
 });
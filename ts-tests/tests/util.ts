@@ -12,6 +12,8 @@ export const DISPLAY_LOG = process.env.FRONTIER_LOG || false;
 export const FRONTIER_LOG = process.env.FRONTIER_LOG || "info";
 export const FRONTIER_BUILD = process.env.FRONTIER_BUILD || "release";
 export const FRONTIER_BACKEND_TYPE = process.env.FRONTIER_BACKEND_TYPE || "key-value";
+// Whether the node is built with the `evm-tracing` feature, required by the tracing RPC.
+export const FRONTIER_EVM_TRACING = process.env.FRONTIER_EVM_TRACING != "false";
 
 export const BINARY_PATH = `../target/${FRONTIER_BUILD}/${NODE_BINARY_NAME}`;
 export const SPAWNING_TIME = 60000;
@@ -58,7 +60,7 @@ export async function createAndFinalizeBlockNowait(web3: Web3) {
 	}
 }
 
-export async function startFrontierNode(provider?: string): Promise<{
+export async function startFrontierNode(provider?: string, additionalArgs: string[] = []): Promise<{
 	web3: Web3;
 	binary: ChildProcess;
 	ethersjs: ethers.JsonRpcProvider;
@@ -82,7 +84,10 @@ export async function startFrontierNode(provider?: string): Promise<{
 		`--port=${PORT}`,
 		`--rpc-port=${RPC_PORT}`,
 		`--frontier-backend-type=${FRONTIER_BACKEND_TYPE}`,
+		...(FRONTIER_EVM_TRACING ? [`--enable-evm-tracing`] : []),
+		...(FRONTIER_EVM_TRACING && FRONTIER_BACKEND_TYPE == "sql" ? [`--frontier-sql-backend-index-traces`] : []),
 		`--tmp`,
+		...additionalArgs,
 	];
 	const binary = spawn(cmd, args);
 
@@ -143,7 +148,12 @@ export async function startFrontierNode(provider?: string): Promise<{
 	return { web3, binary, ethersjs };
 }
 
-export function describeWithFrontier(title: string, cb: (context: { web3: Web3 }) => void, provider?: string) {
+export function describeWithFrontier(
+	title: string,
+	cb: (context: { web3: Web3 }) => void,
+	provider?: string,
+	additionalArgs?: string[]
+) {
 	describe(title, () => {
 		let context: {
 			web3: Web3;
@@ -153,7 +163,7 @@ export function describeWithFrontier(title: string, cb: (context: { web3: Web3 }
 		// Making sure the Frontier node has started
 		before("Starting Frontier Test Node", async function () {
 			this.timeout(SPAWNING_TIME);
-			const init = await startFrontierNode(provider);
+			const init = await startFrontierNode(provider, additionalArgs);
 			context.web3 = init.web3;
 			context.ethersjs = init.ethersjs;
 			binary = init.binary;
@@ -168,6 +178,20 @@ export function describeWithFrontier(title: string, cb: (context: { web3: Web3 }
 	});
 }
 
+// Describes tests of the tracing RPC, skipped when the node is built without `evm-tracing`.
+export function describeWithFrontierTracing(
+	title: string,
+	cb: (context: { web3: Web3 }) => void,
+	provider?: string,
+	additionalArgs?: string[]
+) {
+	if (!FRONTIER_EVM_TRACING) {
+		describe.skip(title, () => {});
+		return;
+	}
+	describeWithFrontier(title, cb, provider, additionalArgs);
+}
+
 export function describeWithFrontierWs(title: string, cb: (context: { web3: Web3 }) => void) {
 	describeWithFrontier(title, cb, "ws");
 }